@@ -0,0 +1,25 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! Audit events emitted by the verification paths.
+//!
+//! Each sub-check performed while verifying a ballot emits a [`tracing`] event at the
+//! [`VERIFICATION_AUDIT_TARGET`] target. Every event has the fields
+//! - `check` - the name of the sub-check, e.g. `"ballot_correctness"`
+//! - `ballot` - the confirmation code of the ballot
+//! - `passed` - whether the sub-check passed
+//!
+//! The events of the sub-checks of a contest also have the field `contest`, the index of the
+//! contest, along with further fields identifying the inputs of the sub-check, such as
+//! `contest_hash` and `option`. A subscriber filtering on this target can
+//! serialize the events to obtain a complete, replayable record of what was checked.
+//!
+//! Verification stops at the first failing sub-check, so the last event of a failed
+//! verification is the one that failed.
+
+/// The `tracing` target of verification audit events.
+pub const VERIFICATION_AUDIT_TARGET: &str = "eg::audit";
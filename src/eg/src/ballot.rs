@@ -5,24 +5,32 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
-use anyhow::Result;
-use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
-use util::{algebra::FieldElement, csprng::Csprng};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+use util::{
+    algebra::{FieldElement, ScalarField},
+    csprng::Csprng,
+};
 
 use crate::{
-    ballot_style::BallotStyleIndex,
+    audit::VERIFICATION_AUDIT_TARGET,
+    ballot_encryption_precompute::BallotEncryptionPrecompute,
+    ballot_style::{BallotStyle, BallotStyleIndex},
     confirmation_code::confirmation_code,
     contest_encrypted::{ContestEncrypted, ScaledContestEncrypted},
     contest_selection::ContestSelection,
     device::Device,
-    election_manifest::{ContestIndex, ElectionManifest},
+    election_manifest::{Contest, ContestIndex, ContestOptionIndex, ElectionManifest},
     election_parameters::ElectionParameters,
     election_record::PreVotingData,
     fixed_parameters::FixedParameters,
     hash::HValue,
     joint_election_public_key::Ciphertext,
-    serializable::SerializablePretty,
+    serializable::{SerializableCanonical, SerializablePretty},
     zk::ProofRangeError,
 };
 use thiserror::Error;
@@ -40,7 +48,18 @@ pub struct BallotEncrypted {
     /// The index of ballot style that this ballot belongs to.
     pub ballot_style_index: BallotStyleIndex,
 
+    /// The [content hash](crate::ballot_style::BallotStyle::content_hash) of the ballot style
+    /// that this ballot was created against. Ballots written before the hash was recorded
+    /// don't have one and still load, but fail verification.
+    #[serde(
+        rename = "ballot_style_hash",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub opt_ballot_style_hash: Option<HValue>,
+
     /// Contests in this ballot
+    #[serde(deserialize_with = "util::serde::deserialize_one_based_index_map")]
     pub contests: BTreeMap<ContestIndex, ContestEncrypted>,
 
     /// Confirmation code
@@ -66,7 +85,25 @@ pub struct ScaledBallotEncrypted {
     pub contests: BTreeMap<ContestIndex, ScaledContestEncrypted>,
 }
 
-#[derive(Error, Debug)]
+/// A scheme for deriving a stable external identifier for a [`BallotEncrypted`], e.g. for
+/// indexing ballots in an external database.
+pub trait BallotIdScheme {
+    /// Returns the identifier of the ballot.
+    fn ballot_id(&self, ballot: &BallotEncrypted) -> String;
+}
+
+/// The default [`BallotIdScheme`]. The ballot id is the hex representation of the ballot's
+/// confirmation code, which is unique to the ballot.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConfirmationCodeBallotIdScheme;
+
+impl BallotIdScheme for ConfirmationCodeBallotIdScheme {
+    fn ballot_id(&self, ballot: &BallotEncrypted) -> String {
+        ballot.confirmation_code.to_string()
+    }
+}
+
+#[derive(Error, Debug, Serialize)]
 pub enum BallotEncryptedError {
     /// Proof production error
     #[error("Error producing ballot proofs: {}", err)]
@@ -75,11 +112,165 @@ pub enum BallotEncryptedError {
     /// Error looking up contest in manifest
     #[error("Contest (index {}) not found in election manifest.", idx)]
     ContestNotInManifest { idx: ContestIndex },
+
+    /// The number of ciphertexts for a contest does not match the election manifest
+    #[error(
+        "Contest (index {}) has {} ciphertexts, but the election manifest implies {}.",
+        idx,
+        actual,
+        expected
+    )]
+    CiphertextCountMismatch {
+        idx: ContestIndex,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// The number of proofs of ballot correctness for a contest does not match its ciphertexts
+    #[error(
+        "Contest (index {}) has {} proofs of ballot correctness, but {} ciphertexts.",
+        idx,
+        actual,
+        expected
+    )]
+    ProofCountMismatch {
+        idx: ContestIndex,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A ciphertext for a contest is not a valid member of the group
+    #[error(
+        "Contest (index {}) has a ciphertext for option {} which is not a valid group element.",
+        idx,
+        option_ix
+    )]
+    CiphertextNotInGroup {
+        idx: ContestIndex,
+        option_ix: ContestOptionIndex,
+    },
+
+    /// Error looking up the ballot style in manifest
+    #[error(
+        "Ballot style (index {}) not found in election manifest.",
+        ballot_style_ix
+    )]
+    BallotStyleNotInManifest { ballot_style_ix: BallotStyleIndex },
+
+    /// The ballot was created against a ballot style other than that in the election manifest
+    #[error(
+        "The ballot style hash of the ballot does not match ballot style (index {}) of the election manifest.",
+        ballot_style_ix
+    )]
+    BallotStyleHashMismatch { ballot_style_ix: BallotStyleIndex },
+
+    /// The ballot does not record the ballot style it was created against
+    #[error(
+        "The ballot does not have a ballot style hash for ballot style (index {}).",
+        ballot_style_ix
+    )]
+    BallotStyleHashMissing { ballot_style_ix: BallotStyleIndex },
+
+    /// The ballot has a contest which is not in its ballot style
+    #[error(
+        "Contest (index {}) is not in ballot style (index {}) of the ballot.",
+        idx,
+        ballot_style_ix
+    )]
+    ContestNotInBallotStyle {
+        idx: ContestIndex,
+        ballot_style_ix: BallotStyleIndex,
+    },
+
+    /// The precompute is for a group generator or joint election public key other than that of
+    /// the pre-voting data
+    #[error("The ballot encryption precompute is not for the joint election public key of the election.")]
+    PrecomputeMismatch,
+}
+
+/// A break in the chain of a batch of ballots, as found by [`verify_ballot_batch_chain`].
+#[derive(Error, Debug, PartialEq, Eq, Serialize)]
+pub enum BallotChainError {
+    /// The first ballot of the batch does not chain from the initial device hash
+    #[error("Ballot {index} of the batch does not chain from the initial device hash.")]
+    NotChainedFromDevice { index: usize },
+
+    /// A ballot of the batch does not chain from its predecessor
+    #[error(
+        "Ballot {index} of the batch does not chain from its predecessor, ballot {predecessor}."
+    )]
+    NotChainedFromPredecessor { index: usize, predecessor: usize },
+}
+
+/// The reason a ballot could not be added to a tally, as found by
+/// [`BallotTallyBuilder::try_update`].
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum TallyError {
+    /// A contest of the ballot is not in the election manifest
+    #[error("Contest (index {idx}) not found in election manifest.")]
+    ContestNotInManifest { idx: ContestIndex },
+
+    /// The number of ciphertexts for a contest does not match the election manifest
+    #[error(
+        "Contest (index {idx}) has {actual} ciphertexts, but the election manifest implies {expected}."
+    )]
+    CiphertextCountMismatch {
+        idx: ContestIndex,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A decrypted tally exceeds the largest tally representable
+    #[error("The decrypted tally {value} exceeds the maximum of {max}.")]
+    TallyOutOfRange { value: String, max: u64 },
+}
+
+/// The largest decrypted tally of a contest option, `2^53 - 1`. This is the largest integer
+/// which a JSON number can represent exactly in common implementations, and far beyond any
+/// realistic count of ballots.
+pub const CONTEST_OPTION_TALLY_MAX: u64 = (1 << 53) - 1;
+
+/// The decrypted tally of the votes for a contest option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ContestOptionTally(u64);
+
+impl ContestOptionTally {
+    /// Converts the plaintext of a decrypted tally to a [`ContestOptionTally`], rejecting a value
+    /// which is not a valid field element or exceeds [`CONTEST_OPTION_TALLY_MAX`].
+    ///
+    /// Every conversion of a decrypted tally should go through this, rather than converting
+    /// the field element to an integer directly.
+    pub fn try_from_field_element(
+        plain_text: &FieldElement,
+        field: &ScalarField,
+    ) -> Result<Self, TallyError> {
+        let out_of_range = || TallyError::TallyOutOfRange {
+            value: plain_text.value().to_string(),
+            max: CONTEST_OPTION_TALLY_MAX,
+        };
+
+        if !plain_text.is_valid(field) {
+            return Err(out_of_range());
+        }
+
+        let tally = u64::try_from(plain_text.value()).map_err(|_| out_of_range())?;
+        if CONTEST_OPTION_TALLY_MAX < tally {
+            return Err(out_of_range());
+        }
+
+        Ok(ContestOptionTally(tally))
+    }
+
+    /// Returns the tally as an integer.
+    pub fn get(self) -> u64 {
+        self.0
+    }
 }
 
 impl BallotEncrypted {
     pub fn new(
         ballot_style_index: BallotStyleIndex,
+        ballot_style_hash: HValue,
         contests: &BTreeMap<ContestIndex, ContestEncrypted>,
         state: BallotState,
         confirmation_code: HValue,
@@ -88,6 +279,7 @@ impl BallotEncrypted {
     ) -> BallotEncrypted {
         BallotEncrypted {
             ballot_style_index,
+            opt_ballot_style_hash: Some(ballot_style_hash),
             contests: contests.clone(),
             state,
             confirmation_code,
@@ -104,6 +296,66 @@ impl BallotEncrypted {
         primary_nonce: &[u8],
         ctest_selections: &BTreeMap<ContestIndex, ContestSelection>,
     ) -> Result<BallotEncrypted, BallotEncryptedError> {
+        Self::new_from_selections_with_opt_precompute(
+            ballot_style_index,
+            device,
+            date,
+            csprng,
+            primary_nonce,
+            ctest_selections,
+            None,
+        )
+    }
+
+    /// Same as [`BallotEncrypted::new_from_selections`], but computes the exponentiations with
+    /// `g` and `K` using the fixed-base tables of the [`BallotEncryptionPrecompute`]. Computing
+    /// the precompute once and reusing it makes encrypting many ballots faster.
+    ///
+    /// The ballot is identical to that from [`BallotEncrypted::new_from_selections`] for the
+    /// same arguments and randomness. Fails if the precompute is not for the pre-voting data of
+    /// the device.
+    pub fn new_from_selections_with_precompute(
+        ballot_style_index: BallotStyleIndex,
+        device: &Device,
+        date: &str,
+        csprng: &mut Csprng,
+        primary_nonce: &[u8],
+        ctest_selections: &BTreeMap<ContestIndex, ContestSelection>,
+        precompute: &BallotEncryptionPrecompute,
+    ) -> Result<BallotEncrypted, BallotEncryptedError> {
+        if !precompute.is_for(&device.header) {
+            return Err(BallotEncryptedError::PrecomputeMismatch);
+        }
+        Self::new_from_selections_with_opt_precompute(
+            ballot_style_index,
+            device,
+            date,
+            csprng,
+            primary_nonce,
+            ctest_selections,
+            Some(precompute),
+        )
+    }
+
+    fn new_from_selections_with_opt_precompute(
+        ballot_style_index: BallotStyleIndex,
+        device: &Device,
+        date: &str,
+        csprng: &mut Csprng,
+        primary_nonce: &[u8],
+        ctest_selections: &BTreeMap<ContestIndex, ContestSelection>,
+        opt_precompute: Option<&BallotEncryptionPrecompute>,
+    ) -> Result<BallotEncrypted, BallotEncryptedError> {
+        let ballot_style_hash = device
+            .header
+            .manifest
+            .ballot_styles
+            .get(ballot_style_index)
+            .ok_or(BallotEncryptedError::BallotStyleNotInManifest {
+                ballot_style_ix: ballot_style_index,
+            })?
+            .content_hash();
+
         let mut contests = BTreeMap::new();
 
         for (&c_idx, selection) in ctest_selections {
@@ -113,9 +365,16 @@ impl BallotEncrypted {
                 .contests
                 .get(c_idx)
                 .ok_or(BallotEncryptedError::ContestNotInManifest { idx: c_idx })?;
-            let contest_encrypted =
-                ContestEncrypted::new(device, csprng, primary_nonce, contest, c_idx, selection)
-                    .map_err(|err| BallotEncryptedError::ProofError { err })?;
+            let contest_encrypted = ContestEncrypted::new_with_opt_precompute(
+                device,
+                csprng,
+                primary_nonce,
+                contest,
+                c_idx,
+                selection,
+                opt_precompute,
+            )
+            .map_err(|err| BallotEncryptedError::ProofError { err })?;
 
             contests.insert(c_idx, contest_encrypted);
         }
@@ -125,6 +384,7 @@ impl BallotEncrypted {
 
         Ok(BallotEncrypted {
             ballot_style_index,
+            opt_ballot_style_hash: Some(ballot_style_hash),
             contests,
             state: BallotState::Uncast,
             confirmation_code,
@@ -149,28 +409,237 @@ impl BallotEncrypted {
         &self.device
     }
 
+    /// Returns the identifier of the ballot according to the given [`BallotIdScheme`].
+    pub fn ballot_id(&self, scheme: &dyn BallotIdScheme) -> String {
+        scheme.ballot_id(self)
+    }
+
+    /// Checks that each contest in the ballot has exactly the number of ciphertexts implied by
+    /// the election manifest (one per option), and one proof of ballot correctness per ciphertext.
+    pub fn validate_ciphertext_counts(
+        &self,
+        election_manifest: &ElectionManifest,
+    ) -> Result<(), BallotEncryptedError> {
+        for (&idx, contest_encrypted) in &self.contests {
+            let contest = election_manifest
+                .contests
+                .get(idx)
+                .ok_or(BallotEncryptedError::ContestNotInManifest { idx })?;
+
+            let expected = contest.options.len();
+            let actual = contest_encrypted.cnt_selections();
+            if actual != expected {
+                return Err(BallotEncryptedError::CiphertextCountMismatch {
+                    idx,
+                    expected,
+                    actual,
+                });
+            }
+
+            let expected = actual;
+            let actual = contest_encrypted.proof_ballot_correctness.len();
+            if actual != expected {
+                return Err(BallotEncryptedError::ProofCountMismatch {
+                    idx,
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the ballot style of the ballot is in the election manifest, and that its
+    /// content hash matches the one recorded on the ballot. This detects ballots created against
+    /// a stale manifest, so a ballot without a recorded hash is rejected.
+    pub fn validate_ballot_style_hash(
+        &self,
+        election_manifest: &ElectionManifest,
+    ) -> Result<(), BallotEncryptedError> {
+        let ballot_style_ix = self.ballot_style_index;
+        let ballot_style = election_manifest
+            .ballot_styles
+            .get(ballot_style_ix)
+            .ok_or(BallotEncryptedError::BallotStyleNotInManifest { ballot_style_ix })?;
+
+        let Some(ballot_style_hash) = self.opt_ballot_style_hash else {
+            return Err(BallotEncryptedError::BallotStyleHashMissing { ballot_style_ix });
+        };
+        if ballot_style.content_hash() != ballot_style_hash {
+            return Err(BallotEncryptedError::BallotStyleHashMismatch { ballot_style_ix });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that every contest of the ballot is in the ballot style of the ballot, so that a
+    /// ballot can't carry contests its voter shouldn't have.
+    pub fn validate_contests_in_ballot_style(
+        &self,
+        election_manifest: &ElectionManifest,
+    ) -> Result<(), BallotEncryptedError> {
+        let ballot_style_ix = self.ballot_style_index;
+        let ballot_style = election_manifest
+            .ballot_styles
+            .get(ballot_style_ix)
+            .ok_or(BallotEncryptedError::BallotStyleNotInManifest { ballot_style_ix })?;
+
+        if let Some(&idx) = self
+            .contests
+            .keys()
+            .find(|idx| !ballot_style.contests.contains(idx))
+        {
+            return Err(BallotEncryptedError::ContestNotInBallotStyle {
+                idx,
+                ballot_style_ix,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that every ciphertext in the ballot is a valid member of the group, see
+    /// [`Ciphertext::is_valid_group_member`].
+    pub fn validate_group_membership(
+        &self,
+        fixed_parameters: &FixedParameters,
+    ) -> Result<(), BallotEncryptedError> {
+        for (&idx, contest_encrypted) in &self.contests {
+            for (option_ix, ct) in contest_encrypted.iter_enumerate_selection() {
+                if !ct.is_valid_group_member(&fixed_parameters.group) {
+                    return Err(BallotEncryptedError::CiphertextNotInGroup { idx, option_ix });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Verify all of the [`ContestEncrypted`] in the [`BallotEncrypted`]. Given
-    /// a ballot style it checks that all contests are voted on in the
-    /// ballot style, and that all of the vote proofs are correct.
+    /// a ballot style it checks that the ballot was created against it, that the ballot has
+    /// exactly the contests of the ballot style, and that all of the vote proofs are correct. Before any proof is
+    /// verified, every ciphertext is checked to be a valid group element.
+    ///
+    /// Each sub-check emits an audit event, see [`crate::audit`].
     pub fn verify(&self, header: &PreVotingData) -> bool {
-        let Some(ballot_style) = header.manifest.ballot_styles.get(self.ballot_style_index) else {
+        let Some(ballot_style) = self.verify_ballot_checks(header) else {
             return false;
         };
+
         for contest_index in &ballot_style.contests {
-            let Some(contest) = header.manifest.contests.get(*contest_index) else {
-                return false;
-            };
-            let Some(contest_encrypted) = self.contests().get(contest_index) else {
+            let Some((contest_encrypted, contest)) =
+                self.verify_contest_present(header, *contest_index)
+            else {
                 return false;
             };
 
-            if !contest_encrypted.verify(header, contest.selection_limit) {
+            if !contest_encrypted.verify(header, &self.confirmation_code, *contest_index, contest) {
                 return false;
             }
         }
         true
     }
 
+    /// Same as [`Self::verify`], but verifies the proofs of the contests concurrently.
+    /// Useful for ballots with many contests.
+    ///
+    /// The result is the same as that of [`Self::verify`], but the audit events differ in order:
+    /// all contests are checked to be present before any proof is verified, and the events of
+    /// the proofs of the contests may be interleaved, and may continue past a failed check.
+    #[cfg(feature = "eg_parallel_verification")]
+    pub fn verify_parallel(&self, header: &PreVotingData) -> bool {
+        use rayon::prelude::*;
+
+        let Some(ballot_style) = self.verify_ballot_checks(header) else {
+            return false;
+        };
+
+        let Some(contests) = ballot_style
+            .contests
+            .iter()
+            .map(|contest_index| {
+                self.verify_contest_present(header, *contest_index).map(
+                    |(contest_encrypted, contest)| (*contest_index, contest_encrypted, contest),
+                )
+            })
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+
+        contests
+            .par_iter()
+            .all(|(contest_index, contest_encrypted, contest)| {
+                contest_encrypted.verify(header, &self.confirmation_code, *contest_index, contest)
+            })
+    }
+
+    /// Performs the checks of [`Self::verify`] which concern the ballot as a whole. If they
+    /// pass, returns the ballot style of the ballot.
+    fn verify_ballot_checks<'a>(&self, header: &'a PreVotingData) -> Option<&'a BallotStyle> {
+        let ballot = &self.confirmation_code;
+
+        let passed = self.validate_ciphertext_counts(header.manifest()).is_ok();
+        tracing::info!(
+            target: VERIFICATION_AUDIT_TARGET,
+            check = "ciphertext_counts",
+            %ballot,
+            passed
+        );
+        if !passed {
+            return None;
+        }
+
+        let passed = self
+            .validate_group_membership(&header.parameters().fixed_parameters)
+            .is_ok();
+        tracing::info!(
+            target: VERIFICATION_AUDIT_TARGET,
+            check = "group_membership",
+            %ballot,
+            passed
+        );
+        if !passed {
+            return None;
+        }
+
+        let passed = self
+            .validate_ballot_style_hash(header.manifest())
+            .and_then(|()| self.validate_contests_in_ballot_style(header.manifest()))
+            .is_ok();
+        tracing::info!(
+            target: VERIFICATION_AUDIT_TARGET,
+            check = "ballot_style",
+            %ballot,
+            ballot_style = %self.ballot_style_index,
+            passed
+        );
+        if !passed {
+            return None;
+        }
+        header.manifest().ballot_styles.get(self.ballot_style_index)
+    }
+
+    /// Checks that the contest is both in the manifest and on the ballot, emitting its audit
+    /// event. If so, returns the contest of the ballot with its definition in the manifest.
+    fn verify_contest_present<'a>(
+        &'a self,
+        header: &'a PreVotingData,
+        contest_index: ContestIndex,
+    ) -> Option<(&'a ContestEncrypted, &'a Contest)> {
+        let opt_contest = header.manifest().contests.get(contest_index);
+        let opt_contest_encrypted = self.contests().get(&contest_index);
+        tracing::info!(
+            target: VERIFICATION_AUDIT_TARGET,
+            check = "contest_present",
+            ballot = %self.confirmation_code,
+            contest = %contest_index,
+            passed = opt_contest.is_some() && opt_contest_encrypted.is_some()
+        );
+        opt_contest_encrypted.zip(opt_contest)
+    }
+
     /// Scale a [`BallotEncrypted`] by a factor, producing a [`ScaledBallotEncrypted`].
     /// Each encrypted vote in the ballot gets scaled by the same factor.
     pub fn scale(
@@ -185,10 +654,52 @@ impl BallotEncrypted {
             .collect();
         ScaledBallotEncrypted { contests }
     }
+
+    /// Gives the [`ScaledBallotEncrypted`] for the factor `1`, e.g., for tallying ballots
+    /// with equal weight. This is equal to [`BallotEncrypted::scale`] with a unit factor,
+    /// without performing any exponentiations.
+    pub fn as_scaled_unit(&self) -> ScaledBallotEncrypted {
+        let contests = self
+            .contests
+            .iter()
+            .map(|(idx, ballot)| (*idx, ballot.as_scaled_unit()))
+            .collect();
+        ScaledBallotEncrypted { contests }
+    }
 }
 
+impl SerializableCanonical for BallotEncrypted {}
+
 impl SerializablePretty for BallotEncrypted {}
 
+/// Verifies that a batch of ballots, in order of submission, forms an intact chain.
+///
+/// With ballot chaining, the auxiliary input `B_aux` of the confirmation code (Equation 59) of
+/// the first ballot is the initial device hash `h_di`, and that of each subsequent ballot is the
+/// confirmation code of its predecessor. Indices in the returned error are zero-based positions
+/// in `ballots`.
+pub fn verify_ballot_batch_chain(
+    header: &PreVotingData,
+    ballots: &[BallotEncrypted],
+    h_di: &HValue,
+) -> Result<(), BallotChainError> {
+    let h_e = header.extended_base_hash();
+    let mut b_aux = h_di;
+    for (index, ballot) in ballots.iter().enumerate() {
+        let expected = confirmation_code(h_e, ballot.contests.values(), b_aux.as_ref());
+        if ballot.confirmation_code != expected {
+            return Err(match index.checked_sub(1) {
+                None => BallotChainError::NotChainedFromDevice { index },
+                Some(predecessor) => {
+                    BallotChainError::NotChainedFromPredecessor { index, predecessor }
+                }
+            });
+        }
+        b_aux = &ballot.confirmation_code;
+    }
+    Ok(())
+}
+
 /// This function takes an iterator over encrypted ballots and tallies up the
 /// votes on each option in each contest. The result is map from `ContestIndex`
 /// to `Vec<Ciphertext>` that given a contest index gives the encrypted result
@@ -209,11 +720,118 @@ pub fn tally_ballots(
     Some(result.finalize())
 }
 
+/// Same as [`tally_ballots`], but also returns [`TallyMetrics`] describing the work performed.
+/// Useful for capacity planning.
+///
+/// The whole tally runs in a `tally_ballots` span, which is closed with the metrics recorded.
+/// Each ballot is checked in a `tally_check` span and added in a `tally_accumulate` span.
+pub fn tally_ballots_timed(
+    encrypted_ballots: impl IntoIterator<Item = ScaledBallotEncrypted>,
+    manifest: &ElectionManifest,
+    parameters: &ElectionParameters,
+) -> Option<(BTreeMap<ContestIndex, Vec<Ciphertext>>, TallyMetrics)> {
+    let span = tracing::info_span!(
+        "tally_ballots",
+        ballots = tracing::field::Empty,
+        contests = tracing::field::Empty,
+        ciphertext_ops = tracing::field::Empty,
+        elapsed_ms = tracing::field::Empty,
+    );
+    let _entered = span.enter();
+
+    let mut result = BallotTallyBuilder::new(manifest, parameters);
+
+    for ballot in encrypted_ballots {
+        if !result.update(ballot) {
+            return None;
+        }
+    }
+    let (tally, metrics) = result.finalize_with_metrics();
+
+    span.record("ballots", metrics.ballots);
+    span.record("contests", metrics.contests);
+    span.record("ciphertext_ops", metrics.ciphertext_ops);
+    span.record("elapsed_ms", metrics.elapsed.as_millis() as u64);
+    Some((tally, metrics))
+}
+
+/// Same as [`tally_ballots`], but instead of failing on the first incompatible ballot, excludes
+/// it from the tally. Also returns the zero-based position in `encrypted_ballots` of each
+/// excluded ballot together with the reason, so that the exclusions can be audited.
+#[allow(clippy::type_complexity)]
+pub fn tally_ballots_collecting_rejections(
+    encrypted_ballots: impl IntoIterator<Item = ScaledBallotEncrypted>,
+    manifest: &ElectionManifest,
+    parameters: &ElectionParameters,
+) -> (
+    BTreeMap<ContestIndex, Vec<Ciphertext>>,
+    Vec<(usize, TallyError)>,
+) {
+    let mut result = BallotTallyBuilder::new(manifest, parameters);
+
+    let rejections = encrypted_ballots
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, ballot)| result.try_update(ballot).err().map(|err| (index, err)))
+        .collect();
+    (result.finalize(), rejections)
+}
+
+/// An encrypted tally, as produced by [`tally_ballots`]. Maps each contest index to a vector
+/// of encrypted tallies; one for each option in the contest.
+///
+/// Contests are ordered by index, so the canonical byte representation is stable and can be
+/// hashed and compared across tools.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct EncryptedTally(pub BTreeMap<ContestIndex, Vec<Ciphertext>>);
+
+impl<'de> Deserialize<'de> for EncryptedTally {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        util::serde::deserialize_one_based_index_map(deserializer).map(EncryptedTally)
+    }
+}
+
+impl EncryptedTally {
+    /// Reads an [`EncryptedTally`] from a byte slice.
+    /// It can be either the canonical or pretty JSON representation.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes).context("Reading EncryptedTally")
+    }
+}
+
+impl From<BTreeMap<ContestIndex, Vec<Ciphertext>>> for EncryptedTally {
+    fn from(tally: BTreeMap<ContestIndex, Vec<Ciphertext>>) -> Self {
+        Self(tally)
+    }
+}
+
+impl SerializableCanonical for EncryptedTally {}
+
+impl SerializablePretty for EncryptedTally {}
+
+/// Timing and throughput metrics collected while tallying ballots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TallyMetrics {
+    /// The number of ballots tallied.
+    pub ballots: usize,
+
+    /// The number of contests tallied, summed over all ballots.
+    pub contests: usize,
+
+    /// The number of homomorphic ciphertext additions performed.
+    pub ciphertext_ops: usize,
+
+    /// The time spent updating the tally.
+    pub elapsed: Duration,
+}
+
 /// A builder to tally ballots incrementally.
 pub struct BallotTallyBuilder<'a> {
     manifest: &'a ElectionManifest,
     parameters: &'a ElectionParameters,
     state: BTreeMap<ContestIndex, Vec<Ciphertext>>,
+    metrics: TallyMetrics,
 }
 
 impl<'a> BallotTallyBuilder<'a> {
@@ -222,36 +840,82 @@ impl<'a> BallotTallyBuilder<'a> {
             manifest,
             parameters,
             state: BTreeMap::new(),
+            metrics: TallyMetrics::default(),
         }
     }
 
+    /// The metrics collected so far.
+    pub fn metrics(&self) -> &TallyMetrics {
+        &self.metrics
+    }
+
     /// Conclude the tallying and get the result.
     pub fn finalize(self) -> BTreeMap<ContestIndex, Vec<Ciphertext>> {
         self.state
     }
 
+    /// Conclude the tallying and get the result together with the collected metrics.
+    pub fn finalize_with_metrics(self) -> (BTreeMap<ContestIndex, Vec<Ciphertext>>, TallyMetrics) {
+        (self.state, self.metrics)
+    }
+
     /// Update the tally with a new ballot. Returns whether the
     /// new ballot was compatible with the tally. If `false` is returned then
     /// the tally is not updated.
     pub fn update(&mut self, ballot: ScaledBallotEncrypted) -> bool {
-        let group = &self.parameters.fixed_parameters.group;
-        for (idx, contest) in ballot.contests {
-            let Some(manifest_contest) = self.manifest.contests.get(idx) else {
-                return false;
-            };
+        self.try_update(ballot).is_ok()
+    }
+
+    /// Same as [`update`](Self::update), but returns why the ballot was incompatible with the
+    /// tally.
+    pub fn try_update(&mut self, ballot: ScaledBallotEncrypted) -> Result<(), TallyError> {
+        let start = Instant::now();
+        let result = self.update_inner(ballot);
+        self.metrics.elapsed += start.elapsed();
+        if result.is_ok() {
+            self.metrics.ballots += 1;
+        }
+        result
+    }
+
+    fn update_inner(&mut self, ballot: ScaledBallotEncrypted) -> Result<(), TallyError> {
+        // Check every contest before updating any, so an incompatible ballot leaves the tally
+        // unchanged.
+        let check_span =
+            tracing::debug_span!("tally_check", contests = ballot.contests.len()).entered();
+        for (&idx, contest) in &ballot.contests {
+            let manifest_contest = self
+                .manifest
+                .contests
+                .get(idx)
+                .ok_or(TallyError::ContestNotInManifest { idx })?;
             if contest.selection.len() != manifest_contest.options.len() {
-                return false;
+                return Err(TallyError::CiphertextCountMismatch {
+                    idx,
+                    expected: manifest_contest.options.len(),
+                    actual: contest.selection.len(),
+                });
             }
+        }
+
+        drop(check_span);
+
+        let _accumulate_entered =
+            tracing::debug_span!("tally_accumulate", contests = ballot.contests.len()).entered();
+        let group = &self.parameters.fixed_parameters.group;
+        for (idx, contest) in ballot.contests {
             if let Some(v) = self.state.get_mut(&idx) {
                 for (j, encryption) in contest.selection.iter().enumerate() {
                     v[j].alpha = v[j].alpha.mul(&encryption.alpha, group);
                     v[j].beta = v[j].beta.mul(&encryption.beta, group);
                 }
+                self.metrics.ciphertext_ops += contest.selection.len();
             } else {
                 self.state.insert(idx, contest.selection);
             }
+            self.metrics.contests += 1;
         }
-        true
+        Ok(())
     }
 }
 
@@ -266,10 +930,12 @@ mod test {
         ballot_style::BallotStyle,
         contest_selection::ContestSelection,
         device::Device,
-        election_manifest::{Contest, ContestOption},
+        election_manifest::{Contest, ContestOption, ELECTION_MANIFEST_SCHEMA_VERSION},
         election_record::PreVotingData,
+        event_capture::EventCapture,
         example_election_manifest::example_election_manifest,
         example_election_parameters::example_election_parameters,
+        example_pre_voting_data::{example_pre_voting_data_with, toy_device},
         guardian_public_key::GuardianPublicKey,
         guardian_secret_key::GuardianSecretKey,
         guardian_share::{GuardianEncryptedShare, GuardianSecretKeyShare},
@@ -282,7 +948,7 @@ mod test {
         },
     };
     use std::iter::zip;
-    use util::csprng::Csprng;
+    use util::{algebra::GroupElement, csprng::Csprng};
 
     fn g_key(i: u32) -> GuardianSecretKey {
         let mut seed = Vec::new();
@@ -405,19 +1071,12 @@ mod test {
             Contest {
                 label: "Minister of Arcane Sciences".to_string(),
                 selection_limit: 2,
+                exactly_one: false,
                 options: [
-                    ContestOption {
-                        label: "Élyria Moonshadow\n(Crystâlheärt)".to_string(),
-                    },
-                    ContestOption {
-                        label: "Archímedes Darkstone\n(Ætherwïng)".to_string(),
-                    },
-                    ContestOption {
-                        label: "Seraphína Stormbinder\n(Independent)".to_string(),
-                    },
-                    ContestOption {
-                        label: "Gávrïel Runëbørne\n(Stärsky)".to_string(),
-                    },
+                    ContestOption::new("Élyria Moonshadow\n(Crystâlheärt)"),
+                    ContestOption::new("Archímedes Darkstone\n(Ætherwïng)"),
+                    ContestOption::new("Seraphína Stormbinder\n(Independent)"),
+                    ContestOption::new("Gávrïel Runëbørne\n(Stärsky)"),
                 ]
                 .try_into()
                 .unwrap(),
@@ -426,16 +1085,11 @@ mod test {
             Contest {
                 label: "Minister of Elemental Resources".to_string(),
                 selection_limit: 1,
+                exactly_one: false,
                 options: [
-                    ContestOption {
-                        label: "Tïtus Stormforge\n(Ætherwïng)".to_string(),
-                    },
-                    ContestOption {
-                        label: "Fæ Willowgrove\n(Crystâlheärt)".to_string(),
-                    },
-                    ContestOption {
-                        label: "Tèrra Stonebinder\n(Independent)".to_string(),
-                    },
+                    ContestOption::new("Tïtus Stormforge\n(Ætherwïng)"),
+                    ContestOption::new("Fæ Willowgrove\n(Crystâlheärt)"),
+                    ContestOption::new("Tèrra Stonebinder\n(Independent)"),
                 ]
                 .try_into()
                 .unwrap(),
@@ -444,16 +1098,11 @@ mod test {
             Contest {
                 label: "Minister of Dance".to_string(),
                 selection_limit: 1,
+                exactly_one: false,
                 options: [
-                    ContestOption {
-                        label: "Äeliana Sunsong\n(Crystâlheärt)".to_string(),
-                    },
-                    ContestOption {
-                        label: "Thâlia Shadowdance\n(Ætherwïng)".to_string(),
-                    },
-                    ContestOption {
-                        label: "Jasper Moonstep\n(Stärsky)".to_string(),
-                    },
+                    ContestOption::new("Äeliana Sunsong\n(Crystâlheärt)"),
+                    ContestOption::new("Thâlia Shadowdance\n(Ætherwïng)"),
+                    ContestOption::new("Jasper Moonstep\n(Stärsky)"),
                 ]
                 .try_into()
                 .unwrap(),
@@ -489,6 +1138,7 @@ mod test {
         .unwrap();
 
         ElectionManifest {
+            schema_version: ELECTION_MANIFEST_SCHEMA_VERSION,
             label: "General Election - The United Realms of Imaginaria".to_string(),
             contests,
             ballot_styles,
@@ -739,18 +1389,12 @@ mod test {
                     &pre_voting_data.public_key,
                     ct
                 ));
-                dec.plain_text
+                ContestOptionTally::try_from_field_element(&dec.plain_text, &fixed_parameters.field)
+                    .unwrap()
+                    .get()
             })
             .collect();
-        assert_eq!(
-            decryption_contest_1,
-            vec![
-                FieldElement::from(2u8, &fixed_parameters.field),
-                FieldElement::from(1u8, &fixed_parameters.field),
-                FieldElement::from(0u8, &fixed_parameters.field),
-                FieldElement::from(0u8, &fixed_parameters.field)
-            ]
-        );
+        assert_eq!(decryption_contest_1, vec![2, 1, 0, 0]);
         let decryption_contest_2: Vec<_> = result_contest_2
             .iter()
             .map(|ct| {
@@ -767,17 +1411,12 @@ mod test {
                     &pre_voting_data.public_key,
                     ct
                 ));
-                dec.plain_text
+                ContestOptionTally::try_from_field_element(&dec.plain_text, &fixed_parameters.field)
+                    .unwrap()
+                    .get()
             })
             .collect();
-        assert_eq!(
-            decryption_contest_2,
-            vec![
-                FieldElement::from(1u8, &fixed_parameters.field),
-                FieldElement::from(1u8, &fixed_parameters.field),
-                FieldElement::from(0u8, &fixed_parameters.field)
-            ]
-        );
+        assert_eq!(decryption_contest_2, vec![1, 1, 0]);
         let decryption_contest_3: Vec<_> = result_contest_3
             .iter()
             .map(|ct| {
@@ -794,16 +1433,978 @@ mod test {
                     &pre_voting_data.public_key,
                     ct
                 ));
-                dec.plain_text
+                ContestOptionTally::try_from_field_element(&dec.plain_text, &fixed_parameters.field)
+                    .unwrap()
+                    .get()
             })
             .collect();
+        assert_eq!(decryption_contest_3, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_contest_option_tally_try_from_field_element() {
+        let election_parameters = example_election_parameters();
+        let field = &election_parameters.fixed_parameters.field;
+
+        let tally =
+            ContestOptionTally::try_from_field_element(&FieldElement::from(42u8, field), field)
+                .unwrap();
+        assert_eq!(tally.get(), 42);
+
+        let max = FieldElement::from_u64_checked(CONTEST_OPTION_TALLY_MAX, field).unwrap();
+        assert_eq!(
+            ContestOptionTally::try_from_field_element(&max, field)
+                .unwrap()
+                .get(),
+            CONTEST_OPTION_TALLY_MAX
+        );
+
+        let too_large =
+            FieldElement::from_u64_checked(CONTEST_OPTION_TALLY_MAX + 1, field).unwrap();
+        assert_eq!(
+            ContestOptionTally::try_from_field_element(&too_large, field).unwrap_err(),
+            TallyError::TallyOutOfRange {
+                value: (CONTEST_OPTION_TALLY_MAX + 1).to_string(),
+                max: CONTEST_OPTION_TALLY_MAX,
+            }
+        );
+
+        // Values beyond `u64`, such as `-1`, are rejected too.
+        let minus_one = FieldElement::from(0u8, field).sub(&FieldElement::from(1u8, field), field);
+        assert!(matches!(
+            ContestOptionTally::try_from_field_element(&minus_one, field),
+            Err(TallyError::TallyOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_tally_ballots_timed() {
+        let election_manifest = short_manifest();
+        let election_parameters = example_election_parameters();
+
+        let scaled_ballot = |contests: &[u32]| ScaledBallotEncrypted {
+            contests: contests
+                .iter()
+                .map(|&ix1| {
+                    let idx = ContestIndex::from_one_based_index(ix1).unwrap();
+                    let cnt_options = election_manifest.contests.get(idx).unwrap().options.len();
+                    let selection = vec![Ciphertext::one(); cnt_options];
+                    (idx, ScaledContestEncrypted { selection })
+                })
+                .collect(),
+        };
+
+        let encrypted_ballots = vec![
+            scaled_ballot(&[1, 3]),
+            scaled_ballot(&[2, 3]),
+            scaled_ballot(&[1, 2, 3]),
+        ];
+
+        let (tally, metrics) =
+            tally_ballots_timed(encrypted_ballots, &election_manifest, &election_parameters)
+                .unwrap();
+
+        assert_eq!(tally.len(), 3);
+        assert_eq!(metrics.ballots, 3);
+        assert_eq!(metrics.contests, 7);
+        // The first occurrence of each contest is stored rather than added.
+        assert_eq!(metrics.ciphertext_ops, 3 + 4 + 3 + 3);
+
+        // An incompatible ballot is not counted.
+        let mut builder = BallotTallyBuilder::new(&election_manifest, &election_parameters);
+        assert!(builder.update(scaled_ballot(&[1])));
+        let mut bad_ballot = scaled_ballot(&[2]);
+        for contest in bad_ballot.contests.values_mut() {
+            contest.selection.pop();
+        }
+        assert!(!builder.update(bad_ballot));
+        assert_eq!(builder.metrics().ballots, 1);
+    }
+
+    #[test]
+    fn test_tally_ballots_collecting_rejections() {
+        let election_manifest = short_manifest();
+        let election_parameters = example_election_parameters();
+        let group = &election_parameters.fixed_parameters.group;
+        let contest_ix = |ix1| ContestIndex::from_one_based_index(ix1).unwrap();
+
+        // A ballot voting on the given contests, with the given ciphertext for every option.
+        let scaled_ballot = |contests: &[u32], ciphertext: &Ciphertext| ScaledBallotEncrypted {
+            contests: contests
+                .iter()
+                .map(|&ix1| {
+                    let cnt_options = election_manifest
+                        .contests
+                        .get(contest_ix(ix1))
+                        .map_or(1, |contest| contest.options.len());
+                    let selection = vec![ciphertext.clone(); cnt_options];
+                    (contest_ix(ix1), ScaledContestEncrypted { selection })
+                })
+                .collect(),
+        };
+        let one = Ciphertext::one();
+        let g = Ciphertext {
+            alpha: group.generator(),
+            beta: group.generator(),
+        };
+
+        let mut short_ballot = scaled_ballot(&[2], &g);
+        for contest in short_ballot.contests.values_mut() {
+            contest.selection.pop();
+        }
+        let encrypted_ballots = vec![
+            scaled_ballot(&[1, 3], &one),
+            short_ballot,
+            scaled_ballot(&[2, 3], &one),
+            // Contest 1 is valid, but the ballot is rejected as a whole.
+            scaled_ballot(&[1, 4], &g),
+        ];
+
+        let (tally, rejections) = tally_ballots_collecting_rejections(
+            encrypted_ballots,
+            &election_manifest,
+            &election_parameters,
+        );
+
         assert_eq!(
-            decryption_contest_3,
-            vec![
-                FieldElement::from(1u8, &fixed_parameters.field),
-                FieldElement::from(2u8, &fixed_parameters.field),
-                FieldElement::from(0u8, &fixed_parameters.field)
+            rejections,
+            [
+                (
+                    1,
+                    TallyError::CiphertextCountMismatch {
+                        idx: contest_ix(2),
+                        expected: 3,
+                        actual: 2,
+                    }
+                ),
+                (3, TallyError::ContestNotInManifest { idx: contest_ix(4) }),
             ]
         );
+
+        // Only the accepted ballots are tallied.
+        let expected = tally_ballots(
+            [scaled_ballot(&[1, 3], &one), scaled_ballot(&[2, 3], &one)],
+            &election_manifest,
+            &election_parameters,
+        )
+        .unwrap();
+        assert_eq!(tally, expected);
+        assert!(tally[&contest_ix(1)].iter().all(|ct| *ct == one));
+    }
+
+    #[test]
+    fn test_encrypted_tally_canonical_bytes() {
+        let fixed_parameters = example_election_parameters().fixed_parameters;
+        let field = &fixed_parameters.field;
+        let group = &fixed_parameters.group;
+
+        let ciphertext = |j: u8| Ciphertext {
+            alpha: group.g_exp(&FieldElement::from(j, field)),
+            beta: group.g_exp(&FieldElement::from(j + 1, field)),
+        };
+
+        let encrypted_tally = EncryptedTally::from(BTreeMap::from([
+            (
+                ContestIndex::from_one_based_index(3).unwrap(),
+                vec![ciphertext(3), ciphertext(4), ciphertext(5)],
+            ),
+            (
+                ContestIndex::from_one_based_index(1).unwrap(),
+                vec![ciphertext(1), ciphertext(2)],
+            ),
+        ]));
+
+        let canonical_bytes = encrypted_tally.to_canonical_bytes().unwrap();
+
+        let from_canonical = EncryptedTally::from_bytes(&canonical_bytes).unwrap();
+        assert_eq!(from_canonical, encrypted_tally);
+        assert_eq!(
+            from_canonical.to_canonical_bytes().unwrap(),
+            canonical_bytes
+        );
+
+        let json_pretty = encrypted_tally.to_json_pretty();
+        let from_pretty = EncryptedTally::from_bytes(json_pretty.as_bytes()).unwrap();
+        assert_eq!(from_pretty.to_canonical_bytes().unwrap(), canonical_bytes);
+
+        // A contest key which is not a 1-based index is rejected.
+        let json_zero_key = String::from_utf8(canonical_bytes)
+            .unwrap()
+            .replace("\"3\"", "\"0\"");
+        let err = EncryptedTally::from_bytes(json_zero_key.as_bytes()).unwrap_err();
+        assert!(
+            format!("{err:#}").contains(&util::serde::IndexMapKeyError::Zero.to_string()),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_ballot_id() {
+        let make_ballot = |confirmation_code: HValue| {
+            BallotEncrypted::new(
+                Index::from_one_based_index(1).unwrap(),
+                HValue::default(),
+                &BTreeMap::new(),
+                BallotState::Cast,
+                confirmation_code,
+                "",
+                "Some encryption device",
+            )
+        };
+
+        let ballot_1 = make_ballot(HValue::from([0x01; 32]));
+        let ballot_2 = make_ballot(HValue::from([0x02; 32]));
+
+        let scheme = ConfirmationCodeBallotIdScheme;
+        let id_1 = ballot_1.ballot_id(&scheme);
+        let id_2 = ballot_2.ballot_id(&scheme);
+
+        assert_ne!(id_1, id_2);
+        assert_eq!(ballot_1.ballot_id(&scheme), id_1);
+        assert_eq!(
+            make_ballot(HValue::from([0x01; 32])).ballot_id(&scheme),
+            id_1
+        );
+    }
+
+    /// Verifies the ballot, returning the result and the audit events emitted.
+    fn verify_capturing_audit_events(
+        ballot: &BallotEncrypted,
+        header: &PreVotingData,
+    ) -> (bool, Vec<String>) {
+        EventCapture::capture(
+            tracing::Level::INFO,
+            Some(VERIFICATION_AUDIT_TARGET),
+            || ballot.verify(header),
+        )
+    }
+
+    /// An encryption device for the [`short_manifest`].
+    fn short_manifest_device() -> Device {
+        manifest_device(short_manifest())
+    }
+
+    /// An encryption device for the given election manifest.
+    fn manifest_device(election_manifest: ElectionManifest) -> Device {
+        let mut csprng = Csprng::new(b"manifest_device");
+        let (_, pre_voting_data) = example_pre_voting_data_with(
+            &mut csprng,
+            example_election_parameters(),
+            election_manifest,
+        )
+        .unwrap();
+        Device::new("Some encryption device", pre_voting_data)
+    }
+
+    #[test]
+    fn test_ballot_style_hash() {
+        let device = short_manifest_device();
+        let mut csprng = Csprng::new(b"test_ballot_style_hash");
+
+        let ballot_style_ix = Index::from_one_based_index(1).unwrap();
+        let selections = BTreeMap::from([
+            (
+                ContestIndex::from_one_based_index(1).unwrap(),
+                ContestSelection::new(vec![0, 1, 0, 0]).unwrap(),
+            ),
+            (
+                ContestIndex::from_one_based_index(3).unwrap(),
+                ContestSelection::new(vec![0, 0, 1]).unwrap(),
+            ),
+        ]);
+        let ballot = BallotEncrypted::new_from_selections(
+            ballot_style_ix,
+            &device,
+            "2023-05-02",
+            &mut csprng,
+            &[0, 1, 2, 3],
+            &selections,
+        )
+        .unwrap();
+        ballot
+            .validate_ballot_style_hash(device.header.manifest())
+            .unwrap();
+        assert!(ballot.verify(&device.header));
+
+        // The ballot style has changed since the ballot was created.
+        let mut header = device.header.clone();
+        header
+            .manifest
+            .ballot_styles
+            .get_mut(ballot_style_ix)
+            .unwrap()
+            .label
+            .push_str(" (revised)");
+        assert!(matches!(
+            ballot.validate_ballot_style_hash(header.manifest()),
+            Err(BallotEncryptedError::BallotStyleHashMismatch { ballot_style_ix: ix })
+                if ix == ballot_style_ix
+        ));
+        assert!(!ballot.verify(&header));
+    }
+
+    #[test]
+    fn test_ballot_without_ballot_style_hash() {
+        let device = short_manifest_device();
+        let mut csprng = Csprng::new(b"test_ballot_without_ballot_style_hash");
+
+        let selections = BTreeMap::from([
+            (
+                ContestIndex::from_one_based_index(1).unwrap(),
+                ContestSelection::new(vec![0, 1, 0, 0]).unwrap(),
+            ),
+            (
+                ContestIndex::from_one_based_index(3).unwrap(),
+                ContestSelection::new(vec![0, 0, 1]).unwrap(),
+            ),
+        ]);
+        let ballot = BallotEncrypted::new_from_selections(
+            Index::from_one_based_index(1).unwrap(),
+            &device,
+            "2023-05-02",
+            &mut csprng,
+            &[0, 1, 2, 3],
+            &selections,
+        )
+        .unwrap();
+
+        // A ballot as written before the ballot style hash was recorded.
+        let mut json = serde_json::to_value(&ballot).unwrap();
+        assert!(json
+            .as_object_mut()
+            .unwrap()
+            .remove("ballot_style_hash")
+            .is_some());
+        let ballot: BallotEncrypted = serde_json::from_value(json).unwrap();
+        assert_eq!(ballot.opt_ballot_style_hash, None);
+
+        // It loads, but fails verification, as it can't be checked against the manifest.
+        assert!(matches!(
+            ballot.validate_ballot_style_hash(device.header.manifest()),
+            Err(BallotEncryptedError::BallotStyleHashMissing { ballot_style_ix })
+                if ballot_style_ix == ballot.ballot_style_index
+        ));
+        assert!(!ballot.verify(&device.header));
+        assert!(!serde_json::to_string(&ballot)
+            .unwrap()
+            .contains("ballot_style_hash"));
+    }
+
+    #[test]
+    fn test_contest_not_in_ballot_style() {
+        let device = short_manifest_device();
+        let mut csprng = Csprng::new(b"test_contest_not_in_ballot_style");
+
+        // Ballot style 1 has contests 1 and 3, but not 2.
+        let ballot_style_ix = Index::from_one_based_index(1).unwrap();
+        let contest_ix_2 = ContestIndex::from_one_based_index(2).unwrap();
+        let mut selections = BTreeMap::from([
+            (
+                ContestIndex::from_one_based_index(1).unwrap(),
+                ContestSelection::new(vec![0, 1, 0, 0]).unwrap(),
+            ),
+            (
+                ContestIndex::from_one_based_index(3).unwrap(),
+                ContestSelection::new(vec![0, 0, 1]).unwrap(),
+            ),
+        ]);
+        let encrypt = |csprng: &mut Csprng, selections: &BTreeMap<_, _>| {
+            BallotEncrypted::new_from_selections(
+                ballot_style_ix,
+                &device,
+                "2023-05-02",
+                csprng,
+                &[0, 1, 2, 3],
+                selections,
+            )
+            .unwrap()
+        };
+
+        let ballot = encrypt(&mut csprng, &selections);
+        ballot
+            .validate_contests_in_ballot_style(device.header.manifest())
+            .unwrap();
+        assert!(ballot.verify(&device.header));
+
+        // The same ballot, stuffed with contest 2.
+        selections.insert(contest_ix_2, ContestSelection::new(vec![1, 0, 0]).unwrap());
+        let ballot = encrypt(&mut csprng, &selections);
+        assert!(matches!(
+            ballot.validate_contests_in_ballot_style(device.header.manifest()),
+            Err(BallotEncryptedError::ContestNotInBallotStyle { idx, ballot_style_ix: ix })
+                if idx == contest_ix_2 && ix == ballot_style_ix
+        ));
+        assert!(!ballot.verify(&device.header));
+    }
+
+    #[test]
+    fn test_verify_ballot_audit_events() {
+        let device = short_manifest_device();
+        let mut csprng = Csprng::new(&[0, 1, 2, 3]);
+
+        // Ballot style 1 has contests 1 and 3.
+        let contest_ix_1 = ContestIndex::from_one_based_index(1).unwrap();
+        let contest_ix_3 = ContestIndex::from_one_based_index(3).unwrap();
+        let selections = BTreeMap::from([
+            (
+                contest_ix_1,
+                ContestSelection::new(vec![1, 0, 0, 1]).unwrap(),
+            ),
+            (contest_ix_3, ContestSelection::new(vec![0, 1, 0]).unwrap()),
+        ]);
+        let ballot = BallotEncrypted::new_from_selections(
+            Index::from_one_based_index(1).unwrap(),
+            &device,
+            "2023-05-02",
+            &mut csprng,
+            &[0, 1, 2, 2, 2, 2, 2, 2, 3],
+            &selections,
+        )
+        .unwrap();
+
+        let cc = ballot.confirmation_code;
+        let contest_events = |contest_ix: ContestIndex, cnt_options: u32, selection_limit| {
+            let contest_hash = ballot.contests()[&contest_ix].contest_hash;
+            let mut events = vec![format!(
+                "ballot={cc} check=contest_present contest={contest_ix} passed=true"
+            )];
+            events.extend((1..=cnt_options).map(|j| {
+                format!(
+                    "ballot={cc} check=ballot_correctness contest={contest_ix} contest_hash={contest_hash} option={j} passed=true"
+                )
+            }));
+            events.push(format!(
+                "ballot={cc} check=selection_limit contest={contest_ix} contest_hash={contest_hash} passed=true selection_limit={selection_limit}"
+            ));
+            events
+        };
+
+        let mut expected = vec![
+            format!("ballot={cc} check=ciphertext_counts passed=true"),
+            format!("ballot={cc} check=group_membership passed=true"),
+            format!("ballot={cc} ballot_style=1 check=ballot_style passed=true"),
+        ];
+        expected.extend(contest_events(contest_ix_1, 4, 2));
+        expected.extend(contest_events(contest_ix_3, 3, 1));
+
+        let (verified, events) = verify_capturing_audit_events(&ballot, &device.header);
+        assert!(verified);
+        assert_eq!(events, expected);
+
+        // The same contests claimed for ballot style 3, which also has contest 2. Verification
+        // stops at the failed check.
+        let ballot_style_ix_3 = Index::from_one_based_index(3).unwrap();
+        let ballot = BallotEncrypted::new(
+            ballot_style_ix_3,
+            device
+                .header
+                .manifest
+                .ballot_styles
+                .get(ballot_style_ix_3)
+                .unwrap()
+                .content_hash(),
+            ballot.contests(),
+            BallotState::Uncast,
+            cc,
+            "2023-05-02",
+            "Some encryption device",
+        );
+        let mut expected = vec![
+            format!("ballot={cc} check=ciphertext_counts passed=true"),
+            format!("ballot={cc} check=group_membership passed=true"),
+            format!("ballot={cc} ballot_style=3 check=ballot_style passed=true"),
+        ];
+        expected.extend(contest_events(contest_ix_1, 4, 2));
+        expected.push(format!(
+            "ballot={cc} check=contest_present contest=2 passed=false"
+        ));
+
+        let (verified, events) = verify_capturing_audit_events(&ballot, &device.header);
+        assert!(!verified);
+        assert_eq!(events, expected);
+    }
+
+    #[cfg(feature = "eg_parallel_verification")]
+    #[test]
+    fn test_verify_parallel() {
+        let device = short_manifest_device();
+        let mut csprng = Csprng::new(b"test_verify_parallel");
+
+        // Ballot style 3 has contests 1, 2, and 3.
+        let selections = BTreeMap::from([
+            (
+                ContestIndex::from_one_based_index(1).unwrap(),
+                ContestSelection::new(vec![0, 1, 1, 0]).unwrap(),
+            ),
+            (
+                ContestIndex::from_one_based_index(2).unwrap(),
+                ContestSelection::new(vec![0, 0, 1]).unwrap(),
+            ),
+            (
+                ContestIndex::from_one_based_index(3).unwrap(),
+                ContestSelection::new(vec![1, 0, 0]).unwrap(),
+            ),
+        ]);
+        let mut ballot = BallotEncrypted::new_from_selections(
+            Index::from_one_based_index(3).unwrap(),
+            &device,
+            "2023-05-02",
+            &mut csprng,
+            &[0, 1, 2, 3, 4],
+            &selections,
+        )
+        .unwrap();
+
+        assert!(ballot.verify(&device.header));
+        assert!(ballot.verify_parallel(&device.header));
+
+        // Swapping two ciphertexts of contest 2 invalidates their proofs.
+        let contest_ix_2 = ContestIndex::from_one_based_index(2).unwrap();
+        let swap_ciphertexts = |ballot: &mut BallotEncrypted| {
+            ballot
+                .contests
+                .get_mut(&contest_ix_2)
+                .unwrap()
+                .selection
+                .swap(0, 2);
+        };
+        swap_ciphertexts(&mut ballot);
+
+        assert!(!ballot.verify(&device.header));
+        assert!(!ballot.verify_parallel(&device.header));
+
+        // As does a missing contest.
+        swap_ciphertexts(&mut ballot);
+        assert!(ballot.verify(&device.header));
+        ballot.contests.remove(&contest_ix_2);
+
+        assert!(!ballot.verify(&device.header));
+        assert!(!ballot.verify_parallel(&device.header));
+    }
+
+    #[test]
+    fn test_as_scaled_unit() {
+        use crate::joint_election_public_key::CNT_SCALE_EXPONENTIATIONS;
+
+        let device = short_manifest_device();
+        let fixed_parameters = &device.header.parameters.fixed_parameters;
+        let mut csprng = Csprng::new(b"test_as_scaled_unit");
+
+        // Ballot style 1 has contests 1 and 3.
+        let selections = BTreeMap::from([
+            (
+                ContestIndex::from_one_based_index(1).unwrap(),
+                ContestSelection::new(vec![1, 0, 0, 1]).unwrap(),
+            ),
+            (
+                ContestIndex::from_one_based_index(3).unwrap(),
+                ContestSelection::new(vec![0, 1, 0]).unwrap(),
+            ),
+        ]);
+        let ballot = BallotEncrypted::new_from_selections(
+            Index::from_one_based_index(1).unwrap(),
+            &device,
+            "2023-05-02",
+            &mut csprng,
+            &[0, 1, 2, 3, 4],
+            &selections,
+        )
+        .unwrap();
+
+        let cnt_exponentiations = || CNT_SCALE_EXPONENTIATIONS.with(|cnt| cnt.get());
+
+        let cnt_before = cnt_exponentiations();
+        let scaled_unit = ballot.as_scaled_unit();
+        assert_eq!(cnt_exponentiations(), cnt_before);
+
+        let factor = FieldElement::from(1u8, &fixed_parameters.field);
+        let scaled = ballot.scale(fixed_parameters, &factor);
+        assert_eq!(cnt_exponentiations(), cnt_before + 2 * 7);
+
+        assert!(scaled_unit.contests == scaled.contests);
+    }
+
+    #[test]
+    fn test_verify_rejects_ciphertext_not_in_group() {
+        let device = short_manifest_device();
+        let group = &device.header.parameters().fixed_parameters.group;
+        let mut csprng = Csprng::new(b"test_verify_rejects_ciphertext_not_in_group");
+
+        // Ballot style 1 has contests 1 and 3.
+        let contest_ix_1 = ContestIndex::from_one_based_index(1).unwrap();
+        let selections = BTreeMap::from([
+            (
+                contest_ix_1,
+                ContestSelection::new(vec![1, 0, 0, 1]).unwrap(),
+            ),
+            (
+                ContestIndex::from_one_based_index(3).unwrap(),
+                ContestSelection::new(vec![0, 1, 0]).unwrap(),
+            ),
+        ]);
+        let mut ballot = BallotEncrypted::new_from_selections(
+            Index::from_one_based_index(1).unwrap(),
+            &device,
+            "2023-05-02",
+            &mut csprng,
+            &[0, 1, 2, 3, 4],
+            &selections,
+        )
+        .unwrap();
+        assert!(ballot.verify(&device.header));
+
+        // `p - 1` has order 2, so it lies outside of the subgroup of order `q`.
+        let minus_one: GroupElement = serde_json::from_value(serde_json::json!(
+            util::base16::to_string_uppercase_hex_bits(&(group.modulus() - 1u8), 4096).unwrap()
+        ))
+        .unwrap();
+        let option_ix = ContestOptionIndex::from_one_based_index(2).unwrap();
+        let contest_encrypted = ballot.contests.get_mut(&contest_ix_1).unwrap();
+        let ct = &mut contest_encrypted.selection[1];
+        ct.beta = ct.beta.mul(&minus_one, group);
+        assert!(!ct.is_valid_group_member(group));
+
+        let err = ballot
+            .validate_group_membership(&device.header.parameters().fixed_parameters)
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                BallotEncryptedError::CiphertextNotInGroup { idx, option_ix: ix }
+                    if idx == contest_ix_1 && ix == option_ix
+            ),
+            "{err}"
+        );
+
+        // The ballot is rejected before any proof is verified.
+        let cc = ballot.confirmation_code;
+        let (verified, events) = verify_capturing_audit_events(&ballot, &device.header);
+        assert!(!verified);
+        assert_eq!(
+            events,
+            [
+                format!("ballot={cc} check=ciphertext_counts passed=true"),
+                format!("ballot={cc} check=group_membership passed=false"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encrypted_proof_size_estimate() {
+        let device = short_manifest_device();
+        let fixed_parameters = &device.header.parameters().fixed_parameters;
+        let mut csprng = Csprng::new(b"test_encrypted_proof_size_estimate");
+
+        // Ballot style 1 has contests 1 and 3, with 4 and 3 options.
+        let selections = BTreeMap::from([
+            (
+                ContestIndex::from_one_based_index(1).unwrap(),
+                ContestSelection::new(vec![1, 0, 0, 1]).unwrap(),
+            ),
+            (
+                ContestIndex::from_one_based_index(3).unwrap(),
+                ContestSelection::new(vec![0, 1, 0]).unwrap(),
+            ),
+        ]);
+        let ballot = BallotEncrypted::new_from_selections(
+            Index::from_one_based_index(1).unwrap(),
+            &device,
+            "2023-05-02",
+            &mut csprng,
+            &[0, 1, 2, 3, 4],
+            &selections,
+        )
+        .unwrap();
+
+        for (contest_ix, contest_encrypted) in ballot.contests() {
+            let contest = device.header.manifest().contests.get(*contest_ix).unwrap();
+            let estimate = contest.encrypted_proof_size_estimate(fixed_parameters);
+
+            let actual = serde_json::to_vec(&contest_encrypted.proof_ballot_correctness)
+                .unwrap()
+                .len()
+                + serde_json::to_vec(&contest_encrypted.proof_selection_limit)
+                    .unwrap()
+                    .len();
+            assert!(
+                estimate.abs_diff(actual) * 100 <= actual,
+                "contest {contest_ix}: estimate {estimate}, actual {actual}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_ciphertext_counts() {
+        let mut csprng = Csprng::new(b"test_validate_ciphertext_counts");
+        let device = toy_device(&mut csprng);
+        let election_manifest = device.header.manifest();
+
+        let mut contest_encrypted = |contest_ix: ContestIndex| {
+            let contest = election_manifest.contests.get(contest_ix).unwrap();
+            let pt_vote = ContestSelection::new_pick_random(
+                &mut csprng,
+                contest.selection_limit,
+                contest.options.len(),
+            );
+            ContestEncrypted::new(
+                &device,
+                &mut csprng,
+                &[5u8; 32],
+                contest,
+                contest_ix,
+                &pt_vote,
+            )
+            .unwrap()
+        };
+
+        // Contest 1 has 2 options, contest 3 has 3 options.
+        let contest_ix_1 = Index::from_one_based_index(1).unwrap();
+        let contest_ix_3 = Index::from_one_based_index(3).unwrap();
+        let contest_3 = contest_encrypted(contest_ix_3);
+        let mut contests = BTreeMap::from([
+            (contest_ix_1, contest_encrypted(contest_ix_1)),
+            (contest_ix_3, contest_3.clone()),
+        ]);
+
+        let make_ballot = |contests: &BTreeMap<ContestIndex, ContestEncrypted>| {
+            BallotEncrypted::new(
+                Index::from_one_based_index(1).unwrap(),
+                HValue::default(),
+                contests,
+                BallotState::Uncast,
+                HValue::default(),
+                "",
+                "Some encryption device",
+            )
+        };
+
+        make_ballot(&contests)
+            .validate_ciphertext_counts(election_manifest)
+            .unwrap();
+
+        // Contest 3 is missing a ciphertext, and the proof for it.
+        let mut contest = contest_3.clone();
+        contest.selection.pop();
+        contest.proof_ballot_correctness.pop();
+        contests.insert(contest_ix_3, contest);
+        let err = make_ballot(&contests)
+            .validate_ciphertext_counts(election_manifest)
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                BallotEncryptedError::CiphertextCountMismatch {
+                    idx,
+                    expected: 3,
+                    actual: 2
+                } if idx == contest_ix_3
+            ),
+            "{err}"
+        );
+
+        // Contest 3 has all its ciphertexts, but is missing a proof.
+        let mut contest = contest_3;
+        contest.proof_ballot_correctness.pop();
+        contests.insert(contest_ix_3, contest);
+        let err = make_ballot(&contests)
+            .validate_ciphertext_counts(election_manifest)
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                BallotEncryptedError::ProofCountMismatch {
+                    expected: 3,
+                    actual: 2,
+                    ..
+                }
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_verify_ballot_batch_chain() {
+        let device = short_manifest_device();
+        let h_e = device.header.hashes_ext.h_e;
+        let h_di = HValue::from([0x5a; 32]);
+        let mut csprng = Csprng::new(b"test_verify_ballot_batch_chain");
+
+        // Ballot style 2 has contests 2 and 3.
+        let selections = BTreeMap::from([
+            (
+                ContestIndex::from_one_based_index(2).unwrap(),
+                ContestSelection::new(vec![0, 1, 0]).unwrap(),
+            ),
+            (
+                ContestIndex::from_one_based_index(3).unwrap(),
+                ContestSelection::new(vec![0, 0, 1]).unwrap(),
+            ),
+        ]);
+
+        // Each ballot chains from its predecessor, the first one from the device.
+        let mut ballots: Vec<BallotEncrypted> = Vec::new();
+        for _ in 0..3 {
+            let mut ballot = BallotEncrypted::new_from_selections(
+                Index::from_one_based_index(2).unwrap(),
+                &device,
+                "2023-05-02",
+                &mut csprng,
+                &[0, 1, 2, 3],
+                &selections,
+            )
+            .unwrap();
+            let b_aux = ballots.last().map_or(h_di, |b| b.confirmation_code);
+            ballot.confirmation_code =
+                confirmation_code(&h_e, ballot.contests.values(), b_aux.as_ref());
+            ballots.push(ballot);
+        }
+
+        verify_ballot_batch_chain(&device.header, &ballots, &h_di).unwrap();
+        verify_ballot_batch_chain(&device.header, &[], &h_di).unwrap();
+
+        // Reordering the batch breaks the chain.
+        ballots.swap(1, 2);
+        assert_eq!(
+            verify_ballot_batch_chain(&device.header, &ballots, &h_di),
+            Err(BallotChainError::NotChainedFromPredecessor {
+                index: 1,
+                predecessor: 0
+            })
+        );
+
+        ballots.swap(0, 1);
+        assert_eq!(
+            verify_ballot_batch_chain(&device.header, &ballots, &h_di),
+            Err(BallotChainError::NotChainedFromDevice { index: 0 })
+        );
+    }
+
+    #[test]
+    fn test_verify_exactly_one() {
+        // Contest 2 of ballot style 2 requires exactly one selection.
+        let contest_ix_2 = ContestIndex::from_one_based_index(2).unwrap();
+        let contest_ix_3 = ContestIndex::from_one_based_index(3).unwrap();
+        let mut election_manifest = short_manifest();
+        election_manifest
+            .contests
+            .get_mut(contest_ix_2)
+            .unwrap()
+            .exactly_one = true;
+        election_manifest.validate().unwrap();
+        let device = manifest_device(election_manifest);
+        let contest_2 = device.header.manifest.contests.get(contest_ix_2).unwrap();
+        let mut csprng = Csprng::new(b"test_verify_exactly_one");
+
+        let encrypt = |csprng: &mut Csprng, vote: Vec<u8>| {
+            let selections = BTreeMap::from([
+                (contest_ix_2, ContestSelection::new(vote).unwrap()),
+                (contest_ix_3, ContestSelection::new(vec![1, 0, 0]).unwrap()),
+            ]);
+            BallotEncrypted::new_from_selections(
+                Index::from_one_based_index(2).unwrap(),
+                &device,
+                "2023-05-02",
+                csprng,
+                &[0, 1, 2, 3],
+                &selections,
+            )
+        };
+
+        // A single selection passes.
+        let mut ballot = encrypt(&mut csprng, vec![0, 0, 1]).unwrap();
+        assert!(ballot.verify(&device.header));
+
+        // Zero and two selections can't be proven.
+        for vote in [vec![0, 0, 0], vec![1, 0, 1]] {
+            let err = encrypt(&mut csprng, vote).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    BallotEncryptedError::ProofError {
+                        err: ProofRangeError::NotExactlyOne { .. }
+                    }
+                ),
+                "{err}"
+            );
+        }
+
+        // Nor do they pass verification with a proof of the selection limit in place of the
+        // proof of exactly one selection.
+        for (vote, selection_limit) in [(vec![0, 0, 0], 1), (vec![1, 0, 1], 2)] {
+            let contest_up_to_limit = Contest {
+                exactly_one: false,
+                selection_limit,
+                ..contest_2.clone()
+            };
+            let contest_encrypted = ContestEncrypted::new(
+                &device,
+                &mut csprng,
+                &[0, 1, 2, 3],
+                &contest_up_to_limit,
+                contest_ix_2,
+                &ContestSelection::new(vote).unwrap(),
+            )
+            .unwrap();
+            let cc = &ballot.confirmation_code;
+            assert!(contest_encrypted.verify(
+                &device.header,
+                cc,
+                contest_ix_2,
+                &contest_up_to_limit
+            ));
+            assert!(!contest_encrypted.verify(&device.header, cc, contest_ix_2, contest_2));
+
+            ballot.contests.insert(contest_ix_2, contest_encrypted);
+            assert!(!ballot.verify(&device.header));
+        }
+    }
+
+    #[cfg(feature = "eg_insecure_deterministic_nonces")]
+    #[test]
+    fn test_primary_nonce_from_seed_str() {
+        use crate::nonce::primary_nonce_from_seed_str;
+
+        let device = short_manifest_device();
+
+        // Ballot style 1 has contests 1 and 3.
+        let selections = BTreeMap::from([
+            (
+                ContestIndex::from_one_based_index(1).unwrap(),
+                ContestSelection::new(vec![1, 1, 0, 0]).unwrap(),
+            ),
+            (
+                ContestIndex::from_one_based_index(3).unwrap(),
+                ContestSelection::new(vec![0, 1, 0]).unwrap(),
+            ),
+        ]);
+        let encrypt = |seed: &str, csprng_seed: &[u8]| {
+            BallotEncrypted::new_from_selections(
+                BallotStyleIndex::from_one_based_index(1).unwrap(),
+                &device,
+                "2024-08-02",
+                &mut Csprng::new(csprng_seed),
+                primary_nonce_from_seed_str(seed).as_ref(),
+                &selections,
+            )
+            .unwrap()
+        };
+        let ciphertexts = |ballot: &BallotEncrypted| {
+            ballot
+                .contests()
+                .values()
+                .flat_map(|contest| contest.selection.clone())
+                .collect::<Vec<_>>()
+        };
+
+        // The same seed gives identical ciphertexts, whatever the csprng used for the proofs.
+        let ballot_1 = encrypt("demo ballot", b"csprng 1");
+        let ballot_2 = encrypt("demo ballot", b"csprng 2");
+        assert_eq!(ciphertexts(&ballot_1), ciphertexts(&ballot_2));
+        assert_eq!(ballot_1.confirmation_code(), ballot_2.confirmation_code());
+        assert!(ballot_2.verify(&device.header));
+
+        // Another seed does not.
+        let ballot_3 = encrypt("another demo ballot", b"csprng 1");
+        assert_ne!(ciphertexts(&ballot_1), ciphertexts(&ballot_3));
     }
 }
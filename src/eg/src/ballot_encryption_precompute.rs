@@ -0,0 +1,233 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! Precomputation for encrypting many ballots under the same election parameters and joint
+//! election public key.
+//!
+//! Encrypting a ballot is dominated by exponentiations with the fixed bases `g` and `K`, both
+//! for the ciphertexts and for the commitments of the range proofs. A
+//! [`BallotEncryptionPrecompute`] holds a [`FixedBaseTable`] for each, which is expensive to
+//! compute but then makes those exponentiations several times faster. Ballots encrypted with it
+//! are identical to those encrypted without it.
+
+use util::algebra::{FieldElement, FixedBaseTable, GroupElement};
+
+use crate::{
+    election_record::PreVotingData, fixed_parameters::FixedParameters,
+    joint_election_public_key::Ciphertext,
+};
+
+/// Fixed-base tables for the generator `g` and the joint election public key `K`, for use
+/// with [`crate::ballot::BallotEncrypted::new_from_selections_with_precompute`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BallotEncryptionPrecompute {
+    g_table: FixedBaseTable,
+    k_table: FixedBaseTable,
+}
+
+impl BallotEncryptionPrecompute {
+    /// Computes the tables for the group and joint election public key of the pre-voting data,
+    /// with the default window size.
+    pub fn new(header: &PreVotingData) -> BallotEncryptionPrecompute {
+        Self::with_window_bits(header, FixedBaseTable::DEFAULT_WINDOW_BITS)
+    }
+
+    /// Computes the tables for the group and joint election public key of the pre-voting data,
+    /// with windows of `window_bits` bits, see [`FixedBaseTable::with_window_bits`].
+    pub fn with_window_bits(header: &PreVotingData, window_bits: u8) -> BallotEncryptionPrecompute {
+        let group = &header.parameters.fixed_parameters.group;
+        let k = &header.public_key.joint_election_public_key;
+        BallotEncryptionPrecompute {
+            g_table: FixedBaseTable::with_window_bits(&group.generator(), window_bits, group),
+            k_table: FixedBaseTable::with_window_bits(k, window_bits, group),
+        }
+    }
+
+    /// Returns true iff the tables are for the generator and joint election public key of the
+    /// pre-voting data.
+    pub fn is_for(&self, header: &PreVotingData) -> bool {
+        let group = &header.parameters.fixed_parameters.group;
+        self.g_table.base() == &group.generator()
+            && self.k_table.base() == &header.public_key.joint_election_public_key
+    }
+
+    /// Computes `g^x mod p`, the same as [`util::algebra::Group::g_exp`].
+    pub fn g_exp(&self, x: &FieldElement, fixed_parameters: &FixedParameters) -> GroupElement {
+        self.g_table.exp(x, &fixed_parameters.group)
+    }
+
+    /// Computes `K^x mod p`, the same as [`GroupElement::exp`] on the joint election public key.
+    pub fn k_exp(&self, x: &FieldElement, fixed_parameters: &FixedParameters) -> GroupElement {
+        self.k_table.exp(x, &fixed_parameters.group)
+    }
+
+    /// Encrypts `message` with the supplied nonce `ξ`, the same as
+    /// [`crate::joint_election_public_key::JointElectionPublicKey::encrypt_value`].
+    pub fn encrypt_value(
+        &self,
+        fixed_parameters: &FixedParameters,
+        nonce: &FieldElement,
+        message: u64,
+    ) -> Ciphertext {
+        let field = &fixed_parameters.field;
+
+        let alpha = self.g_exp(nonce, fixed_parameters);
+        let exponent = &nonce.add(&FieldElement::from(message, field), field);
+        let beta = self.k_exp(exponent, fixed_parameters);
+
+        Ciphertext { alpha, beta }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{
+        ballot::{BallotEncrypted, BallotEncryptedError},
+        ballot_style::BallotStyleIndex,
+        contest_selection::ContestSelection,
+        device::Device,
+        example_pre_voting_data::example_device,
+        serializable::SerializableCanonical,
+    };
+
+    /// Encrypts a ballot of the first ballot style with random selections, drawing all randomness
+    /// from a [`Csprng`] seeded with `seed`, with or without the precompute.
+    fn encrypt_ballot(
+        device: &Device,
+        seed: &[u8],
+        opt_precompute: Option<&BallotEncryptionPrecompute>,
+    ) -> Result<BallotEncrypted, BallotEncryptedError> {
+        let mut csprng = Csprng::new(seed);
+        let ballot_style_ix = BallotStyleIndex::from_one_based_index(1).unwrap();
+        let manifest = &device.header.manifest;
+        let ballot_style = manifest.ballot_styles.get(ballot_style_ix).unwrap();
+
+        let selections: BTreeMap<_, _> = ballot_style
+            .contests
+            .iter()
+            .map(|&contest_ix| {
+                let contest = manifest.contests.get(contest_ix).unwrap();
+                let selection = ContestSelection::new_pick_random(
+                    &mut csprng,
+                    contest.selection_limit,
+                    contest.options.len(),
+                );
+                (contest_ix, selection)
+            })
+            .collect();
+
+        let primary_nonce: [u8; 32] = std::array::from_fn(|_| csprng.next_u8());
+        match opt_precompute {
+            Some(precompute) => BallotEncrypted::new_from_selections_with_precompute(
+                ballot_style_ix,
+                device,
+                "2024-01-01",
+                &mut csprng,
+                &primary_nonce,
+                &selections,
+                precompute,
+            ),
+            None => BallotEncrypted::new_from_selections(
+                ballot_style_ix,
+                device,
+                "2024-01-01",
+                &mut csprng,
+                &primary_nonce,
+                &selections,
+            ),
+        }
+    }
+
+    #[test]
+    fn test_ballot_encryption_precompute() {
+        let device =
+            example_device(&mut Csprng::new(b"test_ballot_encryption_precompute")).unwrap();
+        let fixed_parameters = &device.header.parameters.fixed_parameters;
+        let precompute = BallotEncryptionPrecompute::new(&device.header);
+        assert!(precompute.is_for(&device.header));
+
+        let mut csprng = Csprng::new(b"test_ballot_encryption_precompute nonces");
+        let nonce = fixed_parameters.field.random_field_elem(&mut csprng);
+        assert_eq!(
+            precompute.encrypt_value(fixed_parameters, &nonce, 1),
+            device
+                .header
+                .public_key
+                .encrypt_value(fixed_parameters, &nonce, 1)
+        );
+
+        for seed in [&b"ballot 1"[..], b"ballot 2", b"ballot 3"] {
+            let ballot = encrypt_ballot(&device, seed, None).unwrap();
+            let ballot_precompute = encrypt_ballot(&device, seed, Some(&precompute)).unwrap();
+            assert_eq!(
+                ballot.to_canonical_bytes().unwrap(),
+                ballot_precompute.to_canonical_bytes().unwrap()
+            );
+            assert!(ballot_precompute.verify(&device.header));
+        }
+
+        // Tables for another joint election public key are rejected.
+        let other_device = example_device(&mut Csprng::new(
+            b"test_ballot_encryption_precompute other keys",
+        ))
+        .unwrap();
+        assert!(!precompute.is_for(&other_device.header));
+        assert!(matches!(
+            encrypt_ballot(&other_device, b"ballot 1", Some(&precompute)),
+            Err(BallotEncryptedError::PrecomputeMismatch)
+        ));
+    }
+
+    /// Times encrypting ballots with and without the precompute. Run it with
+    /// `cargo test --release --features eg_benchmarks -- --nocapture bench_`.
+    #[cfg(feature = "eg_benchmarks")]
+    #[test]
+    fn bench_ballot_encryption_precompute() {
+        use std::time::Instant;
+
+        const CNT_BALLOTS: usize = 10;
+
+        let device =
+            example_device(&mut Csprng::new(b"bench_ballot_encryption_precompute")).unwrap();
+        let seeds: Vec<String> = (1..=CNT_BALLOTS).map(|i| format!("ballot {i}")).collect();
+
+        let start = Instant::now();
+        let precompute = BallotEncryptionPrecompute::new(&device.header);
+        let elapsed_precompute = start.elapsed();
+
+        let start = Instant::now();
+        let ballots: Vec<_> = seeds
+            .iter()
+            .map(|seed| encrypt_ballot(&device, seed.as_bytes(), None).unwrap())
+            .collect();
+        let elapsed_standard = start.elapsed();
+
+        let start = Instant::now();
+        let ballots_precompute: Vec<_> = seeds
+            .iter()
+            .map(|seed| encrypt_ballot(&device, seed.as_bytes(), Some(&precompute)).unwrap())
+            .collect();
+        let elapsed_with_precompute = start.elapsed();
+
+        for (ballot, ballot_precompute) in ballots.iter().zip(&ballots_precompute) {
+            assert_eq!(
+                ballot.to_canonical_bytes().unwrap(),
+                ballot_precompute.to_canonical_bytes().unwrap()
+            );
+        }
+
+        eprintln!("Computing the precompute: {elapsed_precompute:?}");
+        eprintln!("Encrypting {CNT_BALLOTS} ballots without precompute: {elapsed_standard:?}");
+        eprintln!("Encrypting {CNT_BALLOTS} ballots with precompute: {elapsed_with_precompute:?}");
+    }
+}
@@ -7,12 +7,17 @@
 
 use std::collections::BTreeSet;
 
-//? use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::election_manifest::ContestIndex;
+use crate::contest_selection::ContestSelection;
+use crate::election_manifest::{
+    ContestIndex, ContestOptionIndex, ElectionManifest, ElectionManifestIndexError,
+};
+use crate::hash::{eg_h, HValue};
 use crate::index::Index;
-use crate::vec1::HasIndexTypeMarker;
+use crate::serializable::{SerializableCanonical, SerializablePretty};
+use crate::vec1::{HasIndexTypeMarker, Vec1};
 
 /// A 1-based index of a `BallotStyle` in the order it is defined in the `ElectionManifest`.
 pub type BallotStyleIndex = Index<BallotStyle>;
@@ -20,12 +25,245 @@ pub type BallotStyleIndex = Index<BallotStyle>;
 /// A ballot style.
 /// TODO: write more?
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "BallotStyleInfo")]
 pub struct BallotStyle {
     /// The label for this ballot style.
     pub label: String,
 
     /// The indices of the `Contest`s which appear on ballots of this style.
+    /// Deserialization rejects a contest listed more than once, rather than silently merging.
     pub contests: BTreeSet<ContestIndex>,
 }
 
+/// A [`BallotStyle`] as deserialized, before its contests are checked to be unique.
+#[derive(Deserialize)]
+struct BallotStyleInfo {
+    label: String,
+    contests: Vec<ContestIndex>,
+}
+
+impl BallotStyleInfo {
+    /// Converts to a [`BallotStyle`], or returns the first contest listed more than once.
+    fn into_ballot_style(self) -> Result<BallotStyle, ContestIndex> {
+        let mut contests = BTreeSet::new();
+        if let Some(&contest_ix) = self
+            .contests
+            .iter()
+            .find(|&&contest_ix| !contests.insert(contest_ix))
+        {
+            return Err(contest_ix);
+        }
+
+        Ok(BallotStyle {
+            label: self.label,
+            contests,
+        })
+    }
+}
+
+impl TryFrom<BallotStyleInfo> for BallotStyle {
+    type Error = String;
+
+    fn try_from(ballot_style_info: BallotStyleInfo) -> Result<Self, Self::Error> {
+        ballot_style_info
+            .into_ballot_style()
+            .map_err(|contest_ix| format!("Contest {contest_ix} is listed more than once."))
+    }
+}
+
+/// Deserializes the ballot styles of an [`ElectionManifest`]. A ballot style listing a contest
+/// more than once is rejected with [`ElectionManifestIndexError::BallotStyleDuplicateContest`].
+pub(crate) fn deserialize_ballot_styles<'de, D>(
+    deserializer: D,
+) -> Result<Vec1<BallotStyle>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    let ballot_style_infos = Vec::<BallotStyleInfo>::deserialize(deserializer)?;
+
+    let ballot_styles = ballot_style_infos
+        .into_iter()
+        .zip(1..)
+        .map(|(ballot_style_info, ix1)| {
+            let ballot_style_ix =
+                BallotStyleIndex::from_one_based_index(ix1).map_err(D::Error::custom)?;
+            ballot_style_info.into_ballot_style().map_err(|contest_ix| {
+                D::Error::custom(ElectionManifestIndexError::BallotStyleDuplicateContest {
+                    ballot_style_ix,
+                    contest_ix,
+                })
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    ballot_styles.try_into().map_err(D::Error::custom)
+}
+
 impl HasIndexTypeMarker for BallotStyle {}
+
+impl SerializableCanonical for BallotStyle {}
+
+impl BallotStyle {
+    /// Returns a digest of the canonical byte representation of the [`BallotStyle`], i.e.,
+    /// `H(0, canonical bytes)`.
+    ///
+    /// It is recorded on each [`BallotEncrypted`](crate::ballot::BallotEncrypted) to bind the
+    /// ballot to the exact ballot style it was created against. Like
+    /// [`ElectionManifest::digest`], it is not part of the spec.
+    pub fn content_hash(&self) -> HValue {
+        // `unwrap()` is justified here because why would JSON serialization fail?
+        #[allow(clippy::unwrap_used)]
+        let canonical_bytes = self.to_canonical_bytes().unwrap();
+        eg_h(&HValue::default(), &canonical_bytes)
+    }
+
+    /// Returns the [`BallotRenderSpec`] for this ballot style, i.e., the contests appearing on
+    /// ballots of this style, in manifest order, each with its options in manifest order and
+    /// the applicable selection limits.
+    pub fn render_spec(&self, election_manifest: &ElectionManifest) -> Result<BallotRenderSpec> {
+        let contests = self
+            .contests
+            .iter()
+            .map(|&contest_index| {
+                let contest = election_manifest.contests.get(contest_index).ok_or_else(|| {
+                    anyhow!(
+                        "Ballot style {:?} contains contest {contest_index} which is not in the election manifest",
+                        self.label
+                    )
+                })?;
+
+                // An option can never receive more votes than the contest allows in total.
+                let option_selection_limit = contest
+                    .selection_limit
+                    .min(ContestSelection::OPTION_SELECTION_LIMIT as usize);
+
+                let options = contest
+                    .options
+                    .indices()
+                    .zip(contest.options.iter())
+                    .map(|(option_index, option)| ContestOptionRenderSpec {
+                        option_index,
+                        label: option.label.clone(),
+                        selection_limit: option_selection_limit,
+                    })
+                    .collect();
+
+                Ok(ContestRenderSpec {
+                    contest_index,
+                    label: contest.label.clone(),
+                    selection_limit: contest.selection_limit,
+                    options,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(BallotRenderSpec {
+            label: self.label.clone(),
+            contests,
+        })
+    }
+}
+
+/// What is needed to render a ballot of a specific [`BallotStyle`], e.g., by a front-end.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BallotRenderSpec {
+    /// The label of the ballot style.
+    pub label: String,
+
+    /// The contests on the ballot, in the order they are defined in the election manifest.
+    pub contests: Vec<ContestRenderSpec>,
+}
+
+impl SerializablePretty for BallotRenderSpec {}
+
+/// A contest as it appears in a [`BallotRenderSpec`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContestRenderSpec {
+    /// The index of the contest in the election manifest.
+    pub contest_index: ContestIndex,
+
+    /// The label of the contest.
+    pub label: String,
+
+    /// The maximum count of options that a voter may select.
+    pub selection_limit: usize,
+
+    /// The options of the contest, in the order they are defined in the election manifest.
+    pub options: Vec<ContestOptionRenderSpec>,
+}
+
+/// A contest option as it appears in a [`BallotRenderSpec`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContestOptionRenderSpec {
+    /// The index of the option within its contest.
+    pub option_index: ContestOptionIndex,
+
+    /// The label of the option.
+    pub label: String,
+
+    /// The maximum count of votes that a voter may apply to this option.
+    pub selection_limit: usize,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::example_election_manifest::example_election_manifest;
+
+    #[test]
+    fn test_render_spec() {
+        let election_manifest = example_election_manifest();
+        let ballot_style = election_manifest
+            .ballot_styles
+            .get(BallotStyleIndex::from_one_based_index(2).unwrap())
+            .unwrap();
+
+        let render_spec = ballot_style.render_spec(&election_manifest).unwrap();
+        assert_eq!(render_spec.label, "Silvërspîre County Ballot");
+
+        let contest_ixs: Vec<u32> = render_spec
+            .contests
+            .iter()
+            .map(|c| c.contest_index.get_one_based_u32())
+            .collect();
+        assert_eq!(contest_ixs, [1, 2, 3, 4, 5, 6, 7, 8, 9, 11]);
+
+        let contest_5 = &render_spec.contests[4];
+        assert_eq!(
+            contest_5.label,
+            "Gränd Cøuncil of Arcáne and Technomägical Affairs"
+        );
+        assert_eq!(contest_5.selection_limit, 3);
+        assert_eq!(contest_5.options.len(), 11);
+        for (ix0, option) in contest_5.options.iter().enumerate() {
+            assert_eq!(option.option_index.get_zero_based_usize(), ix0);
+            assert_eq!(option.selection_limit, 1);
+        }
+        assert_eq!(
+            contest_5.options[1].label,
+            "Èlena Wîndwhisper\n(Technocrat)"
+        );
+
+        let contest_11 = &render_spec.contests[9];
+        let option_labels: Vec<&str> = contest_11
+            .options
+            .iter()
+            .map(|o| o.label.as_str())
+            .collect();
+        assert_eq!(option_labels, ["Retain", "Remove"]);
+
+        // Serializable for a front-end.
+        let json = render_spec.to_json_pretty();
+        assert!(json.contains("\"selection_limit\": 3"));
+
+        // A ballot style referring to a contest missing from the manifest.
+        let mut ballot_style = ballot_style.clone();
+        ballot_style
+            .contests
+            .insert(ContestIndex::from_one_based_index(12).unwrap());
+        assert!(ballot_style.render_spec(&election_manifest).is_err());
+    }
+}
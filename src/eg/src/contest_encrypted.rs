@@ -6,9 +6,12 @@
 #![deny(clippy::manual_assert)]
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use util::{algebra::FieldElement, csprng::Csprng};
 
 use crate::{
+    audit::VERIFICATION_AUDIT_TARGET,
+    ballot_encryption_precompute::BallotEncryptionPrecompute,
     contest_hash,
     contest_selection::ContestSelection,
     device::Device,
@@ -26,6 +29,27 @@ use crate::{
 /// A 1-based index of a [`ContestEncrypted`] in the order it is defined in the [`crate::ballot::BallotEncrypted`].
 pub type ContestEncryptedIndex = Index<ContestEncrypted>;
 
+/// An error pairing an encrypted vote of a [`ContestEncrypted`] with its proof of ballot
+/// correctness.
+#[derive(Error, Debug, PartialEq, Eq, Serialize)]
+pub enum SelectionProofError {
+    /// The contest does not have one proof of ballot correctness per encrypted vote
+    #[error(
+        "The contest has {cnt_proofs} proofs of ballot correctness, but {cnt_selections} encrypted votes."
+    )]
+    ProofCountMismatch {
+        cnt_selections: usize,
+        cnt_proofs: usize,
+    },
+
+    /// The contest has no encrypted vote for the option
+    #[error("The contest has no encrypted vote for option {option_ix}, only {cnt_selections}.")]
+    OptionOutOfRange {
+        option_ix: ContestOptionIndex,
+        cnt_selections: usize,
+    },
+}
+
 /// A contest in an encrypted ballot.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ContestEncrypted {
@@ -71,7 +95,9 @@ impl ContestEncrypted {
         primary_nonce: &[u8],
         contest_index: ContestIndex,
         pt_vote: &ContestSelection,
+        opt_precompute: Option<&BallotEncryptionPrecompute>,
     ) -> Vec<(Ciphertext, Nonce)> {
+        let fixed_parameters = &header.parameters.fixed_parameters;
         // TODO: Check if selection limit is satisfied
 
         let mut vote: Vec<(Ciphertext, Nonce)> = Vec::new();
@@ -79,14 +105,14 @@ impl ContestEncrypted {
             // This is fine since 1 <= j <= Index::VALID_MAX_U32
             let o_idx = ContestOptionIndex::from_one_based_index_unchecked(j as u32);
             let nonce = nonce(header, primary_nonce, contest_index, o_idx);
-            vote.push((
-                header.public_key.encrypt_with(
-                    &header.parameters.fixed_parameters,
-                    &nonce,
-                    pt_vote.get_vote()[j - 1] as usize,
-                ),
-                Nonce::new(nonce),
-            ));
+            let pt = pt_vote.get_vote()[j - 1];
+            let ct = match opt_precompute {
+                Some(precompute) => precompute.encrypt_value(fixed_parameters, &nonce, pt.into()),
+                None => header
+                    .public_key
+                    .encrypt_with(fixed_parameters, &nonce, pt as usize),
+            };
+            vote.push((ct, Nonce::new(nonce)));
         }
         vote
     }
@@ -99,8 +125,35 @@ impl ContestEncrypted {
         contest_index: ContestIndex,
         pt_vote: &ContestSelection,
     ) -> Result<ContestEncrypted, ProofRangeError> {
-        let selection_and_nonce =
-            Self::encrypt_selection(&device.header, primary_nonce, contest_index, pt_vote);
+        Self::new_with_opt_precompute(
+            device,
+            csprng,
+            primary_nonce,
+            contest,
+            contest_index,
+            pt_vote,
+            None,
+        )
+    }
+
+    /// Same as [`ContestEncrypted::new`], but computes the exponentiations with `g` and `K`
+    /// using the [`BallotEncryptionPrecompute`], if any. The result is the same either way.
+    pub(crate) fn new_with_opt_precompute(
+        device: &Device,
+        csprng: &mut Csprng,
+        primary_nonce: &[u8],
+        contest: &Contest,
+        contest_index: ContestIndex,
+        pt_vote: &ContestSelection,
+        opt_precompute: Option<&BallotEncryptionPrecompute>,
+    ) -> Result<ContestEncrypted, ProofRangeError> {
+        let selection_and_nonce = Self::encrypt_selection(
+            &device.header,
+            primary_nonce,
+            contest_index,
+            pt_vote,
+            opt_precompute,
+        );
         let selection = selection_and_nonce
             .iter()
             .map(|(ct, _)| ct.clone())
@@ -112,23 +165,37 @@ impl ContestEncrypted {
             // This is OK, since selection_and_nonce.len() = pt_vote.vote.len() which
             // is guaranteed to not exceed the size of a `Index<T>` by how a `ContestSelection` is
             // constructed.
-            proof_ballot_correctness.push_unchecked(sel.proof_ballot_correctness(
-                &device.header,
-                csprng,
-                pt_vote.get_vote()[i] == 1u8,
-                nonce,
-            )?);
+            proof_ballot_correctness.push_unchecked(
+                sel.proof_ballot_correctness_with_opt_precompute(
+                    &device.header,
+                    csprng,
+                    pt_vote.get_vote()[i] == 1u8,
+                    nonce,
+                    opt_precompute,
+                )?,
+            );
         }
 
         let mut num_selections = 0;
         pt_vote.get_vote().iter().for_each(|v| num_selections += v);
-        let proof_selection_limit = ContestEncrypted::proof_selection_limit(
-            &device.header,
-            csprng,
-            &selection_and_nonce,
-            num_selections as usize,
-            contest.selection_limit,
-        )?;
+        let proof_selection_limit = if contest.exactly_one {
+            ContestEncrypted::proof_exactly_one_with_opt_precompute(
+                &device.header,
+                csprng,
+                &selection_and_nonce,
+                num_selections as usize,
+                opt_precompute,
+            )?
+        } else {
+            ContestEncrypted::proof_selection_limit_with_opt_precompute(
+                &device.header,
+                csprng,
+                &selection_and_nonce,
+                num_selections as usize,
+                contest.selection_limit,
+                opt_precompute,
+            )?
+        };
         Ok(ContestEncrypted {
             selection,
             contest_hash,
@@ -137,6 +204,52 @@ impl ContestEncrypted {
         })
     }
 
+    /// Returns the encrypted vote on the option with the given [`ContestOptionIndex`],
+    /// or `None` if the index is out of range.
+    pub fn get_selection(&self, option_ix: ContestOptionIndex) -> Option<&Ciphertext> {
+        self.selection.get(option_ix.get_zero_based_usize())
+    }
+
+    /// Returns the number of encrypted votes, one for each option of the contest.
+    pub fn cnt_selections(&self) -> usize {
+        self.selection.len()
+    }
+
+    /// Iterates over the encrypted votes along with the [`ContestOptionIndex`] of each.
+    pub fn iter_enumerate_selection(
+        &self,
+    ) -> impl Iterator<Item = (ContestOptionIndex, &Ciphertext)> {
+        ContestOptionIndex::iter_range_inclusive(ContestOptionIndex::MIN, ContestOptionIndex::MAX)
+            .zip(self.selection.iter())
+    }
+
+    /// Returns the encrypted vote on the option with the given [`ContestOptionIndex`] together
+    /// with its proof of ballot correctness.
+    ///
+    /// Fails unless the contest has exactly one proof per encrypted vote, so that a proof is never
+    /// paired with the wrong encrypted vote.
+    pub fn proof_for_selection(
+        &self,
+        option_ix: ContestOptionIndex,
+    ) -> Result<(&Ciphertext, &ProofRange), SelectionProofError> {
+        let cnt_selections = self.cnt_selections();
+        let cnt_proofs = self.proof_ballot_correctness.len();
+        if cnt_proofs != cnt_selections {
+            return Err(SelectionProofError::ProofCountMismatch {
+                cnt_selections,
+                cnt_proofs,
+            });
+        }
+
+        let proof_ix = Index::from_one_based_index_unchecked(option_ix.get_one_based_u32());
+        self.get_selection(option_ix)
+            .zip(self.proof_ballot_correctness.get(proof_ix))
+            .ok_or(SelectionProofError::OptionOutOfRange {
+                option_ix,
+                cnt_selections,
+            })
+    }
+
     pub fn get_proof_ballot_correctness(&self) -> &Vec1<ProofRange> {
         &self.proof_ballot_correctness
     }
@@ -151,23 +264,101 @@ impl ContestEncrypted {
         selection: &[(Ciphertext, Nonce)],
         num_selections: usize,
         selection_limit: usize,
+    ) -> Result<ProofRange, ProofRangeError> {
+        Self::proof_selection_limit_with_opt_precompute(
+            header,
+            csprng,
+            selection,
+            num_selections,
+            selection_limit,
+            None,
+        )
+    }
+
+    /// Same as [`ContestEncrypted::proof_selection_limit`], using the
+    /// [`BallotEncryptionPrecompute`], if any.
+    fn proof_selection_limit_with_opt_precompute(
+        header: &PreVotingData,
+        csprng: &mut Csprng,
+        selection: &[(Ciphertext, Nonce)],
+        num_selections: usize,
+        selection_limit: usize,
+        opt_precompute: Option<&BallotEncryptionPrecompute>,
     ) -> Result<ProofRange, ProofRangeError> {
         let (combined_ct, combined_nonce) =
             Self::sum_selection_nonce_vector(&header.parameters.fixed_parameters, selection);
-        ProofRange::new(
+        ProofRange::new_with_opt_precompute(
             header,
             csprng,
             &combined_ct,
             &combined_nonce,
             num_selections,
             selection_limit,
+            opt_precompute,
         )
     }
 
+    /// Computes the proof that exactly one option is selected, for a contest with
+    /// [`Contest::exactly_one`] set. This is a range proof with range bound `0` for the sum of
+    /// the encrypted votes with one subtracted, see [`Self::sub_one`].
+    pub fn proof_exactly_one(
+        header: &PreVotingData,
+        csprng: &mut Csprng,
+        selection: &[(Ciphertext, Nonce)],
+        num_selections: usize,
+    ) -> Result<ProofRange, ProofRangeError> {
+        Self::proof_exactly_one_with_opt_precompute(header, csprng, selection, num_selections, None)
+    }
+
+    /// Same as [`ContestEncrypted::proof_exactly_one`], using the
+    /// [`BallotEncryptionPrecompute`], if any.
+    fn proof_exactly_one_with_opt_precompute(
+        header: &PreVotingData,
+        csprng: &mut Csprng,
+        selection: &[(Ciphertext, Nonce)],
+        num_selections: usize,
+        opt_precompute: Option<&BallotEncryptionPrecompute>,
+    ) -> Result<ProofRange, ProofRangeError> {
+        if num_selections != 1 {
+            return Err(ProofRangeError::NotExactlyOne {
+                small_l: num_selections,
+            });
+        }
+        let fixed_parameters = &header.parameters.fixed_parameters;
+        let (combined_ct, combined_nonce) =
+            Self::sum_selection_nonce_vector(fixed_parameters, selection);
+        let ct = Self::sub_one(header, &combined_ct)?;
+        ProofRange::new_with_opt_precompute(
+            header,
+            csprng,
+            &ct,
+            &combined_nonce,
+            0,
+            0,
+            opt_precompute,
+        )
+    }
+
+    /// Subtracts one from the number encrypted by a ciphertext, i.e., divides `β` by `K`.
+    /// The nonce of the ciphertext is unchanged.
+    ///
+    /// Fails if `K` has no inverse, which can happen only if it is not a valid group element.
+    fn sub_one(header: &PreVotingData, ct: &Ciphertext) -> Result<Ciphertext, ProofRangeError> {
+        let group = &header.parameters().fixed_parameters.group;
+        let k = &header.joint_public_key().joint_election_public_key;
+        let k_inv = k
+            .inv(group)
+            .ok_or(ProofRangeError::JointPublicKeyNotInvertible)?;
+        Ok(Ciphertext {
+            alpha: ct.alpha.clone(),
+            beta: ct.beta.mul(&k_inv, group),
+        })
+    }
+
     /// Verify the proof that the selection limit is satisfied.
-    fn verify_selection_limit(&self, header: &PreVotingData, selection_limit: usize) -> bool {
+    pub fn verify_selection_limit(&self, header: &PreVotingData, selection_limit: usize) -> bool {
         let combined_ct =
-            Self::sum_selection_vector(&header.parameters.fixed_parameters, &self.selection);
+            Self::sum_selection_vector(&header.parameters().fixed_parameters, &self.selection);
         ProofRange::verify(
             &self.proof_selection_limit,
             header,
@@ -176,6 +367,16 @@ impl ContestEncrypted {
         )
     }
 
+    /// Verify the proof that exactly one option is selected.
+    pub fn verify_exactly_one(&self, header: &PreVotingData) -> bool {
+        let combined_ct =
+            Self::sum_selection_vector(&header.parameters().fixed_parameters, &self.selection);
+        let Ok(ct) = Self::sub_one(header, &combined_ct) else {
+            return false;
+        };
+        ProofRange::verify(&self.proof_selection_limit, header, &ct, 0)
+    }
+
     /// Sum up the encrypted votes on a contest and their nonces. The sum of the nonces can be used
     /// to proof properties about the sum of the ciphertexts, e.g. that it satisfies the selection
     /// limit.
@@ -215,22 +416,92 @@ impl ContestEncrypted {
         sum_ct
     }
 
+    /// Verifies the proof of ballot correctness of every encrypted vote, i.e., that it is an
+    /// encryption of 0 or 1. Unlike [`Self::verify`], this does not stop at the first failure,
+    /// but returns the indices of all the options whose proofs fail, in order, to pinpoint the
+    /// corruption within a contest. An option without a proof, or with an unpaired one, fails.
+    pub fn verify_ballot_correctness_detailed(
+        &self,
+        header: &PreVotingData,
+    ) -> Result<(), Vec<ContestOptionIndex>> {
+        let failed_option_ixs: Vec<_> = self
+            .iter_enumerate_selection()
+            .filter(|&(option_ix, _)| {
+                !self
+                    .proof_for_selection(option_ix)
+                    .is_ok_and(|(ct, proof)| ct.verify_ballot_correctness(header, proof))
+            })
+            .map(|(option_ix, _)| option_ix)
+            .collect();
+
+        if failed_option_ixs.is_empty() {
+            Ok(())
+        } else {
+            Err(failed_option_ixs)
+        }
+    }
+
     /// Verify the proof that each encrypted vote is an encryption of 0 or 1,
-    /// and that the selection limit is satisfied.
-    pub fn verify(&self, header: &PreVotingData, selection_limit: usize) -> bool {
-        for (ct, j) in self.selection.iter().zip(1..) {
-            let Ok(idx) = Index::from_one_based_index(j) else {
-                return false;
-            };
-            let Some(proof) = self.proof_ballot_correctness.get(idx) else {
-                return false;
-            };
-            if !ct.verify_ballot_correctness(header, proof) {
+    /// and that the selection limit of the contest is satisfied. For a contest with
+    /// [`Contest::exactly_one`] set, verifies instead that exactly one option is selected.
+    ///
+    /// Each sub-check emits an audit event, see [`crate::audit`], identifying the contest by
+    /// `contest_index` and the ballot it is on by its confirmation code `ballot`.
+    pub fn verify(
+        &self,
+        header: &PreVotingData,
+        ballot: &HValue,
+        contest_index: ContestIndex,
+        contest: &Contest,
+    ) -> bool {
+        let contest_hash = &self.contest_hash;
+
+        if ContestOptionIndex::VALID_MAX_USIZE < self.cnt_selections() {
+            return false;
+        }
+        for (option_ix, _) in self.iter_enumerate_selection() {
+            let passed = self
+                .proof_for_selection(option_ix)
+                .is_ok_and(|(ct, proof)| ct.verify_ballot_correctness(header, proof));
+            tracing::info!(
+                target: VERIFICATION_AUDIT_TARGET,
+                check = "ballot_correctness",
+                %ballot,
+                contest = %contest_index,
+                %contest_hash,
+                option = %option_ix,
+                passed
+            );
+            if !passed {
                 return false;
             }
         }
 
-        self.verify_selection_limit(header, selection_limit)
+        if contest.exactly_one {
+            let passed = self.verify_exactly_one(header);
+            tracing::info!(
+                target: VERIFICATION_AUDIT_TARGET,
+                check = "exactly_one",
+                %ballot,
+                contest = %contest_index,
+                %contest_hash,
+                passed
+            );
+            return passed;
+        }
+
+        let selection_limit = contest.selection_limit;
+        let passed = self.verify_selection_limit(header, selection_limit);
+        tracing::info!(
+            target: VERIFICATION_AUDIT_TARGET,
+            check = "selection_limit",
+            %ballot,
+            contest = %contest_index,
+            %contest_hash,
+            selection_limit,
+            passed
+        );
+        passed
     }
 
     /// Scales all the encrypted votes on the contest by the same factor.
@@ -246,4 +517,146 @@ impl ContestEncrypted {
             .collect();
         ScaledContestEncrypted { selection }
     }
+
+    /// Gives the encrypted votes on the contest scaled by the factor `1`, i.e., unchanged.
+    /// This is equal to [`ContestEncrypted::scale`] with a unit factor, without performing any
+    /// exponentiations.
+    pub fn as_scaled_unit(&self) -> ScaledContestEncrypted {
+        ScaledContestEncrypted {
+            selection: self.selection.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::example_pre_voting_data::toy_device;
+
+    #[test]
+    fn test_selection_by_option_index() {
+        let mut csprng = Csprng::new(b"test_selection_by_option_index");
+        let device = toy_device(&mut csprng);
+        let (_, contest, contest_encrypted) =
+            encrypt_contest_with_three_options(&mut csprng, &device);
+        let cnt_selections = contest.options.len();
+        let selection = &contest_encrypted.selection;
+
+        assert_eq!(contest_encrypted.cnt_selections(), cnt_selections);
+
+        let option_ix_2 = ContestOptionIndex::from_one_based_index(2).unwrap();
+        assert_eq!(
+            contest_encrypted.get_selection(option_ix_2),
+            Some(&selection[1])
+        );
+
+        let option_ix_past_end =
+            ContestOptionIndex::from_one_based_index(cnt_selections as u32 + 1).unwrap();
+        assert_eq!(contest_encrypted.get_selection(option_ix_past_end), None);
+
+        let enumerated: Vec<_> = contest_encrypted
+            .iter_enumerate_selection()
+            .map(|(option_ix, ct)| (option_ix.get_one_based_u32() as usize, ct))
+            .collect();
+        let expected: Vec<_> = (1..=cnt_selections).zip(selection).collect();
+        assert_eq!(enumerated, expected);
+    }
+
+    /// Encrypts a random vote on the first contest of the manifest with at least three options.
+    fn encrypt_contest_with_three_options<'a>(
+        csprng: &mut Csprng,
+        device: &'a Device,
+    ) -> (ContestIndex, &'a Contest, ContestEncrypted) {
+        let (contest_index, contest) = device
+            .header
+            .manifest
+            .contests
+            .indices()
+            .zip(device.header.manifest.contests.iter())
+            .find(|(_, contest)| 3 <= contest.options.len())
+            .unwrap();
+        let pt_vote = ContestSelection::new_pick_random(
+            csprng,
+            contest.selection_limit,
+            contest.options.len(),
+        );
+        let contest_encrypted =
+            ContestEncrypted::new(device, csprng, &[5u8; 32], contest, contest_index, &pt_vote)
+                .unwrap();
+        (contest_index, contest, contest_encrypted)
+    }
+
+    #[test]
+    fn test_proof_for_selection() {
+        let mut csprng = Csprng::new(b"test_proof_for_selection");
+        let device = toy_device(&mut csprng);
+        let (_, contest, mut contest_encrypted) =
+            encrypt_contest_with_three_options(&mut csprng, &device);
+        let cnt_selections = contest.options.len();
+
+        // The encrypted vote on option 2 is paired with the second proof.
+        let option_ix_2 = ContestOptionIndex::from_one_based_index(2).unwrap();
+        let (ct, proof) = contest_encrypted.proof_for_selection(option_ix_2).unwrap();
+        assert!(std::ptr::eq(ct, &contest_encrypted.selection[1]));
+        let proof_ix_2 = Index::from_one_based_index(2).unwrap();
+        assert!(std::ptr::eq(
+            proof,
+            contest_encrypted
+                .proof_ballot_correctness
+                .get(proof_ix_2)
+                .unwrap()
+        ));
+
+        let option_ix_past_end =
+            ContestOptionIndex::from_one_based_index(cnt_selections as u32 + 1).unwrap();
+        assert_eq!(
+            contest_encrypted
+                .proof_for_selection(option_ix_past_end)
+                .unwrap_err(),
+            (SelectionProofError::OptionOutOfRange {
+                option_ix: option_ix_past_end,
+                cnt_selections,
+            })
+        );
+
+        // With a proof missing, no proof is paired with an encrypted vote.
+        contest_encrypted.proof_ballot_correctness.pop();
+        assert_eq!(
+            contest_encrypted
+                .proof_for_selection(option_ix_2)
+                .unwrap_err(),
+            (SelectionProofError::ProofCountMismatch {
+                cnt_selections,
+                cnt_proofs: cnt_selections - 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_ballot_correctness_detailed() {
+        let mut csprng = Csprng::new(b"test_verify_ballot_correctness_detailed");
+        let device = toy_device(&mut csprng);
+        let (contest_ix, contest, mut contest_encrypted) =
+            encrypt_contest_with_three_options(&mut csprng, &device);
+        assert_eq!(
+            contest_encrypted.verify_ballot_correctness_detailed(&device.header),
+            Ok(())
+        );
+
+        // Tamper with the ciphertexts of options 1 and 3.
+        let group = &device.header.parameters.fixed_parameters.group;
+        for option_ix in [1, 3] {
+            let ct = &mut contest_encrypted.selection[option_ix - 1];
+            ct.beta = ct.beta.mul(&group.generator(), group);
+        }
+        assert_eq!(
+            contest_encrypted.verify_ballot_correctness_detailed(&device.header),
+            Err(vec![
+                ContestOptionIndex::from_one_based_index(1).unwrap(),
+                ContestOptionIndex::from_one_based_index(3).unwrap(),
+            ])
+        );
+        assert!(!contest_encrypted.verify(&device.header, &HValue::default(), contest_ix, contest));
+    }
 }
@@ -11,12 +11,18 @@ use crate::{
     election_record::PreVotingData,
     hash::{eg_h, HValue},
     joint_election_public_key::Ciphertext,
+    serializable::SerializableCanonical,
 };
 
 /// Contest hash for encrypted ballots (Equation 58)
 ///
-/// χl = H(H_E;23,Λ_l,K,α_1,β_1,α_2,β_2 ...,α_m,β_m),
+/// χl = H(H_E;23,Λ_l,K,α_1,β_1,α_2,β_2 ...,α_m,β_m,O_1,O_2,...,O_n),
 ///
+/// where `O_i = b(len(C_i), 4) | C_i` for the canonical bytes `C_i` of the `i`-th option of the
+/// contest in the election manifest, in manifest order. This extends Equation 58 so that the
+/// metadata of an option, such as its party and external identifier, is bound to the contest
+/// hash and cannot be altered undetected. An option without metadata contributes only its label.
+/// A contest not in the manifest contributes no options.
 pub fn contest_hash(
     header: &PreVotingData,
     contest_index: ContestIndex,
@@ -39,5 +45,78 @@ pub fn contest_hash(
         v.extend_from_slice(vote_i.beta.to_be_bytes_left_pad(group).as_slice());
     });
 
+    // B1 = ... | b(len(C_1), 4) | C_1 | · · · | b(len(C_n), 4) | C_n
+    if let Some(contest) = header.manifest.contests.get(contest_index) {
+        for option in contest.options.iter() {
+            // `unwrap()` is justified here because why would JSON serialization fail?
+            #[allow(clippy::unwrap_used)]
+            let canonical_bytes = option.to_canonical_bytes().unwrap();
+            v.extend_from_slice(&(canonical_bytes.len() as u32).to_be_bytes());
+            v.extend_from_slice(&canonical_bytes);
+        }
+    }
+
     eg_h(&header.hashes_ext.h_e, &v)
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{
+        election_manifest::ContestOptionIndex, example_pre_voting_data::example_pre_voting_data,
+        ident::Ident,
+    };
+
+    #[test]
+    fn test_contest_hash_option_metadata() {
+        let mut csprng = Csprng::new(b"test_contest_hash_option_metadata");
+        let mut header = example_pre_voting_data(&mut csprng).unwrap();
+
+        let contest_ix_1 = ContestIndex::from_one_based_index(1).unwrap();
+        let contest_ix_2 = ContestIndex::from_one_based_index(2).unwrap();
+        let option_ix = ContestOptionIndex::from_one_based_index(1).unwrap();
+        let vote: Vec<Ciphertext> = (0..2)
+            .map(|_| {
+                let nonce = header
+                    .parameters
+                    .fixed_parameters
+                    .field
+                    .random_field_elem(&mut csprng);
+                header
+                    .public_key
+                    .encrypt_with(&header.parameters.fixed_parameters, &nonce, 0)
+            })
+            .collect();
+
+        let chi_1 = contest_hash(&header, contest_ix_1, &vote);
+        let chi_2 = contest_hash(&header, contest_ix_2, &vote);
+
+        // Stable for an unchanged manifest.
+        assert_eq!(contest_hash(&header, contest_ix_1, &vote), chi_1);
+
+        // Changes with the metadata of an option of the contest, but not of another contest.
+        let contest_1 = header.manifest.contests.get_mut(contest_ix_1).unwrap();
+        let option = contest_1.options.get_mut(option_ix).unwrap();
+        option.party = Some("Independent".to_string());
+        let chi_1_party = contest_hash(&header, contest_ix_1, &vote);
+        assert_ne!(chi_1_party, chi_1);
+        assert_eq!(contest_hash(&header, contest_ix_2, &vote), chi_2);
+
+        let contest_1 = header.manifest.contests.get_mut(contest_ix_1).unwrap();
+        let option = contest_1.options.get_mut(option_ix).unwrap();
+        option.external_id = Some(Ident::try_from_str("cand-0001").unwrap());
+        let chi_1_external_id = contest_hash(&header, contest_ix_1, &vote);
+        assert_ne!(chi_1_external_id, chi_1_party);
+        assert_ne!(chi_1_external_id, chi_1);
+
+        // Removing the metadata restores the contest hash.
+        let contest_1 = header.manifest.contests.get_mut(contest_ix_1).unwrap();
+        let option = contest_1.options.get_mut(option_ix).unwrap();
+        option.party = None;
+        option.external_id = None;
+        assert_eq!(contest_hash(&header, contest_ix_1, &vote), chi_1);
+    }
+}
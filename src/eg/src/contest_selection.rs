@@ -6,10 +6,12 @@
 #![deny(clippy::manual_assert)]
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use util::csprng::Csprng;
 
 use crate::{
+    ballot_encryption_precompute::BallotEncryptionPrecompute,
     election_manifest::Contest,
     election_record::PreVotingData,
     index::Index,
@@ -24,22 +26,96 @@ pub type ContestSelectionPlaintext = u8;
 pub type ContestSelectionIndex = Index<ContestSelection>;
 
 /// A contest selection by a voter.
+///
+/// Deserialization performs the checks of [`ContestSelection::try_new`].
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(try_from = "ContestSelectionUnchecked")]
 pub struct ContestSelection {
     /// Vector used to represent the selection
     vote: Vec<ContestSelectionPlaintext>,
 }
 
+/// The serialized form of a [`ContestSelection`], before its checks.
+#[derive(Deserialize)]
+struct ContestSelectionUnchecked {
+    vote: Vec<ContestSelectionPlaintext>,
+}
+
+impl TryFrom<ContestSelectionUnchecked> for ContestSelection {
+    type Error = ContestSelectionError;
+
+    fn try_from(unchecked: ContestSelectionUnchecked) -> Result<Self, Self::Error> {
+        Self::try_new(unchecked.vote)
+    }
+}
+
 impl HasIndexType for ContestSelection {
     type IndexType = Contest;
 }
 
+/// Represents errors occurring when constructing a [`ContestSelection`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ContestSelectionError {
+    /// Occurs if there are more options than can be indexed.
+    #[error("The selection has {len} options, more than the maximum of {max}.")]
+    TooManyOptions { len: usize, max: usize },
+
+    /// Occurs if the value for an option exceeds the number of votes an option may receive.
+    #[error("The value {value} for option {option} exceeds the option selection limit of {max}.")]
+    ValueOutOfRange {
+        option: usize,
+        value: ContestSelectionPlaintext,
+        max: ContestSelectionPlaintext,
+    },
+
+    /// Occurs if the packed bits of a [`ContestSelectionPacked`] are not hex digits.
+    #[error("The packed bits of the selection are not hex digits.")]
+    PackedBitsNotHex,
+
+    /// Occurs if a [`ContestSelectionPacked`] doesn't have one byte per eight options.
+    #[error("The packed selection has {len} options, but {cnt_bytes} bytes of packed bits.")]
+    PackedBitsLengthMismatch { len: usize, cnt_bytes: usize },
+
+    /// Occurs if a [`ContestSelectionPacked`] has bits set beyond its last option.
+    #[error("The packed selection has {len} options, but bits set beyond the last option.")]
+    PackedBitsPaddingNotZero { len: usize },
+}
+
 impl ContestSelection {
+    /// The maximum value for a single option, i.e., the options are approval style and
+    /// each is either selected (`1`) or not (`0`). This matches the range proofs on the
+    /// encrypted selections.
+    pub const OPTION_SELECTION_LIMIT: ContestSelectionPlaintext = 1;
+
+    /// Creates a new `ContestSelection`, returning `None` if the selection is invalid.
+    /// See [`ContestSelection::try_new`].
     pub fn new(vote: Vec<ContestSelectionPlaintext>) -> Option<ContestSelection> {
-        if vote.len() > Index::<ContestSelectionPlaintext>::VALID_MAX_USIZE {
-            return None;
+        Self::try_new(vote).ok()
+    }
+
+    /// Creates a new `ContestSelection`, verifying that there are not too many options
+    /// and that the value for each option is within [`ContestSelection::OPTION_SELECTION_LIMIT`].
+    pub fn try_new(
+        vote: Vec<ContestSelectionPlaintext>,
+    ) -> Result<ContestSelection, ContestSelectionError> {
+        let max = Index::<ContestSelectionPlaintext>::VALID_MAX_USIZE;
+        if vote.len() > max {
+            return Err(ContestSelectionError::TooManyOptions {
+                len: vote.len(),
+                max,
+            });
+        }
+
+        let max = Self::OPTION_SELECTION_LIMIT;
+        if let Some((ix, &value)) = vote.iter().enumerate().find(|(_, &value)| max < value) {
+            return Err(ContestSelectionError::ValueOutOfRange {
+                option: ix + 1,
+                value,
+                max,
+            });
         }
-        Some(ContestSelection { vote })
+
+        Ok(ContestSelection { vote })
     }
 
     pub fn get_vote(&self) -> &[ContestSelectionPlaintext] {
@@ -68,6 +144,86 @@ impl ContestSelection {
 
         Self { vote }
     }
+
+    /// Returns the compact serialization of the selection, see [`ContestSelectionPacked`].
+    pub fn to_packed(&self) -> ContestSelectionPacked {
+        const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+        let mut bytes = vec![0u8; self.vote.len().div_ceil(8)];
+        for (ix, &value) in self.vote.iter().enumerate() {
+            // The values are at most `OPTION_SELECTION_LIMIT`, i.e., `0` or `1`.
+            bytes[ix / 8] |= value << (ix % 8);
+        }
+
+        ContestSelectionPacked {
+            len: self.vote.len(),
+            bits: bytes
+                .iter()
+                .flat_map(|&byte| {
+                    [
+                        HEX_DIGITS[(byte >> 4) as usize],
+                        HEX_DIGITS[(byte & 0x0F) as usize],
+                    ]
+                })
+                .map(char::from)
+                .collect(),
+        }
+    }
+}
+
+/// A compact serialization of a [`ContestSelection`], e.g. for voter selection files.
+///
+/// As the options are approval style, the value of each option is a single bit. The bits are
+/// packed into bytes, least significant bit first, and written as uppercase hex digits, along
+/// with the count of options. This is for selection plaintexts only, ciphertexts can't be packed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContestSelectionPacked {
+    /// The count of options.
+    pub len: usize,
+
+    /// The values of the options, packed into bits and written as hex digits.
+    pub bits: String,
+}
+
+impl ContestSelectionPacked {
+    /// Unpacks the [`ContestSelection`], checking that there is exactly one bit per option and
+    /// the remaining bits of the last byte are zero, as well as the checks of
+    /// [`ContestSelection::try_new`].
+    pub fn try_unpack(&self) -> Result<ContestSelection, ContestSelectionError> {
+        let bits = self.bits.as_bytes();
+        if !bits.len().is_multiple_of(2) || !bits.iter().all(u8::is_ascii_hexdigit) {
+            return Err(ContestSelectionError::PackedBitsNotHex);
+        }
+
+        let bytes = bits
+            .chunks_exact(2)
+            .map(|hex_digits| {
+                std::str::from_utf8(hex_digits)
+                    .ok()
+                    .and_then(|hex_digits| u8::from_str_radix(hex_digits, 16).ok())
+                    .ok_or(ContestSelectionError::PackedBitsNotHex)
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        let len = self.len;
+        if bytes.len() != len.div_ceil(8) {
+            return Err(ContestSelectionError::PackedBitsLengthMismatch {
+                len,
+                cnt_bytes: bytes.len(),
+            });
+        }
+
+        if let Some(&last_byte) = bytes.last() {
+            let cnt_bits_used = len - (bytes.len() - 1) * 8;
+            if cnt_bits_used < 8 && last_byte >> cnt_bits_used != 0 {
+                return Err(ContestSelectionError::PackedBitsPaddingNotZero { len });
+            }
+        }
+
+        let vote = (0..len).map(|ix| (bytes[ix / 8] >> (ix % 8)) & 1).collect();
+
+        ContestSelection::try_new(vote)
+    }
 }
 
 impl Ciphertext {
@@ -78,7 +234,28 @@ impl Ciphertext {
         selected: bool,
         nonce: &Nonce,
     ) -> Result<ProofRange, ProofRangeError> {
-        ProofRange::new(header, csprng, self, nonce, selected as usize, 1)
+        self.proof_ballot_correctness_with_opt_precompute(header, csprng, selected, nonce, None)
+    }
+
+    /// Same as [`Ciphertext::proof_ballot_correctness`], using the
+    /// [`BallotEncryptionPrecompute`], if any.
+    pub(crate) fn proof_ballot_correctness_with_opt_precompute(
+        &self,
+        header: &PreVotingData,
+        csprng: &mut Csprng,
+        selected: bool,
+        nonce: &Nonce,
+        opt_precompute: Option<&BallotEncryptionPrecompute>,
+    ) -> Result<ProofRange, ProofRangeError> {
+        ProofRange::new_with_opt_precompute(
+            header,
+            csprng,
+            self,
+            nonce,
+            selected as usize,
+            1,
+            opt_precompute,
+        )
     }
 
     /// Verify the proof that the cipher text is an encryption of 0 or 1.
@@ -86,3 +263,100 @@ impl Ciphertext {
         proof.verify(header, self, 1)
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_contest_selection_values() {
+        for vote in [vec![], vec![0], vec![1], vec![0, 1, 0], vec![1, 1, 1]] {
+            let selection = ContestSelection::try_new(vote.clone()).unwrap();
+            assert_eq!(selection.get_vote(), vote.as_slice());
+        }
+
+        assert_eq!(
+            ContestSelection::try_new(vec![0, 1, 2]).unwrap_err(),
+            ContestSelectionError::ValueOutOfRange {
+                option: 3,
+                value: 2,
+                max: 1
+            }
+        );
+        assert!(ContestSelection::new(vec![2, 0]).is_none());
+    }
+
+    #[test]
+    fn test_contest_selection_deserialize() {
+        let selection: ContestSelection = serde_json::from_str(r#"{"vote":[0,1,0]}"#).unwrap();
+        assert_eq!(selection.get_vote(), [0, 1, 0]);
+        assert_eq!(
+            serde_json::to_string(&selection).unwrap(),
+            r#"{"vote":[0,1,0]}"#
+        );
+
+        // A value out of range is rejected, as by `ContestSelection::try_new`.
+        let err = serde_json::from_str::<ContestSelection>(r#"{"vote":[0,2,0]}"#).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("The value 2 for option 2 exceeds the option selection limit of 1."),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_contest_selection_packed() {
+        let selection = ContestSelection::try_new(vec![1, 0, 0, 1, 0, 0, 0, 0, 0, 1]).unwrap();
+
+        let packed = selection.to_packed();
+        assert_eq!(
+            packed,
+            ContestSelectionPacked {
+                len: 10,
+                bits: "0902".to_string()
+            }
+        );
+        assert_eq!(
+            packed.try_unpack().unwrap().get_vote(),
+            selection.get_vote()
+        );
+
+        // Round trip through JSON, which is smaller than that of the unpacked selection.
+        let json_packed = serde_json::to_string(&packed).unwrap();
+        let json_unpacked = serde_json::to_string(&selection).unwrap();
+        assert!(json_packed.len() < json_unpacked.len());
+        let unpacked = serde_json::from_str::<ContestSelectionPacked>(&json_packed)
+            .unwrap()
+            .try_unpack()
+            .unwrap();
+        assert_eq!(serde_json::to_string(&unpacked).unwrap(), json_unpacked);
+
+        for vote in [vec![], vec![0; 8], vec![1; 8], vec![1; 9]] {
+            let selection = ContestSelection::try_new(vote.clone()).unwrap();
+            assert_eq!(selection.to_packed().try_unpack().unwrap().get_vote(), vote);
+        }
+
+        let unpack = |len, bits: &str| {
+            ContestSelectionPacked {
+                len,
+                bits: bits.to_string(),
+            }
+            .try_unpack()
+            .unwrap_err()
+        };
+        assert_eq!(
+            unpack(10, "09"),
+            ContestSelectionError::PackedBitsLengthMismatch {
+                len: 10,
+                cnt_bytes: 1
+            }
+        );
+        assert_eq!(
+            unpack(10, "0906"),
+            ContestSelectionError::PackedBitsPaddingNotZero { len: 10 }
+        );
+        assert_eq!(unpack(10, "09G2"), ContestSelectionError::PackedBitsNotHex);
+        assert_eq!(unpack(10, "090"), ContestSelectionError::PackedBitsNotHex);
+    }
+}
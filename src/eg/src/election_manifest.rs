@@ -5,17 +5,111 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
-use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use crate::ballot_style::BallotStyle;
+use crate::ballot_style::{deserialize_ballot_styles, BallotStyle, BallotStyleIndex};
+use crate::fixed_parameters::FixedParameters;
+use crate::hash::{eg_h, HValue};
+use crate::ident::Ident;
 use crate::index::Index;
+use crate::manifest_lint::{manifest_problems, ManifestLintSeverity};
+use crate::schema_version::{
+    initial_schema_version, is_initial_schema_version, SchemaVersion, INITIAL_SCHEMA_VERSION,
+};
 use crate::serializable::{SerializableCanonical, SerializablePretty};
 use crate::vec1::{HasIndexTypeMarker, Vec1};
 
+/// The version of the schema of [`ElectionManifest`] written and supported by this library.
+pub const ELECTION_MANIFEST_SCHEMA_VERSION: SchemaVersion = INITIAL_SCHEMA_VERSION;
+
+/// The default maximum count of [`Contest`]s in an [`ElectionManifest`].
+pub const MANIFEST_MAX_CONTESTS: usize = 10_000;
+
+/// The default maximum count of [`ContestOption`]s in a [`Contest`].
+pub const CONTEST_MAX_OPTIONS: usize = 1_000;
+
+/// Limits on the size of an [`ElectionManifest`], enforced by
+/// [`ElectionManifest::validate_with_limits`].
+///
+/// The defaults are far beyond any realistic election, but keep a pathological manifest from
+/// producing unmanageable ballots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElectionManifestLimits {
+    /// The maximum count of contests in the manifest.
+    pub max_contests: usize,
+
+    /// The maximum count of options in a contest.
+    pub max_options_per_contest: usize,
+
+    /// Whether a ballot style without contests, which yields empty ballots and is almost
+    /// certainly a mistake, only emits a warning instead of being rejected.
+    pub allow_empty_ballot_styles: bool,
+}
+
+impl Default for ElectionManifestLimits {
+    fn default() -> Self {
+        Self {
+            max_contests: MANIFEST_MAX_CONTESTS,
+            max_options_per_contest: CONTEST_MAX_OPTIONS,
+            allow_empty_ballot_styles: false,
+        }
+    }
+}
+
+/// Represents errors occurring if an [`ElectionManifest`] exceeds its [`ElectionManifestLimits`].
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ElectionManifestLimitError {
+    /// Occurs if the manifest has more contests than allowed.
+    #[error("The election manifest has {cnt} contests, exceeding the maximum of {max}.")]
+    TooManyContests { cnt: usize, max: usize },
+
+    /// Occurs if a contest has more options than allowed.
+    #[error("Contest {contest_ix} has {cnt} options, exceeding the maximum of {max}.")]
+    TooManyOptions {
+        contest_ix: ContestIndex,
+        cnt: usize,
+        max: usize,
+    },
+
+    /// Occurs if a ballot style has no contests, unless allowed.
+    #[error("Ballot style {ballot_style_ix} has no contests.")]
+    BallotStyleHasNoContests { ballot_style_ix: BallotStyleIndex },
+}
+
+/// Represents errors occurring if an index refers to an item not in the [`ElectionManifest`],
+/// as found by [`ElectionManifest::validate_indices`].
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ElectionManifestIndexError {
+    /// Occurs if a contest index is not that of a contest in the manifest.
+    #[error("Contest {contest_ix} is not in the election manifest.")]
+    ContestNotInManifest { contest_ix: ContestIndex },
+
+    /// Occurs if a ballot style index is not that of a ballot style in the manifest.
+    #[error("Ballot style {ballot_style_ix} is not in the election manifest.")]
+    BallotStyleNotInManifest { ballot_style_ix: BallotStyleIndex },
+
+    /// Occurs if a ballot style lists a contest more than once.
+    #[error("Ballot style {ballot_style_ix} lists contest {contest_ix} more than once.")]
+    BallotStyleDuplicateContest {
+        ballot_style_ix: BallotStyleIndex,
+        contest_ix: ContestIndex,
+    },
+}
+
 /// The election manifest.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ElectionManifest {
+    /// The version of the schema of the manifest, see [`crate::schema_version`].
+    #[serde(
+        default = "initial_schema_version",
+        skip_serializing_if = "is_initial_schema_version"
+    )]
+    pub schema_version: SchemaVersion,
+
     /// A descriptive label for this election.
     pub label: String,
 
@@ -28,10 +122,17 @@ pub struct ElectionManifest {
     //? TODO
     //
     /// All the [`BallotStyle`]s of the election.
+    #[serde(deserialize_with = "deserialize_ballot_styles")]
     pub ballot_styles: Vec1<BallotStyle>,
 }
 
 impl ElectionManifest {
+    /// Reads an [`ElectionManifest`] from a [`std::io::Read`] without validating it, e.g., for
+    /// linting. Use [`Self::from_stdioread_validated`] for a manifest to be used.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading ElectionManifest")
+    }
+
     /// Reads an [`ElectionManifest`] from a [`std::io::Read`] and validates it.
     /// It can be either the canonical or pretty JSON representation.
     pub fn from_stdioread_validated(stdioread: &mut dyn std::io::Read) -> Result<Self> {
@@ -42,16 +143,235 @@ impl ElectionManifest {
         Ok(self_)
     }
 
-    /// Validates that the [`ElectionManifest`] is well-formed.
+    /// Reads an [`ElectionManifest`] from a byte slice and validates it.
+    /// It can be either the canonical or pretty JSON representation.
+    ///
+    /// Parsing is done directly from the borrowed bytes (e.g., a memory-mapped file),
+    /// avoiding a copy into an intermediate buffer.
+    pub fn from_bytes_validated(bytes: &[u8]) -> Result<Self> {
+        let self_: Self = serde_json::from_slice(bytes).context("Reading ElectionManifest")?;
+
+        self_.validate()?;
+
+        Ok(self_)
+    }
+
+    /// Validates that the [`ElectionManifest`] is well-formed, and within the default
+    /// [`ElectionManifestLimits`].
     /// Useful after deserialization.
     pub fn validate(&self) -> Result<()> {
-        // We currently have no validation rules for this type.
+        self.validate_with_limits(&ElectionManifestLimits::default())
+    }
+
+    /// Validates that the [`ElectionManifest`] is well-formed, and within the specified limits.
+    ///
+    /// Applies the rules of [`lint_election_manifest`](crate::manifest_lint::lint_election_manifest),
+    /// returning the first error found. The warnings are only logged.
+    pub fn validate_with_limits(&self, limits: &ElectionManifestLimits) -> Result<()> {
+        for (severity, err) in manifest_problems(self, limits) {
+            match severity {
+                ManifestLintSeverity::Error => return Err(err),
+                ManifestLintSeverity::Warning => tracing::warn!("{err:#}"),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that every contest and ballot style index, e.g., referenced by external selection
+    /// data, refers to an item of the [`ElectionManifest`]. Returns the first dangling reference,
+    /// contests first.
+    pub fn validate_indices(
+        &self,
+        contests: &[ContestIndex],
+        ballot_styles: &[BallotStyleIndex],
+    ) -> Result<(), ElectionManifestIndexError> {
+        if let Some(&contest_ix) = contests
+            .iter()
+            .find(|&&contest_ix| self.contests.get(contest_ix).is_none())
+        {
+            return Err(ElectionManifestIndexError::ContestNotInManifest { contest_ix });
+        }
+
+        if let Some(&ballot_style_ix) = ballot_styles
+            .iter()
+            .find(|&&ballot_style_ix| self.ballot_styles.get(ballot_style_ix).is_none())
+        {
+            return Err(ElectionManifestIndexError::BallotStyleNotInManifest { ballot_style_ix });
+        }
+
+        Ok(())
+    }
+
+    /// Appends a fragment of [`Contest`]s, e.g., authored separately, to the contests of the
+    /// [`ElectionManifest`]. The appended contests take the indices following the existing ones.
+    ///
+    /// Duplicate contest labels are not rejected here, but by [`Self::validate`], which should be
+    /// called once all fragments have been merged.
+    pub fn merge_contests(&mut self, contests: Vec1<Contest>) -> Result<()> {
+        for contest in contests {
+            self.contests
+                .try_push(contest)
+                .context("Merging contests into the election manifest")?;
+        }
         Ok(())
     }
+
+    /// Appends a fragment of [`BallotStyle`]s, e.g., authored separately, to the ballot styles
+    /// of the [`ElectionManifest`]. The appended ballot styles take the indices following the
+    /// existing ones.
+    ///
+    /// Duplicate ballot style labels are not rejected here, but by [`Self::validate`], which
+    /// should be called once all fragments have been merged.
+    pub fn merge_ballot_styles(&mut self, ballot_styles: Vec1<BallotStyle>) -> Result<()> {
+        for ballot_style in ballot_styles {
+            self.ballot_styles
+                .try_push(ballot_style)
+                .context("Merging ballot styles into the election manifest")?;
+        }
+        Ok(())
+    }
+
+    /// Returns the indices of the [`BallotStyle`]s under which the supplied contest selections
+    /// could be cast, i.e., those whose set of contests is exactly the set of contests selected.
+    ///
+    /// This is useful to check, e.g., the ballot style declared for a set of voter selections.
+    pub fn compatible_ballot_styles<V>(
+        &self,
+        contest_selections: &BTreeMap<ContestIndex, V>,
+    ) -> Vec<BallotStyleIndex> {
+        self.ballot_styles
+            .indices()
+            .zip(self.ballot_styles.iter())
+            .filter(|(_, ballot_style)| {
+                ballot_style.contests.len() == contest_selections.len()
+                    && ballot_style
+                        .contests
+                        .iter()
+                        .all(|contest_ix| contest_selections.contains_key(contest_ix))
+            })
+            .map(|(ballot_style_ix, _)| ballot_style_ix)
+            .collect()
+    }
+
+    /// Returns, for each contest, the ballot styles on which it appears, along with the
+    /// contests which appear on no ballot style. Useful for reviewing the ballot design.
+    ///
+    /// Returns an error if a ballot style refers to a contest not in the manifest.
+    pub fn contest_ballot_style_matrix(&self) -> Result<ContestBallotStyleMatrix> {
+        let mut ballot_styles_by_contest: BTreeMap<ContestIndex, Vec<BallotStyleIndex>> = self
+            .contests
+            .indices()
+            .map(|contest_ix| (contest_ix, Vec::new()))
+            .collect();
+
+        for (ballot_style_ix, ballot_style) in
+            self.ballot_styles.indices().zip(self.ballot_styles.iter())
+        {
+            for contest_ix in &ballot_style.contests {
+                let Some(ballot_style_ixs) = ballot_styles_by_contest.get_mut(contest_ix) else {
+                    bail!("Ballot style {ballot_style_ix} refers to contest {contest_ix}, which is not in the election manifest");
+                };
+                ballot_style_ixs.push(ballot_style_ix);
+            }
+        }
+
+        let contests_in_no_ballot_style = ballot_styles_by_contest
+            .iter()
+            .filter(|(_, ballot_style_ixs)| ballot_style_ixs.is_empty())
+            .map(|(&contest_ix, _)| contest_ix)
+            .collect();
+
+        Ok(ContestBallotStyleMatrix {
+            ballot_styles_by_contest,
+            contests_in_no_ballot_style,
+        })
+    }
+
+    /// Returns, for each option of each contest, the maximum possible tally given an upper bound
+    /// on the count of ballots cast and on the weight of a ballot. This is the ceiling for the
+    /// discrete log search when decrypting the tally, and can be used for display.
+    ///
+    /// A ballot can give each option at most one vote (or none if the contest's selection limit
+    /// is 0), scaled by the ballot's weight (see [`BallotEncrypted::scale`]). An option of a
+    /// contest which appears on no ballot style can receive no votes. With unweighted ballots,
+    /// `max_ballot_weight` is `1`.
+    ///
+    /// Returns an error if a ballot style refers to a contest not in the manifest, or if a
+    /// maximum tally exceeds `u64::MAX`.
+    ///
+    /// [`BallotEncrypted::scale`]: crate::ballot::BallotEncrypted::scale
+    pub fn max_possible_tally_per_option(
+        &self,
+        ballots_cast: u64,
+        max_ballot_weight: u64,
+    ) -> Result<BTreeMap<(ContestIndex, ContestOptionIndex), u64>> {
+        let matrix = self.contest_ballot_style_matrix()?;
+
+        let mut max_tallies = BTreeMap::new();
+        for (contest_ix, contest) in self.contests.indices().zip(self.contests.iter()) {
+            let max_votes_per_ballot = if matrix.contests_in_no_ballot_style.contains(&contest_ix) {
+                0
+            } else {
+                contest.selection_limit.min(1) as u64
+            };
+
+            let Some(max_tally) = ballots_cast
+                .checked_mul(max_ballot_weight)
+                .and_then(|max_votes| max_votes.checked_mul(max_votes_per_ballot))
+            else {
+                bail!(
+                    "The maximum tally of contest {contest_ix} for {ballots_cast} ballots of weight up to {max_ballot_weight} exceeds {}",
+                    u64::MAX
+                );
+            };
+            for option_ix in contest.options.indices() {
+                max_tallies.insert((contest_ix, option_ix), max_tally);
+            }
+        }
+
+        Ok(max_tallies)
+    }
+
+    /// Returns a digest of the canonical byte representation of the [`ElectionManifest`].
+    ///
+    /// This is `H(0, canonical bytes)`, intended only as an aid for confirming that artifacts
+    /// refer to the same manifest. It is not the election manifest hash `H_M`, and unlike the
+    /// base hash `H_B`, it does not depend on the election parameters.
+    pub fn digest(&self) -> Result<HValue> {
+        let canonical_bytes = self.to_canonical_bytes()?;
+        Ok(eg_h(&HValue::default(), &canonical_bytes))
+    }
+
+    /// Returns an identifier of the content of the [`ElectionManifest`], the same as
+    /// [`Self::digest`].
+    ///
+    /// It depends only on the manifest, so records of the same election can be checked to share
+    /// a manifest by comparing identifiers. Unlike the base hash `H_B`, which also depends on the
+    /// election parameters, it is a pure content identifier and not part of the spec.
+    pub fn content_id(&self) -> HValue {
+        // `unwrap()` is justified here because why would JSON serialization fail?
+        #[allow(clippy::unwrap_used)]
+        let content_id = self.digest().unwrap();
+        content_id
+    }
 }
 
 impl SerializableCanonical for ElectionManifest {}
 
+/// Which [`BallotStyle`]s include each [`Contest`], as computed by
+/// [`ElectionManifest::contest_ballot_style_matrix`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContestBallotStyleMatrix {
+    /// For every contest, the ballot styles on which it appears, in order.
+    pub ballot_styles_by_contest: BTreeMap<ContestIndex, Vec<BallotStyleIndex>>,
+
+    /// The contests which appear on no ballot style. These can never be voted on.
+    pub contests_in_no_ballot_style: Vec<ContestIndex>,
+}
+
+impl SerializablePretty for ContestBallotStyleMatrix {}
+
 impl SerializablePretty for ElectionManifest {}
 
 /// A contest.
@@ -63,11 +383,44 @@ pub struct Contest {
     /// The maximum count of [`ContestOption`]s that a voter may select.
     pub selection_limit: usize, //? TODO NonZeroU32,
 
+    /// Whether a voter must select exactly one option, e.g. in a single-winner contest in
+    /// which undervotes are not allowed. The proof of satisfying the selection limit then
+    /// proves that exactly one option is selected. Requires a `selection_limit` of 1.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exactly_one: bool,
+
     /// The candidates/options.
     /// The order of options matches the virtual ballot.
     pub options: Vec1<ContestOption>,
 }
 
+impl Contest {
+    /// Returns the approximate size, in bytes, of the canonical serialization of the proofs of
+    /// an encrypted contest, i.e., of one proof of ballot correctness per option and the proof
+    /// of satisfying the selection limit. Useful for estimating the size of encrypted ballots.
+    pub fn encrypted_proof_size_estimate(&self, fixed_parameters: &FixedParameters) -> usize {
+        // A field element is serialized as a quoted hex string of at least 256 bits.
+        let len_field_elem = 2 + 2 * fixed_parameters.field.q_len_bytes().max(32);
+
+        // `{"c":…,"v":…}`
+        let len_proof_single = 11 + 2 * len_field_elem;
+
+        // A range proof for `0 <= m <= small_l` is an array of `small_l + 1` single proofs.
+        let len_proof_range = |small_l: usize| 2 + (small_l + 1) * len_proof_single + small_l;
+
+        let cnt_options = self.options.len();
+        let len_proofs_ballot_correctness =
+            2 + cnt_options * len_proof_range(1) + cnt_options.saturating_sub(1);
+
+        let small_l = if self.exactly_one {
+            0
+        } else {
+            self.selection_limit
+        };
+        len_proofs_ballot_correctness + len_proof_range(small_l)
+    }
+}
+
 impl HasIndexTypeMarker for Contest {}
 
 /// A 1-based index of a [`Contest`] in the order it is defined in the [`ElectionManifest`].
@@ -78,6 +431,21 @@ pub type ContestIndex = Index<Contest>;
 pub struct ContestOption {
     /// The label for this `ContestOption`.
     pub label: String,
+
+    /// The party affiliation of the candidate, if any.
+    ///
+    /// Like the label, it is part of the canonical bytes and therefore of the election manifest
+    /// hash, as it is shown to voters. It is omitted when absent, so manifests without it hash
+    /// the same as before it existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub party: Option<String>,
+
+    /// An identifier of the option in an external system, e.g., a candidate ID. Being an
+    /// [`Ident`], it is checked when the manifest is loaded.
+    ///
+    /// Included in the election manifest hash, and omitted when absent, just as `party`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<Ident>,
     /*
     /// The maximum count of votes that a voter can apply to this option.
     /// In the traditional election style, will use `Some(1)` to indicate that a voter may select the option 0 or 1 times.
@@ -92,8 +460,22 @@ pub struct ContestOption {
      */
 }
 
+impl ContestOption {
+    /// Creates a `ContestOption` with the given label and neither a party nor an external
+    /// identifier.
+    pub fn new(label: &str) -> Self {
+        ContestOption {
+            label: label.to_string(),
+            party: None,
+            external_id: None,
+        }
+    }
+}
+
 impl HasIndexTypeMarker for ContestOption {}
 
+impl SerializableCanonical for ContestOption {}
+
 /// A 1-based index of a [`ContestOption`] in the order it is defined within its
 /// [`Contest`], in the order it is defined in the [`ElectionManifest`].
 pub type ContestOptionIndex = Index<ContestOption>;
@@ -136,4 +518,557 @@ pub mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_election_manifest_from_bytes() -> Result<()> {
+        let election_manifest = example_election_manifest();
+
+        let json_pretty = election_manifest.to_json_pretty();
+        let canonical_bytes = election_manifest.to_canonical_bytes()?;
+
+        for bytes in [json_pretty.as_bytes(), canonical_bytes.as_slice()] {
+            let from_bytes = ElectionManifest::from_bytes_validated(bytes)?;
+            let from_stdioread =
+                ElectionManifest::from_stdioread_validated(&mut Cursor::new(bytes))?;
+
+            assert_eq!(from_bytes, from_stdioread);
+            assert_eq!(from_bytes, election_manifest);
+        }
+
+        assert!(ElectionManifest::from_bytes_validated(b"{}").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_election_manifest_schema_version() -> Result<()> {
+        use crate::schema_version::SchemaVersionError;
+
+        let election_manifest = example_election_manifest();
+        assert_eq!(
+            election_manifest.schema_version,
+            ELECTION_MANIFEST_SCHEMA_VERSION
+        );
+
+        // A manifest of the current version loads.
+        let mut json: serde_json::Value = serde_json::to_value(&election_manifest)?;
+        assert_eq!(
+            ElectionManifest::from_bytes_validated(&serde_json::to_vec(&json)?)?,
+            election_manifest
+        );
+
+        // The initial version is not written, so the canonical bytes, and hence `H_M`, are the
+        // same as those of a manifest written before manifests had a schema version.
+        assert!(json.get("schema_version").is_none());
+        assert_eq!(
+            ElectionManifest::from_bytes_validated(&serde_json::to_vec(&json)?)?,
+            election_manifest
+        );
+
+        // But not one of a later version.
+        json["schema_version"] = (ELECTION_MANIFEST_SCHEMA_VERSION + 1).into();
+        let err = ElectionManifest::from_bytes_validated(&serde_json::to_vec(&json)?).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SchemaVersionError>(),
+            Some(&SchemaVersionError::UnsupportedSchemaVersion {
+                type_name: "ElectionManifest".to_string(),
+                found: ELECTION_MANIFEST_SCHEMA_VERSION + 1,
+                supported: ELECTION_MANIFEST_SCHEMA_VERSION,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_election_manifest_digest() -> Result<()> {
+        let canonical_bytes = example_election_manifest().to_canonical_bytes()?;
+
+        let digest_1 = ElectionManifest::from_bytes_validated(&canonical_bytes)?.digest()?;
+        let digest_2 = ElectionManifest::from_bytes_validated(&canonical_bytes)?.digest()?;
+        assert_eq!(digest_1, digest_2);
+
+        let mut election_manifest = example_election_manifest();
+        election_manifest.label.push('!');
+        assert_ne!(election_manifest.digest()?, digest_1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_election_manifest_content_id() -> Result<()> {
+        let election_manifest = example_election_manifest();
+        let content_id = election_manifest.content_id();
+        assert_eq!(content_id, election_manifest.digest()?);
+
+        // Stable across loads, from either representation.
+        let canonical_bytes = election_manifest.to_canonical_bytes()?;
+        let pretty_bytes = election_manifest.to_json_pretty().into_bytes();
+        for bytes in [&canonical_bytes, &pretty_bytes] {
+            let loaded = ElectionManifest::from_bytes_validated(bytes)?;
+            assert_eq!(loaded.content_id(), content_id);
+        }
+
+        // But changes with the manifest.
+        let mut election_manifest = example_election_manifest();
+        let contest_ix = ContestIndex::from_one_based_index(1)?;
+        let contest = election_manifest.contests.get_mut(contest_ix).unwrap();
+        contest.selection_limit += 1;
+        assert_ne!(election_manifest.content_id(), content_id);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_election_manifest_label_too_long() {
+        use crate::label::{LabelValidationError, LABEL_MAX_LEN_CHARS};
+
+        let mut election_manifest = example_election_manifest();
+        election_manifest.validate().unwrap();
+
+        let option = election_manifest
+            .contests
+            .get_mut(ContestIndex::from_one_based_index(2).unwrap())
+            .unwrap()
+            .options
+            .get_mut(ContestOptionIndex::from_one_based_index(3).unwrap())
+            .unwrap();
+        option.label = "x".repeat(LABEL_MAX_LEN_CHARS + 1);
+
+        let err = election_manifest.validate().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<LabelValidationError>(),
+            Some(&LabelValidationError::LabelTooLong {
+                labeled_item: "option 3 of contest 2".to_string(),
+                len: LABEL_MAX_LEN_CHARS + 1,
+                max: LABEL_MAX_LEN_CHARS,
+            })
+        );
+    }
+
+    #[test]
+    fn test_contest_option_party_and_external_id() -> Result<()> {
+        use crate::ident::IdentError;
+        use crate::label::{validate_external_id, LabelValidationError};
+
+        let contest_ix = ContestIndex::from_one_based_index(2)?;
+        let option_ix = ContestOptionIndex::from_one_based_index(3)?;
+        let election_manifest_without = example_election_manifest();
+
+        let mut election_manifest = example_election_manifest();
+        let option = election_manifest
+            .contests
+            .get_mut(contest_ix)
+            .unwrap()
+            .options
+            .get_mut(option_ix)
+            .unwrap();
+        option.party = Some("Independent".to_string());
+        option.external_id = Some(Ident::try_from_str("cand-0042.A_1")?);
+        election_manifest.validate()?;
+
+        // Round-trips through both representations, and is part of the canonical bytes.
+        let canonical_bytes = election_manifest.to_canonical_bytes()?;
+        let pretty_bytes = election_manifest.to_json_pretty().into_bytes();
+        for bytes in [&canonical_bytes, &pretty_bytes] {
+            let loaded = ElectionManifest::from_bytes_validated(bytes)?;
+            assert_eq!(loaded, election_manifest);
+        }
+        assert_ne!(
+            election_manifest.digest()?,
+            election_manifest_without.digest()?
+        );
+
+        // Absent fields are not serialized.
+        let canonical_without = String::from_utf8(election_manifest_without.to_canonical_bytes()?)?;
+        assert!(!canonical_without.contains("party"));
+        assert!(!canonical_without.contains("external_id"));
+
+        // A malformed external identifier is rejected when loading, reporting what is wrong.
+        let labeled_item = "option 3 of contest 2";
+        for (external_id, ident_err) in [
+            ("", IdentError::Empty),
+            (
+                "cand 42",
+                IdentError::InvalidChar {
+                    position: 4,
+                    ch: ' ',
+                },
+            ),
+            (
+                "cand/42",
+                IdentError::InvalidChar {
+                    position: 4,
+                    ch: '/',
+                },
+            ),
+            (
+                "ĉand",
+                IdentError::InvalidChar {
+                    position: 0,
+                    ch: 'ĉ',
+                },
+            ),
+        ] {
+            let mut json = serde_json::to_value(&election_manifest)?;
+            json["contests"][1]["options"][2]["external_id"] = external_id.into();
+            let err =
+                ElectionManifest::from_bytes_validated(&serde_json::to_vec(&json)?).unwrap_err();
+            assert!(
+                format!("{err:#}").contains(&ident_err.to_string()),
+                "{err:#}"
+            );
+
+            assert_eq!(
+                validate_external_id(external_id, labeled_item).unwrap_err(),
+                LabelValidationError::MalformedExternalId {
+                    labeled_item: labeled_item.to_string(),
+                    external_id: external_id.to_string(),
+                    source: ident_err,
+                }
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ballot_style_duplicate_contest() -> Result<()> {
+        let election_manifest = example_election_manifest();
+        let mut json: serde_json::Value = serde_json::to_value(&election_manifest)?;
+        assert_eq!(
+            ElectionManifest::from_bytes_validated(&serde_json::to_vec(&json)?)?,
+            election_manifest
+        );
+
+        // Ballot style 1 lists contest 3 twice.
+        json["ballot_styles"][0]["contests"]
+            .as_array_mut()
+            .unwrap()
+            .push(3.into());
+        let err = ElectionManifest::from_bytes_validated(&serde_json::to_vec(&json)?).unwrap_err();
+        let expected = ElectionManifestIndexError::BallotStyleDuplicateContest {
+            ballot_style_ix: BallotStyleIndex::from_one_based_index(1)?,
+            contest_ix: ContestIndex::from_one_based_index(3)?,
+        };
+        let message = format!("{err:#}");
+        assert!(message.contains(&expected.to_string()), "{message}");
+
+        // A ballot style on its own is also rejected.
+        let err =
+            serde_json::from_value::<BallotStyle>(json["ballot_styles"][0].clone()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Contest 3 is listed more than once."),
+            "{err}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_indices() {
+        let mut election_manifest = example_election_manifest();
+
+        let contest_ixs = |ixs: &[u32]| -> Vec<ContestIndex> {
+            ixs.iter()
+                .map(|&ix| ContestIndex::from_one_based_index(ix).unwrap())
+                .collect()
+        };
+        let bs_ixs = |ixs: &[u32]| -> Vec<BallotStyleIndex> {
+            ixs.iter()
+                .map(|&ix| BallotStyleIndex::from_one_based_index(ix).unwrap())
+                .collect()
+        };
+
+        // The example manifest has 11 contests and 2 ballot styles.
+        election_manifest
+            .validate_indices(&contest_ixs(&[1, 5, 11]), &bs_ixs(&[1, 2]))
+            .unwrap();
+        election_manifest.validate_indices(&[], &[]).unwrap();
+
+        assert_eq!(
+            election_manifest.validate_indices(&contest_ixs(&[1, 12, 13]), &bs_ixs(&[3])),
+            Err(ElectionManifestIndexError::ContestNotInManifest {
+                contest_ix: contest_ixs(&[12])[0]
+            })
+        );
+        assert_eq!(
+            election_manifest.validate_indices(&contest_ixs(&[1]), &bs_ixs(&[2, 3])),
+            Err(ElectionManifestIndexError::BallotStyleNotInManifest {
+                ballot_style_ix: bs_ixs(&[3])[0]
+            })
+        );
+
+        // A ballot style referring to a contest not in the manifest is invalid.
+        let ballot_style_ix = bs_ixs(&[1])[0];
+        election_manifest
+            .ballot_styles
+            .get_mut(ballot_style_ix)
+            .unwrap()
+            .contests
+            .insert(contest_ixs(&[12])[0]);
+        let err = election_manifest.validate().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ElectionManifestIndexError>(),
+            Some(&ElectionManifestIndexError::ContestNotInManifest {
+                contest_ix: contest_ixs(&[12])[0]
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_with_limits() {
+        let election_manifest = example_election_manifest();
+        election_manifest.validate().unwrap();
+
+        // The example manifest has 11 contests.
+        let cnt_contests = election_manifest.contests.len();
+        assert_eq!(cnt_contests, 11);
+        let max_options = election_manifest
+            .contests
+            .iter()
+            .map(|contest| contest.options.len())
+            .max()
+            .unwrap();
+        let (max_options_contest_ix, _) = election_manifest
+            .contests
+            .indices()
+            .zip(election_manifest.contests.iter())
+            .find(|(_, contest)| contest.options.len() == max_options)
+            .unwrap();
+
+        // At the limits.
+        let at_limits = ElectionManifestLimits {
+            max_contests: cnt_contests,
+            max_options_per_contest: max_options,
+            allow_empty_ballot_styles: false,
+        };
+        election_manifest.validate_with_limits(&at_limits).unwrap();
+
+        // Beyond the limit on contests.
+        let limits = ElectionManifestLimits {
+            max_contests: cnt_contests - 1,
+            ..at_limits
+        };
+        let err = election_manifest.validate_with_limits(&limits).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ElectionManifestLimitError>(),
+            Some(&ElectionManifestLimitError::TooManyContests { cnt: 11, max: 10 })
+        );
+
+        // Beyond the limit on options, reported for the first contest with the most options.
+        let limits = ElectionManifestLimits {
+            max_options_per_contest: max_options - 1,
+            ..at_limits
+        };
+        let err = election_manifest.validate_with_limits(&limits).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ElectionManifestLimitError>(),
+            Some(&ElectionManifestLimitError::TooManyOptions {
+                contest_ix: max_options_contest_ix,
+                cnt: max_options,
+                max: max_options - 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_ballot_style_has_no_contests() {
+        let mut election_manifest = example_election_manifest();
+
+        // The example ballot styles all have contests.
+        election_manifest.validate().unwrap();
+
+        let ballot_style_ix = BallotStyleIndex::from_one_based_index(2).unwrap();
+        election_manifest
+            .ballot_styles
+            .get_mut(ballot_style_ix)
+            .unwrap()
+            .contests
+            .clear();
+        let err = election_manifest.validate().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ElectionManifestLimitError>(),
+            Some(&ElectionManifestLimitError::BallotStyleHasNoContests { ballot_style_ix })
+        );
+
+        // Unless allowed.
+        let limits = ElectionManifestLimits {
+            allow_empty_ballot_styles: true,
+            ..Default::default()
+        };
+        election_manifest.validate_with_limits(&limits).unwrap();
+    }
+
+    #[test]
+    fn test_merge_contests() {
+        use crate::label::LabelValidationError;
+
+        let contest = |label: &str| Contest {
+            label: label.to_string(),
+            selection_limit: 1,
+            exactly_one: false,
+            options: [ContestOption::new("Yes"), ContestOption::new("No")]
+                .try_into()
+                .unwrap(),
+        };
+
+        let mut election_manifest = example_election_manifest();
+        let contest_1_label = election_manifest
+            .contests
+            .iter()
+            .next()
+            .unwrap()
+            .label
+            .clone();
+
+        // A fragment of new contests.
+        election_manifest
+            .merge_contests(
+                [contest("Library Levy"), contest("Bridge Bond")]
+                    .try_into()
+                    .unwrap(),
+            )
+            .unwrap();
+        election_manifest.validate().unwrap();
+        assert_eq!(election_manifest.contests.len(), 13);
+        assert_eq!(
+            election_manifest
+                .contests
+                .get(ContestIndex::from_one_based_index(13).unwrap())
+                .unwrap()
+                .label,
+            "Bridge Bond"
+        );
+
+        // A fragment re-introducing the label of contest 1.
+        election_manifest
+            .merge_contests(
+                [contest("Park District Levy"), contest(&contest_1_label)]
+                    .try_into()
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(election_manifest.contests.len(), 15);
+
+        let err = election_manifest.validate().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<LabelValidationError>(),
+            Some(&LabelValidationError::DuplicateLabel {
+                labeled_item: "contest 15".to_string(),
+                first_labeled_item: "contest 1".to_string(),
+                label: contest_1_label,
+            })
+        );
+    }
+
+    #[test]
+    fn test_contest_ballot_style_matrix() {
+        let mut election_manifest = example_election_manifest();
+
+        let bs_ixs = |ixs: &[u32]| -> Vec<BallotStyleIndex> {
+            ixs.iter()
+                .map(|&ix| BallotStyleIndex::from_one_based_index(ix).unwrap())
+                .collect()
+        };
+
+        // Ballot style 1 has contests 1 through 10, ballot style 2 has 1 through 9 and 11.
+        let matrix = election_manifest.contest_ballot_style_matrix().unwrap();
+        assert_eq!(matrix.ballot_styles_by_contest.len(), 11);
+        for (contest_ix, ballot_style_ixs) in &matrix.ballot_styles_by_contest {
+            let expected = match contest_ix.get_one_based_u32() {
+                10 => bs_ixs(&[1]),
+                11 => bs_ixs(&[2]),
+                _ => bs_ixs(&[1, 2]),
+            };
+            assert_eq!(ballot_style_ixs, &expected, "contest {contest_ix}");
+        }
+        assert!(matrix.contests_in_no_ballot_style.is_empty());
+
+        // A contest which is on no ballot style.
+        let contest = election_manifest.contests.iter().next().unwrap().clone();
+        election_manifest.contests.try_push(contest).unwrap();
+
+        let matrix = election_manifest.contest_ballot_style_matrix().unwrap();
+        let contest_ix_12 = ContestIndex::from_one_based_index(12).unwrap();
+        assert!(matrix.ballot_styles_by_contest[&contest_ix_12].is_empty());
+        assert_eq!(matrix.contests_in_no_ballot_style, [contest_ix_12]);
+    }
+
+    #[test]
+    fn test_max_possible_tally_per_option() {
+        let mut election_manifest = example_election_manifest();
+
+        let max_tallies = election_manifest
+            .max_possible_tally_per_option(1000, 1)
+            .unwrap();
+        let cnt_options: usize = election_manifest
+            .contests
+            .iter()
+            .map(|contest| contest.options.len())
+            .sum();
+        assert_eq!(max_tallies.len(), cnt_options);
+        assert!(max_tallies.values().all(|&max_tally| max_tally == 1000));
+
+        let contest_ix_1 = ContestIndex::from_one_based_index(1).unwrap();
+        let option_ix_2 = ContestOptionIndex::from_one_based_index(2).unwrap();
+        assert_eq!(max_tallies[&(contest_ix_1, option_ix_2)], 1000);
+
+        // Each ballot may count several times.
+        let max_tallies = election_manifest
+            .max_possible_tally_per_option(1000, 3)
+            .unwrap();
+        assert!(max_tallies.values().all(|&max_tally| max_tally == 3000));
+        assert!(election_manifest
+            .max_possible_tally_per_option(u64::MAX / 2, 3)
+            .is_err());
+
+        // An option of a contest which is on no ballot style can receive no votes.
+        let contest = election_manifest.contests.iter().next().unwrap().clone();
+        let cnt_options_12 = contest.options.len();
+        election_manifest.contests.try_push(contest).unwrap();
+
+        let max_tallies = election_manifest
+            .max_possible_tally_per_option(1000, 1)
+            .unwrap();
+        let contest_ix_12 = ContestIndex::from_one_based_index(12).unwrap();
+        let max_tallies_12: Vec<u64> = max_tallies
+            .iter()
+            .filter(|((contest_ix, _), _)| *contest_ix == contest_ix_12)
+            .map(|(_, &max_tally)| max_tally)
+            .collect();
+        assert_eq!(max_tallies_12, vec![0; cnt_options_12]);
+        assert_eq!(max_tallies[&(contest_ix_1, option_ix_2)], 1000);
+    }
+
+    #[test]
+    fn test_compatible_ballot_styles() {
+        use crate::contest_selection::ContestSelection;
+
+        let election_manifest = example_election_manifest();
+
+        // Contests 1 through 10 appear on ballot style 1 only.
+        let mut contest_selections: BTreeMap<ContestIndex, ContestSelection> = election_manifest
+            .contests
+            .indices()
+            .zip(election_manifest.contests.iter())
+            .filter(|(contest_ix, _)| *contest_ix != 11)
+            .map(|(contest_ix, contest)| {
+                let vote = vec![0; contest.options.len()];
+                (contest_ix, ContestSelection::new(vote).unwrap())
+            })
+            .collect();
+
+        assert_eq!(
+            election_manifest.compatible_ballot_styles(&contest_selections),
+            [BallotStyleIndex::from_one_based_index(1).unwrap()]
+        );
+
+        // Contests 1 through 9 are a subset of both, but match neither exactly.
+        contest_selections.remove(&ContestIndex::from_one_based_index(10).unwrap());
+        assert!(election_manifest
+            .compatible_ballot_styles(&contest_selections)
+            .is_empty());
+    }
 }
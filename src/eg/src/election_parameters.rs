@@ -11,12 +11,28 @@ use serde::{Deserialize, Serialize};
 use util::csprng::Csprng;
 
 use crate::{
-    fixed_parameters::FixedParameters, serializable::SerializablePretty,
+    fixed_parameters::FixedParameters,
+    hash::{eg_h, HValue},
+    schema_version::{
+        initial_schema_version, is_initial_schema_version, validate_schema_version, SchemaVersion,
+        INITIAL_SCHEMA_VERSION,
+    },
+    serializable::{SerializableCanonical, SerializablePretty},
     varying_parameters::VaryingParameters,
 };
 
+/// The version of the schema of [`ElectionParameters`] written and supported by this library.
+pub const ELECTION_PARAMETERS_SCHEMA_VERSION: SchemaVersion = INITIAL_SCHEMA_VERSION;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ElectionParameters {
+    /// The version of the schema of the election parameters, see [`crate::schema_version`].
+    #[serde(
+        default = "initial_schema_version",
+        skip_serializing_if = "is_initial_schema_version"
+    )]
+    pub schema_version: SchemaVersion,
+
     /// The fixed ElectionGuard parameters that apply to all elections.
     pub fixed_parameters: FixedParameters,
 
@@ -38,17 +54,43 @@ impl ElectionParameters {
         Ok(self_)
     }
 
+    /// Reads a `ElectionParameters` from a byte slice and validates it.
+    /// Parsing is done directly from the borrowed bytes, without an intermediate buffer.
+    pub fn from_bytes_validated(bytes: &[u8], csprng: &mut Csprng) -> Result<Self> {
+        let self_ = Self::from_bytes(bytes)?;
+
+        self_.validate(csprng)?;
+
+        Ok(self_)
+    }
+
     /// Verifies that the `ElectionParameters` meet some basic validity requirements.
     pub fn validate(&self, csprng: &mut Csprng) -> Result<()> {
+        validate_schema_version(
+            "ElectionParameters",
+            self.schema_version,
+            ELECTION_PARAMETERS_SCHEMA_VERSION,
+        )?;
         self.fixed_parameters.validate(csprng)?;
         self.varying_parameters.validate()?;
         Ok(())
     }
 
+    /// Returns a digest of the canonical byte representation of the `ElectionParameters`.
+    ///
+    /// This is `H(0, canonical bytes)`, intended only as an aid for confirming that artifacts
+    /// refer to the same parameters. It is not the parameter base hash `H_P`.
+    pub fn digest(&self) -> Result<HValue> {
+        let canonical_bytes = self.to_canonical_bytes()?;
+        Ok(eg_h(&HValue::default(), &canonical_bytes))
+    }
+
     /// Reads an `ElectionParameters` from a byte sequence.
     pub fn from_bytes(bytes: &[u8]) -> Result<ElectionParameters> {
         serde_json::from_slice(bytes).with_context(|| "Error parsing ElectionParameters bytes")
     }
 }
 
+impl SerializableCanonical for ElectionParameters {}
+
 impl SerializablePretty for ElectionParameters {}
@@ -7,15 +7,16 @@
 
 use std::collections::{BTreeMap, HashMap};
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use serde::{Deserialize, Serialize};
-use util::algebra::FieldElement;
+use util::{algebra::FieldElement, csprng::Csprng};
 
 use crate::{
     ballot::BallotEncrypted,
     election_manifest::{ContestIndex, ElectionManifest},
     election_parameters::ElectionParameters,
     guardian_public_key::GuardianPublicKey,
+    hash::HValue,
     hashes::Hashes,
     hashes_ext::HashesExt,
     joint_election_public_key::{Ciphertext, JointElectionPublicKey},
@@ -113,6 +114,31 @@ impl PreVotingData {
         Ok(pre_voting_data)
     }
 
+    /// The baseline election and cryptographic parameters.
+    pub fn parameters(&self) -> &ElectionParameters {
+        &self.parameters
+    }
+
+    /// The election manifest.
+    pub fn manifest(&self) -> &ElectionManifest {
+        &self.manifest
+    }
+
+    /// The hashes `H_P`, `H_M` and `H_B` of the election.
+    pub fn hashes(&self) -> &Hashes {
+        &self.hashes
+    }
+
+    /// The joint election public key `K`.
+    pub fn joint_public_key(&self) -> &JointElectionPublicKey {
+        &self.public_key
+    }
+
+    /// The extended base hash `H_E`.
+    pub fn extended_base_hash(&self) -> &HValue {
+        &self.hashes_ext.h_e
+    }
+
     pub fn set_manifest(&mut self, manifest: ElectionManifest) {
         self.manifest = manifest;
     }
@@ -131,8 +157,293 @@ impl PreVotingData {
     pub fn from_bytes(bytes: &[u8]) -> Result<PreVotingData> {
         serde_json::from_slice(bytes).map_err(|e| anyhow!("Error parsing canonical bytes: {}", e))
     }
+
+    /// Reads a `PreVotingData` from a `std::io::Read` and validates it.
+    pub fn from_stdioread_validated(
+        stdioread: &mut dyn std::io::Read,
+        csprng: &mut Csprng,
+    ) -> Result<Self> {
+        let self_: Self = serde_json::from_reader(stdioread).context("Reading PreVotingData")?;
+
+        self_.validate(csprng)?;
+
+        Ok(self_)
+    }
+
+    /// Reads a `PreVotingData` from a byte slice and validates it.
+    pub fn from_bytes_validated(bytes: &[u8], csprng: &mut Csprng) -> Result<Self> {
+        let self_ = Self::from_bytes(bytes)?;
+
+        self_.validate(csprng)?;
+
+        Ok(self_)
+    }
+
+    /// Verifies that the `PreVotingData` is internally consistent, i.e., that the contained hashes
+    /// match those computed from the contained parameters, manifest, and joint election public key.
+    /// Useful after deserialization.
+    pub fn validate(&self, csprng: &mut Csprng) -> Result<()> {
+        self.parameters.validate(csprng)?;
+        self.manifest.validate()?;
+        self.public_key.validate(&self.parameters)?;
+        self.hashes.validate()?;
+        self.hashes_ext.validate()?;
+
+        let hashes = Hashes::compute(&self.parameters, &self.manifest)
+            .context("Could not compute hashes from election context")?;
+        ensure!(
+            self.hashes.h_p == hashes.h_p,
+            "Parameter base hash H_P does not match the election parameters"
+        );
+        ensure!(
+            self.hashes.h_m == hashes.h_m,
+            "Election manifest hash H_M does not match the election manifest"
+        );
+        ensure!(
+            self.hashes.h_b == hashes.h_b,
+            "Election base hash H_B does not match the election parameters and manifest"
+        );
+
+        let hashes_ext = HashesExt::compute(&self.parameters, &hashes, &self.public_key);
+        ensure!(
+            self.hashes_ext.h_e == hashes_ext.h_e,
+            "Extended base hash H_E does not match the joint election public key"
+        );
+
+        Ok(())
+    }
+}
+
+impl ElectionRecord {
+    /// Checks that the objects making up the election record are consistent with each other.
+    /// Each object is expected to have already been validated in isolation.
+    ///
+    /// Returns an error describing the first inconsistency found.
+    pub fn cross_validate(&self) -> Result<()> {
+        let parameters = &self.prevoting.parameters;
+        let manifest = &self.prevoting.manifest;
+        let body = &self.body;
+
+        // Guardian public keys must conform to the election parameters, and together they must
+        // form the joint election public key.
+        for guardian_public_key in &body.guardian_public_keys {
+            guardian_public_key.validate(parameters).with_context(|| {
+                format!(
+                    "Guardian public key {} does not conform to the election parameters",
+                    guardian_public_key.i
+                )
+            })?;
+        }
+
+        let joint_election_public_key =
+            JointElectionPublicKey::compute(parameters, &body.guardian_public_keys)
+                .context("Computing the joint election public key from the guardian public keys")?;
+        ensure!(
+            joint_election_public_key == self.prevoting.public_key,
+            "Joint election public key does not match the guardian public keys"
+        );
+
+        // Ballots must reference ballot styles present in the manifest, and only contain
+        // contests of that ballot style.
+        for (ballot_ix, (ballot, _weight)) in body.all_ballots.iter().enumerate() {
+            let Some(ballot_style) = manifest.ballot_styles.get(ballot.ballot_style_index) else {
+                bail!(
+                    "Ballot {ballot_ix} (confirmation code {}) references ballot style {} which is not in the election manifest",
+                    ballot.confirmation_code,
+                    ballot.ballot_style_index
+                );
+            };
+
+            for contest_ix in ballot.contests.keys() {
+                ensure!(
+                    ballot_style.contests.contains(contest_ix),
+                    "Ballot {ballot_ix} (confirmation code {}) contains contest {contest_ix} which is not in ballot style {}",
+                    ballot.confirmation_code,
+                    ballot.ballot_style_index
+                );
+            }
+        }
+
+        for &ballot_ix in body.ballots_by_device.values() {
+            ensure!(
+                ballot_ix < body.all_ballots.len(),
+                "Ballots by device references ballot {ballot_ix}, but there are only {} ballots",
+                body.all_ballots.len()
+            );
+        }
+
+        // Tallies must reference contests present in the manifest, with one entry per option.
+        let cnt_options = |what: &str, contest_ix: ContestIndex| -> Result<usize> {
+            manifest
+                .contests
+                .get(contest_ix)
+                .map(|contest| contest.options.len())
+                .ok_or_else(|| {
+                    anyhow!("{what} references contest {contest_ix} which is not in the election manifest")
+                })
+        };
+
+        for (&contest_ix, tally) in &body.encrypted_tallies {
+            let cnt = cnt_options("Encrypted tally", contest_ix)?;
+            ensure!(
+                tally.len() == cnt,
+                "Encrypted tally for contest {contest_ix} has {} entries, but the contest has {cnt} options",
+                tally.len()
+            );
+        }
+
+        for (&contest_ix, tally) in &body.decrypted_tallies {
+            let cnt = cnt_options("Decrypted tally", contest_ix)?;
+            ensure!(
+                tally.len() == cnt,
+                "Decrypted tally for contest {contest_ix} has {} entries, but the contest has {cnt} options",
+                tally.len()
+            );
+        }
+
+        Ok(())
+    }
 }
 
 impl SerializableCanonical for PreVotingData {}
 
 impl SerializablePretty for PreVotingData {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::{
+        ballot::BallotState,
+        ballot_style::BallotStyleIndex,
+        example_election_manifest::example_election_manifest,
+        example_election_parameters::example_election_parameters,
+        example_pre_voting_data::{example_pre_voting_data, example_pre_voting_data_with},
+        guardian_secret_key::GuardianSecretKey,
+    };
+
+    fn example_election_record(csprng: &mut Csprng) -> ElectionRecord {
+        let (guardian_secret_keys, prevoting) = example_pre_voting_data_with(
+            csprng,
+            example_election_parameters(),
+            example_election_manifest(),
+        )
+        .unwrap();
+        let guardian_public_keys = guardian_secret_keys
+            .iter()
+            .map(GuardianSecretKey::make_public_key)
+            .collect();
+
+        let field = &prevoting.parameters.fixed_parameters.field;
+        let ballot_style_ix = BallotStyleIndex::from_one_based_index(1).unwrap();
+        let ballot = BallotEncrypted::new(
+            ballot_style_ix,
+            prevoting
+                .manifest
+                .ballot_styles
+                .get(ballot_style_ix)
+                .unwrap()
+                .content_hash(),
+            &BTreeMap::new(),
+            BallotState::Cast,
+            HValue::from([0x01; 32]),
+            "",
+            "device",
+        );
+
+        let body = ElectionRecordBody {
+            guardian_public_keys,
+            all_ballots: vec![(ballot, FieldElement::from(1u8, field))],
+            encrypted_tallies: BTreeMap::new(),
+            decrypted_tallies: BTreeMap::new(),
+            ballots_by_device: HashMap::from([("device".to_string(), 0)]),
+        };
+
+        ElectionRecord { prevoting, body }
+    }
+
+    #[test]
+    fn test_cross_validate_consistent() {
+        let mut csprng = Csprng::new(b"test_cross_validate_consistent");
+
+        let election_record = example_election_record(&mut csprng);
+        election_record.cross_validate().unwrap();
+    }
+
+    #[test]
+    fn test_cross_validate_nonexistent_ballot_style() {
+        let mut csprng = Csprng::new(b"test_cross_validate_nonexistent_ballot_style");
+
+        let mut election_record = example_election_record(&mut csprng);
+        election_record.body.all_ballots[0].0.ballot_style_index =
+            BallotStyleIndex::from_one_based_index(3).unwrap();
+
+        let err = election_record.cross_validate().unwrap_err();
+        assert!(err.to_string().contains("ballot style 3"), "{err}");
+    }
+
+    #[test]
+    fn test_pre_voting_data_accessors() {
+        let mut csprng = Csprng::new(b"test_pre_voting_data_accessors");
+        let computed = example_pre_voting_data(&mut csprng).unwrap();
+        let election_parameters = computed.parameters.clone();
+        let joint_election_public_key = computed.public_key.clone();
+        let hashes = computed.hashes.clone();
+        let hashes_ext = computed.hashes_ext.clone();
+
+        let pre_voting_data = PreVotingData::new(
+            example_election_manifest(),
+            election_parameters.clone(),
+            hashes.clone(),
+            hashes_ext.clone(),
+            joint_election_public_key.clone(),
+        );
+
+        assert_eq!(
+            pre_voting_data.parameters().fixed_parameters,
+            election_parameters.fixed_parameters
+        );
+        assert_eq!(
+            pre_voting_data.parameters().varying_parameters.n,
+            election_parameters.varying_parameters.n
+        );
+        assert_eq!(pre_voting_data.manifest(), &example_election_manifest());
+        assert!(pre_voting_data.hashes() == &hashes);
+        assert_eq!(
+            pre_voting_data.joint_public_key(),
+            &joint_election_public_key
+        );
+        assert_eq!(pre_voting_data.extended_base_hash(), &hashes_ext.h_e);
+    }
+
+    #[test]
+    fn test_pre_voting_data_round_trip() {
+        let mut csprng = Csprng::new(b"test_pre_voting_data_round_trip");
+
+        let pre_voting_data = example_pre_voting_data(&mut csprng).unwrap();
+        let bytes = pre_voting_data.to_canonical_bytes().unwrap();
+
+        let pre_voting_data_2 = PreVotingData::from_bytes_validated(&bytes, &mut csprng).unwrap();
+        assert_eq!(pre_voting_data_2.to_canonical_bytes().unwrap(), bytes);
+
+        let pre_voting_data_3 =
+            PreVotingData::from_stdioread_validated(&mut bytes.as_slice(), &mut csprng).unwrap();
+        assert_eq!(pre_voting_data_3.hashes, pre_voting_data.hashes);
+        assert_eq!(pre_voting_data_3.hashes_ext, pre_voting_data.hashes_ext);
+    }
+
+    #[test]
+    fn test_pre_voting_data_tampered_h_e() {
+        let mut csprng = Csprng::new(b"test_pre_voting_data_tampered_h_e");
+
+        let mut pre_voting_data = example_pre_voting_data(&mut csprng).unwrap();
+        pre_voting_data.hashes_ext.h_e = HValue::from([0xAB; 32]);
+        let bytes = pre_voting_data.to_canonical_bytes().unwrap();
+
+        // Parsing alone does not detect the mismatch.
+        assert!(PreVotingData::from_bytes(&bytes).is_ok());
+
+        let err = PreVotingData::from_bytes_validated(&bytes, &mut csprng).unwrap_err();
+        assert!(err.to_string().contains("H_E"), "{err}");
+    }
+}
@@ -0,0 +1,692 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! Stable identifiers for errors.
+//!
+//! Consumers such as FFI or JSON interfaces need to branch on the kind of an error without
+//! matching on the shape of its variant or parsing its message. Each kind of error has a code,
+//! e.g. `"LABEL_DUPLICATE"`, which doesn't change when the message does. Once published, a code
+//! must not be changed or reused for another kind of error.
+//!
+//! For API surfaces, [`ErrorCode::to_problem_json`] gives a consistent JSON representation of an
+//! error, along with its message and the fields of its variant.
+//!
+//! Every error type with a code is listed in [`ERROR_CODE_REGISTRY`], through which
+//! [`error_code_of`] finds the code of an [`anyhow::Error`].
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use util::algebra_utils::LagrangeError;
+
+use crate::{
+    ballot::{BallotChainError, BallotEncryptedError, TallyError},
+    contest_encrypted::SelectionProofError,
+    contest_selection::ContestSelectionError,
+    election_manifest::{ElectionManifestIndexError, ElectionManifestLimitError},
+    hash::HValueHexError,
+    ident::IdentError,
+    label::LabelValidationError,
+    schema_version::SchemaVersionError,
+    spoiled_ballot::SpoiledBallotError,
+    verifiable_decryption::{
+        DecryptionAuditError, DecryptionProofRangeError, ProofTranscriptError,
+    },
+    verifier::{DecryptionVerificationError, TallyVerificationError},
+    zk::ProofRangeError,
+};
+
+/// An error with a stable identifier of its kind.
+pub trait ErrorCode {
+    /// Returns the stable identifier of the kind of the error.
+    fn error_code(&self) -> &'static str;
+
+    /// Returns the error as a JSON object `{ "code", "message", "details" }`, where `details`
+    /// is an object with the fields of the error variant, e.g.,
+    /// `{ "code": "LABEL_TOO_LONG", "message": "The label of ...", "details": { "len": 2, ... } }`.
+    fn to_problem_json(&self) -> Value
+    where
+        Self: Sized + std::fmt::Display + Serialize,
+    {
+        // A variant with fields serializes as `{ "<variant>": { <fields> } }`.
+        let details = match serde_json::to_value(self) {
+            Ok(Value::Object(variant)) => variant
+                .into_iter()
+                .next()
+                .map(|(_, fields)| fields)
+                .filter(Value::is_object),
+            _ => None,
+        };
+        json!({
+            "code": self.error_code(),
+            "message": self.to_string(),
+            "details": details.unwrap_or_else(|| json!({})),
+        })
+    }
+}
+
+impl ErrorCode for LabelValidationError {
+    fn error_code(&self) -> &'static str {
+        use LabelValidationError::*;
+        match self {
+            LabelTooLong { .. } => "LABEL_TOO_LONG",
+            DuplicateLabel { .. } => "LABEL_DUPLICATE",
+            MalformedExternalId { .. } => "EXTERNAL_ID_MALFORMED",
+        }
+    }
+}
+
+impl ErrorCode for ElectionManifestLimitError {
+    fn error_code(&self) -> &'static str {
+        use ElectionManifestLimitError::*;
+        match self {
+            TooManyContests { .. } => "MANIFEST_TOO_MANY_CONTESTS",
+            TooManyOptions { .. } => "CONTEST_TOO_MANY_OPTIONS",
+            BallotStyleHasNoContests { .. } => "BALLOT_STYLE_NO_CONTESTS",
+        }
+    }
+}
+
+impl ErrorCode for ElectionManifestIndexError {
+    fn error_code(&self) -> &'static str {
+        use ElectionManifestIndexError::*;
+        match self {
+            ContestNotInManifest { .. } => "MANIFEST_CONTEST_NOT_FOUND",
+            BallotStyleNotInManifest { .. } => "MANIFEST_BALLOT_STYLE_NOT_FOUND",
+            BallotStyleDuplicateContest { .. } => "MANIFEST_BALLOT_STYLE_DUPLICATE_CONTEST",
+        }
+    }
+}
+
+impl ErrorCode for BallotEncryptedError {
+    fn error_code(&self) -> &'static str {
+        use BallotEncryptedError::*;
+        match self {
+            ProofError { .. } => "BALLOT_PROOF",
+            ContestNotInManifest { .. } => "BALLOT_CONTEST_NOT_IN_MANIFEST",
+            CiphertextCountMismatch { .. } => "BALLOT_CIPHERTEXT_COUNT_MISMATCH",
+            ProofCountMismatch { .. } => "BALLOT_PROOF_COUNT_MISMATCH",
+            CiphertextNotInGroup { .. } => "BALLOT_CIPHERTEXT_NOT_IN_GROUP",
+            BallotStyleNotInManifest { .. } => "BALLOT_STYLE_NOT_IN_MANIFEST",
+            BallotStyleHashMismatch { .. } => "BALLOT_STYLE_HASH_MISMATCH",
+            BallotStyleHashMissing { .. } => "BALLOT_STYLE_HASH_MISSING",
+            ContestNotInBallotStyle { .. } => "BALLOT_CONTEST_NOT_IN_STYLE",
+            PrecomputeMismatch => "BALLOT_PRECOMPUTE_MISMATCH",
+        }
+    }
+}
+
+impl ErrorCode for BallotChainError {
+    fn error_code(&self) -> &'static str {
+        use BallotChainError::*;
+        match self {
+            NotChainedFromDevice { .. } => "BALLOT_CHAIN_NOT_FROM_DEVICE",
+            NotChainedFromPredecessor { .. } => "BALLOT_CHAIN_NOT_FROM_PREDECESSOR",
+        }
+    }
+}
+
+impl ErrorCode for TallyError {
+    fn error_code(&self) -> &'static str {
+        use TallyError::*;
+        match self {
+            ContestNotInManifest { .. } => "TALLY_CONTEST_NOT_IN_MANIFEST",
+            CiphertextCountMismatch { .. } => "TALLY_CIPHERTEXT_COUNT_MISMATCH",
+            TallyOutOfRange { .. } => "TALLY_OUT_OF_RANGE",
+        }
+    }
+}
+
+impl ErrorCode for SpoiledBallotError {
+    fn error_code(&self) -> &'static str {
+        use SpoiledBallotError::*;
+        match self {
+            NotChallenged => "SPOILED_BALLOT_NOT_CHALLENGED",
+            ContestMismatch { .. } => "SPOILED_BALLOT_CONTEST_MISMATCH",
+            SelectionCountMismatch { .. } => "SPOILED_BALLOT_SELECTION_COUNT_MISMATCH",
+            SelectionMismatch { .. } => "SPOILED_BALLOT_SELECTION_MISMATCH",
+        }
+    }
+}
+
+impl ErrorCode for SelectionProofError {
+    fn error_code(&self) -> &'static str {
+        use SelectionProofError::*;
+        match self {
+            ProofCountMismatch { .. } => "CONTEST_PROOF_COUNT_MISMATCH",
+            OptionOutOfRange { .. } => "CONTEST_OPTION_OUT_OF_RANGE",
+        }
+    }
+}
+
+impl ErrorCode for DecryptionProofRangeError {
+    fn error_code(&self) -> &'static str {
+        use DecryptionProofRangeError::*;
+        match self {
+            ChallengeNotInField => "DECRYPTION_PROOF_CHALLENGE_NOT_IN_FIELD",
+            ResponseNotInField => "DECRYPTION_PROOF_RESPONSE_NOT_IN_FIELD",
+        }
+    }
+}
+
+impl ErrorCode for SchemaVersionError {
+    fn error_code(&self) -> &'static str {
+        use SchemaVersionError::*;
+        match self {
+            UnsupportedSchemaVersion { .. } => "SCHEMA_VERSION_UNSUPPORTED",
+        }
+    }
+}
+
+impl ErrorCode for ContestSelectionError {
+    fn error_code(&self) -> &'static str {
+        use ContestSelectionError::*;
+        match self {
+            TooManyOptions { .. } => "SELECTION_TOO_MANY_OPTIONS",
+            ValueOutOfRange { .. } => "SELECTION_VALUE_OUT_OF_RANGE",
+            PackedBitsNotHex => "SELECTION_PACKED_BITS_NOT_HEX",
+            PackedBitsLengthMismatch { .. } => "SELECTION_PACKED_BITS_LENGTH_MISMATCH",
+            PackedBitsPaddingNotZero { .. } => "SELECTION_PACKED_BITS_PADDING_NOT_ZERO",
+        }
+    }
+}
+
+impl ErrorCode for IdentError {
+    fn error_code(&self) -> &'static str {
+        use IdentError::*;
+        match self {
+            Empty => "IDENT_EMPTY",
+            TooLong { .. } => "IDENT_TOO_LONG",
+            InvalidChar { .. } => "IDENT_INVALID_CHAR",
+        }
+    }
+}
+
+impl ErrorCode for HValueHexError {
+    fn error_code(&self) -> &'static str {
+        use HValueHexError::*;
+        match self {
+            WrongLength { .. } => "HVALUE_HEX_WRONG_LENGTH",
+            InvalidChar { .. } => "HVALUE_HEX_INVALID_CHAR",
+        }
+    }
+}
+
+impl ErrorCode for ProofRangeError {
+    fn error_code(&self) -> &'static str {
+        use ProofRangeError::*;
+        match self {
+            RangeNotSatisfied { .. } => "PROOF_RANGE_NOT_SATISFIED",
+            NotExactlyOne { .. } => "PROOF_NOT_EXACTLY_ONE",
+            JointPublicKeyNotInvertible => "PROOF_JOINT_PUBLIC_KEY_NOT_INVERTIBLE",
+        }
+    }
+}
+
+impl ErrorCode for TallyVerificationError {
+    fn error_code(&self) -> &'static str {
+        use TallyVerificationError::*;
+        match self {
+            IncompatibleBallot { .. } => "TALLY_VERIFICATION_INCOMPATIBLE_BALLOT",
+            ContestMissing { .. } => "TALLY_VERIFICATION_CONTEST_MISSING",
+            UnexpectedContest { .. } => "TALLY_VERIFICATION_UNEXPECTED_CONTEST",
+            CiphertextCountMismatch { .. } => "TALLY_VERIFICATION_CIPHERTEXT_COUNT_MISMATCH",
+            CiphertextMismatch { .. } => "TALLY_VERIFICATION_CIPHERTEXT_MISMATCH",
+        }
+    }
+}
+
+impl ErrorCode for DecryptionVerificationError {
+    fn error_code(&self) -> &'static str {
+        use DecryptionVerificationError::*;
+        match self {
+            ContestMissing { .. } => "DECRYPTION_VERIFICATION_CONTEST_MISSING",
+            UnexpectedContest { .. } => "DECRYPTION_VERIFICATION_UNEXPECTED_CONTEST",
+            DecryptionCountMismatch { .. } => "DECRYPTION_VERIFICATION_COUNT_MISMATCH",
+            InvalidProof { .. } => "DECRYPTION_VERIFICATION_INVALID_PROOF",
+            ProofOutOfRange { .. } => "DECRYPTION_VERIFICATION_PROOF_OUT_OF_RANGE",
+        }
+    }
+}
+
+impl ErrorCode for DecryptionAuditError {
+    fn error_code(&self) -> &'static str {
+        use DecryptionAuditError::*;
+        match self {
+            MissingShare { .. } => "DECRYPTION_AUDIT_MISSING_SHARE",
+            ShareCombination { .. } => "DECRYPTION_AUDIT_SHARE_COMBINATION",
+            Decryption { .. } => "DECRYPTION_AUDIT_DECRYPTION",
+            DivergentQuorum { .. } => "DECRYPTION_AUDIT_DIVERGENT_QUORUM",
+        }
+    }
+}
+
+impl ErrorCode for ProofTranscriptError {
+    fn error_code(&self) -> &'static str {
+        use ProofTranscriptError::*;
+        match self {
+            UnexpectedProofTranscriptLength { .. } => "PROOF_TRANSCRIPT_LENGTH_MISMATCH",
+        }
+    }
+}
+
+impl ErrorCode for LagrangeError {
+    fn error_code(&self) -> &'static str {
+        use LagrangeError::*;
+        match self {
+            LengthMismatch { .. } => "LAGRANGE_LENGTH_MISMATCH",
+            ZeroNode { .. } => "LAGRANGE_ZERO_NODE",
+            DuplicateNode { .. } => "LAGRANGE_DUPLICATE_NODE",
+        }
+    }
+}
+
+/// Returns the code of an error, if it is of the type of this entry of an error code registry.
+pub type ErrorCodeDowncast = fn(&(dyn std::error::Error + 'static)) -> Option<&'static str>;
+
+/// The entry of an error code registry for the error type `E`.
+pub fn downcast_error_code<E>(err: &(dyn std::error::Error + 'static)) -> Option<&'static str>
+where
+    E: ErrorCode + std::error::Error + 'static,
+{
+    err.downcast_ref::<E>().map(ErrorCode::error_code)
+}
+
+/// Every error type of this crate which has an [`ErrorCode`].
+pub const ERROR_CODE_REGISTRY: &[ErrorCodeDowncast] = &[
+    downcast_error_code::<LabelValidationError>,
+    downcast_error_code::<ElectionManifestLimitError>,
+    downcast_error_code::<ElectionManifestIndexError>,
+    downcast_error_code::<BallotEncryptedError>,
+    downcast_error_code::<BallotChainError>,
+    downcast_error_code::<TallyError>,
+    downcast_error_code::<SelectionProofError>,
+    downcast_error_code::<SpoiledBallotError>,
+    downcast_error_code::<DecryptionProofRangeError>,
+    downcast_error_code::<SchemaVersionError>,
+    downcast_error_code::<ContestSelectionError>,
+    downcast_error_code::<IdentError>,
+    downcast_error_code::<HValueHexError>,
+    downcast_error_code::<ProofRangeError>,
+    downcast_error_code::<TallyVerificationError>,
+    downcast_error_code::<DecryptionVerificationError>,
+    downcast_error_code::<DecryptionAuditError>,
+    downcast_error_code::<ProofTranscriptError>,
+    downcast_error_code::<LagrangeError>,
+];
+
+/// Returns the code of the first error in the chain of an [`anyhow::Error`] which has an
+/// [`ErrorCode`], e.g., of the error returned by
+/// [`ElectionManifest::validate`](crate::election_manifest::ElectionManifest::validate).
+pub fn error_code_of(err: &anyhow::Error) -> Option<&'static str> {
+    error_code_of_with(err, &[])
+}
+
+/// Same as [`error_code_of`], but also considers the error types of a registry of another
+/// crate, e.g., one building on this one.
+pub fn error_code_of_with(
+    err: &anyhow::Error,
+    registry: &[ErrorCodeDowncast],
+) -> Option<&'static str> {
+    err.chain().find_map(|cause| {
+        ERROR_CODE_REGISTRY
+            .iter()
+            .chain(registry)
+            .find_map(|downcast| downcast(cause))
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use anyhow::Context;
+
+    use super::*;
+    use crate::{
+        election_manifest::{ContestIndex, ContestOptionIndex},
+        example_election_manifest::example_election_manifest,
+        guardian::GuardianIndex,
+        verifiable_decryption::{DecryptionError, ShareCombinationError},
+    };
+
+    #[test]
+    fn test_error_codes() {
+        let contest_ix = ContestIndex::from_one_based_index(1).unwrap();
+        let option_ix = ContestOptionIndex::from_one_based_index(1).unwrap();
+        let guardian_ix = |i| GuardianIndex::from_one_based_index(i).unwrap();
+        let errors: Vec<anyhow::Error> = vec![
+            anyhow::Error::new(LabelValidationError::LabelTooLong {
+                labeled_item: "contest 1".to_string(),
+                len: 2,
+                max: 1,
+            }),
+            anyhow::Error::new(LabelValidationError::DuplicateLabel {
+                labeled_item: "contest 2".to_string(),
+                first_labeled_item: "contest 1".to_string(),
+                label: "Contest".to_string(),
+            }),
+            anyhow::Error::new(LabelValidationError::MalformedExternalId {
+                labeled_item: "option 1 of contest 1".to_string(),
+                external_id: "".to_string(),
+                source: IdentError::Empty,
+            }),
+            anyhow::Error::new(ElectionManifestLimitError::TooManyContests { cnt: 2, max: 1 }),
+            anyhow::Error::new(ElectionManifestLimitError::TooManyOptions {
+                contest_ix,
+                cnt: 2,
+                max: 1,
+            }),
+            anyhow::Error::new(ElectionManifestLimitError::BallotStyleHasNoContests {
+                ballot_style_ix: crate::ballot_style::BallotStyleIndex::from_one_based_index(1)
+                    .unwrap(),
+            }),
+            anyhow::Error::new(ElectionManifestIndexError::ContestNotInManifest { contest_ix }),
+            anyhow::Error::new(ElectionManifestIndexError::BallotStyleNotInManifest {
+                ballot_style_ix: crate::ballot_style::BallotStyleIndex::from_one_based_index(1)
+                    .unwrap(),
+            }),
+            anyhow::Error::new(ElectionManifestIndexError::BallotStyleDuplicateContest {
+                ballot_style_ix: crate::ballot_style::BallotStyleIndex::from_one_based_index(1)
+                    .unwrap(),
+                contest_ix,
+            }),
+            anyhow::Error::new(BallotEncryptedError::ProofError {
+                err: ProofRangeError::NotExactlyOne { small_l: 0 },
+            }),
+            anyhow::Error::new(BallotEncryptedError::ContestNotInManifest { idx: contest_ix }),
+            anyhow::Error::new(BallotEncryptedError::CiphertextCountMismatch {
+                idx: contest_ix,
+                expected: 2,
+                actual: 1,
+            }),
+            anyhow::Error::new(BallotEncryptedError::ProofCountMismatch {
+                idx: contest_ix,
+                expected: 2,
+                actual: 1,
+            }),
+            anyhow::Error::new(BallotEncryptedError::CiphertextNotInGroup {
+                idx: contest_ix,
+                option_ix: ContestOptionIndex::from_one_based_index(1).unwrap(),
+            }),
+            anyhow::Error::new(BallotEncryptedError::BallotStyleNotInManifest {
+                ballot_style_ix: crate::ballot_style::BallotStyleIndex::from_one_based_index(1)
+                    .unwrap(),
+            }),
+            anyhow::Error::new(BallotEncryptedError::BallotStyleHashMismatch {
+                ballot_style_ix: crate::ballot_style::BallotStyleIndex::from_one_based_index(1)
+                    .unwrap(),
+            }),
+            anyhow::Error::new(BallotEncryptedError::BallotStyleHashMissing {
+                ballot_style_ix: crate::ballot_style::BallotStyleIndex::from_one_based_index(1)
+                    .unwrap(),
+            }),
+            anyhow::Error::new(BallotEncryptedError::ContestNotInBallotStyle {
+                idx: contest_ix,
+                ballot_style_ix: crate::ballot_style::BallotStyleIndex::from_one_based_index(1)
+                    .unwrap(),
+            }),
+            anyhow::Error::new(BallotEncryptedError::PrecomputeMismatch),
+            anyhow::Error::new(BallotChainError::NotChainedFromDevice { index: 0 }),
+            anyhow::Error::new(BallotChainError::NotChainedFromPredecessor {
+                index: 1,
+                predecessor: 0,
+            }),
+            anyhow::Error::new(TallyError::ContestNotInManifest { idx: contest_ix }),
+            anyhow::Error::new(TallyError::CiphertextCountMismatch {
+                idx: contest_ix,
+                expected: 2,
+                actual: 1,
+            }),
+            anyhow::Error::new(TallyError::TallyOutOfRange {
+                value: "9007199254740992".to_string(),
+                max: crate::ballot::CONTEST_OPTION_TALLY_MAX,
+            }),
+            anyhow::Error::new(SelectionProofError::ProofCountMismatch {
+                cnt_selections: 2,
+                cnt_proofs: 1,
+            }),
+            anyhow::Error::new(SelectionProofError::OptionOutOfRange {
+                option_ix: ContestOptionIndex::from_one_based_index(3).unwrap(),
+                cnt_selections: 2,
+            }),
+            anyhow::Error::new(SpoiledBallotError::NotChallenged),
+            anyhow::Error::new(SpoiledBallotError::ContestMismatch { idx: contest_ix }),
+            anyhow::Error::new(SpoiledBallotError::SelectionCountMismatch {
+                idx: contest_ix,
+                expected: 2,
+                actual: 1,
+            }),
+            anyhow::Error::new(SpoiledBallotError::SelectionMismatch {
+                idx: contest_ix,
+                option_ix: ContestOptionIndex::from_one_based_index(1).unwrap(),
+            }),
+            anyhow::Error::new(DecryptionProofRangeError::ChallengeNotInField),
+            anyhow::Error::new(DecryptionProofRangeError::ResponseNotInField),
+            anyhow::Error::new(SchemaVersionError::UnsupportedSchemaVersion {
+                type_name: "ElectionManifest".to_string(),
+                found: 2,
+                supported: 1,
+            }),
+            anyhow::Error::new(ContestSelectionError::TooManyOptions { len: 2, max: 1 }),
+            anyhow::Error::new(ContestSelectionError::ValueOutOfRange {
+                option: 1,
+                value: 2,
+                max: 1,
+            }),
+            anyhow::Error::new(ContestSelectionError::PackedBitsNotHex),
+            anyhow::Error::new(ContestSelectionError::PackedBitsLengthMismatch {
+                len: 2,
+                cnt_bytes: 2,
+            }),
+            anyhow::Error::new(ContestSelectionError::PackedBitsPaddingNotZero { len: 2 }),
+            anyhow::Error::new(IdentError::Empty),
+            anyhow::Error::new(IdentError::TooLong { len: 2, max: 1 }),
+            anyhow::Error::new(IdentError::InvalidChar {
+                position: 0,
+                ch: ' ',
+            }),
+            anyhow::Error::new(HValueHexError::WrongLength {
+                len: 1,
+                expected: 64,
+            }),
+            anyhow::Error::new(HValueHexError::InvalidChar {
+                position: 0,
+                ch: 'g',
+            }),
+            anyhow::Error::new(ProofRangeError::RangeNotSatisfied {
+                small_l: 2,
+                big_l: 1,
+            }),
+            anyhow::Error::new(ProofRangeError::NotExactlyOne { small_l: 0 }),
+            anyhow::Error::new(ProofRangeError::JointPublicKeyNotInvertible),
+            anyhow::Error::new(TallyVerificationError::IncompatibleBallot { index: 0 }),
+            anyhow::Error::new(TallyVerificationError::ContestMissing { contest_ix }),
+            anyhow::Error::new(TallyVerificationError::UnexpectedContest { contest_ix }),
+            anyhow::Error::new(TallyVerificationError::CiphertextCountMismatch {
+                contest_ix,
+                expected: 2,
+                actual: 1,
+            }),
+            anyhow::Error::new(TallyVerificationError::CiphertextMismatch {
+                contest_ix,
+                option_ix,
+            }),
+            anyhow::Error::new(DecryptionVerificationError::ContestMissing { contest_ix }),
+            anyhow::Error::new(DecryptionVerificationError::UnexpectedContest { contest_ix }),
+            anyhow::Error::new(DecryptionVerificationError::DecryptionCountMismatch {
+                contest_ix,
+                expected: 2,
+                actual: 1,
+            }),
+            anyhow::Error::new(DecryptionVerificationError::InvalidProof {
+                contest_ix,
+                option_ix,
+            }),
+            anyhow::Error::new(DecryptionVerificationError::ProofOutOfRange {
+                contest_ix,
+                option_ix,
+                source: DecryptionProofRangeError::ResponseNotInField,
+            }),
+            anyhow::Error::new(DecryptionAuditError::MissingShare {
+                i: guardian_ix(2),
+                quorum: vec![guardian_ix(1), guardian_ix(2)],
+            }),
+            anyhow::Error::new(DecryptionAuditError::ShareCombination {
+                quorum: vec![guardian_ix(1)],
+                err: ShareCombinationError::NotEnoughShares { l: 1, k: 2 },
+            }),
+            anyhow::Error::new(DecryptionAuditError::Decryption {
+                quorum: vec![guardian_ix(1), guardian_ix(2)],
+                err: DecryptionError::NoInverse,
+            }),
+            anyhow::Error::new(DecryptionAuditError::DivergentQuorum {
+                quorum: vec![guardian_ix(1), guardian_ix(3)],
+                first_quorum: vec![guardian_ix(1), guardian_ix(2)],
+            }),
+            anyhow::Error::new(ProofTranscriptError::UnexpectedProofTranscriptLength {
+                expected: 2,
+                actual: 1,
+            }),
+            anyhow::Error::new(LagrangeError::LengthMismatch {
+                cnt_xs: 2,
+                cnt_ys: 1,
+            }),
+            anyhow::Error::new(LagrangeError::ZeroNode { ix: 0 }),
+            anyhow::Error::new(LagrangeError::DuplicateNode { ix: 1, first_ix: 0 }),
+        ];
+        let codes: Vec<_> = errors
+            .iter()
+            .map(|err| error_code_of(err).unwrap())
+            .collect();
+
+        // The codes are stable.
+        assert_eq!(
+            codes,
+            [
+                "LABEL_TOO_LONG",
+                "LABEL_DUPLICATE",
+                "EXTERNAL_ID_MALFORMED",
+                "MANIFEST_TOO_MANY_CONTESTS",
+                "CONTEST_TOO_MANY_OPTIONS",
+                "BALLOT_STYLE_NO_CONTESTS",
+                "MANIFEST_CONTEST_NOT_FOUND",
+                "MANIFEST_BALLOT_STYLE_NOT_FOUND",
+                "MANIFEST_BALLOT_STYLE_DUPLICATE_CONTEST",
+                "BALLOT_PROOF",
+                "BALLOT_CONTEST_NOT_IN_MANIFEST",
+                "BALLOT_CIPHERTEXT_COUNT_MISMATCH",
+                "BALLOT_PROOF_COUNT_MISMATCH",
+                "BALLOT_CIPHERTEXT_NOT_IN_GROUP",
+                "BALLOT_STYLE_NOT_IN_MANIFEST",
+                "BALLOT_STYLE_HASH_MISMATCH",
+                "BALLOT_STYLE_HASH_MISSING",
+                "BALLOT_CONTEST_NOT_IN_STYLE",
+                "BALLOT_PRECOMPUTE_MISMATCH",
+                "BALLOT_CHAIN_NOT_FROM_DEVICE",
+                "BALLOT_CHAIN_NOT_FROM_PREDECESSOR",
+                "TALLY_CONTEST_NOT_IN_MANIFEST",
+                "TALLY_CIPHERTEXT_COUNT_MISMATCH",
+                "TALLY_OUT_OF_RANGE",
+                "CONTEST_PROOF_COUNT_MISMATCH",
+                "CONTEST_OPTION_OUT_OF_RANGE",
+                "SPOILED_BALLOT_NOT_CHALLENGED",
+                "SPOILED_BALLOT_CONTEST_MISMATCH",
+                "SPOILED_BALLOT_SELECTION_COUNT_MISMATCH",
+                "SPOILED_BALLOT_SELECTION_MISMATCH",
+                "DECRYPTION_PROOF_CHALLENGE_NOT_IN_FIELD",
+                "DECRYPTION_PROOF_RESPONSE_NOT_IN_FIELD",
+                "SCHEMA_VERSION_UNSUPPORTED",
+                "SELECTION_TOO_MANY_OPTIONS",
+                "SELECTION_VALUE_OUT_OF_RANGE",
+                "SELECTION_PACKED_BITS_NOT_HEX",
+                "SELECTION_PACKED_BITS_LENGTH_MISMATCH",
+                "SELECTION_PACKED_BITS_PADDING_NOT_ZERO",
+                "IDENT_EMPTY",
+                "IDENT_TOO_LONG",
+                "IDENT_INVALID_CHAR",
+                "HVALUE_HEX_WRONG_LENGTH",
+                "HVALUE_HEX_INVALID_CHAR",
+                "PROOF_RANGE_NOT_SATISFIED",
+                "PROOF_NOT_EXACTLY_ONE",
+                "PROOF_JOINT_PUBLIC_KEY_NOT_INVERTIBLE",
+                "TALLY_VERIFICATION_INCOMPATIBLE_BALLOT",
+                "TALLY_VERIFICATION_CONTEST_MISSING",
+                "TALLY_VERIFICATION_UNEXPECTED_CONTEST",
+                "TALLY_VERIFICATION_CIPHERTEXT_COUNT_MISMATCH",
+                "TALLY_VERIFICATION_CIPHERTEXT_MISMATCH",
+                "DECRYPTION_VERIFICATION_CONTEST_MISSING",
+                "DECRYPTION_VERIFICATION_UNEXPECTED_CONTEST",
+                "DECRYPTION_VERIFICATION_COUNT_MISMATCH",
+                "DECRYPTION_VERIFICATION_INVALID_PROOF",
+                "DECRYPTION_VERIFICATION_PROOF_OUT_OF_RANGE",
+                "DECRYPTION_AUDIT_MISSING_SHARE",
+                "DECRYPTION_AUDIT_SHARE_COMBINATION",
+                "DECRYPTION_AUDIT_DECRYPTION",
+                "DECRYPTION_AUDIT_DIVERGENT_QUORUM",
+                "PROOF_TRANSCRIPT_LENGTH_MISMATCH",
+                "LAGRANGE_LENGTH_MISMATCH",
+                "LAGRANGE_ZERO_NODE",
+                "LAGRANGE_DUPLICATE_NODE",
+            ]
+        );
+
+        // And distinct.
+        assert_eq!(codes.iter().collect::<BTreeSet<_>>().len(), codes.len());
+
+        // Every type of the registry is covered above.
+        for downcast in ERROR_CODE_REGISTRY {
+            assert!(errors
+                .iter()
+                .any(|err| downcast(err.as_ref() as &(dyn std::error::Error + 'static)).is_some()));
+        }
+
+        // The code is found behind context, and independent of the message.
+        let mut election_manifest = example_election_manifest();
+        election_manifest.label = "x".repeat(crate::label::LABEL_MAX_LEN_CHARS + 1);
+        let err = election_manifest
+            .validate()
+            .context("Validating the manifest")
+            .unwrap_err();
+        assert_eq!(error_code_of(&err), Some("LABEL_TOO_LONG"));
+        assert_eq!(error_code_of(&anyhow::anyhow!("Some error")), None);
+    }
+
+    #[test]
+    fn test_to_problem_json() {
+        let err = LabelValidationError::DuplicateLabel {
+            labeled_item: "contest 2".to_string(),
+            first_labeled_item: "contest 1".to_string(),
+            label: "Mayor".to_string(),
+        };
+        assert_eq!(
+            err.to_problem_json(),
+            json!({
+                "code": "LABEL_DUPLICATE",
+                "message": "The label of contest 2 duplicates that of contest 1: \"Mayor\"",
+                "details": {
+                    "labeled_item": "contest 2",
+                    "first_labeled_item": "contest 1",
+                    "label": "Mayor",
+                },
+            })
+        );
+
+        let err = BallotEncryptedError::ContestNotInManifest {
+            idx: ContestIndex::from_one_based_index(12).unwrap(),
+        };
+        assert_eq!(
+            err.to_problem_json(),
+            json!({
+                "code": "BALLOT_CONTEST_NOT_IN_MANIFEST",
+                "message": "Contest (index 12) not found in election manifest.",
+                "details": { "idx": 12 },
+            })
+        );
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![allow(clippy::unwrap_used)]
+
+//! Capturing of [`tracing`] events in tests, e.g., the audit events of
+//! [`VERIFICATION_AUDIT_TARGET`](crate::audit::VERIFICATION_AUDIT_TARGET).
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+/// A subscriber recording the fields of each event at `level` or more severe and, if
+/// `opt_target` is set, at that target. Each event is recorded as its fields in the form
+/// `name=value`, ordered by name and separated by spaces.
+pub(crate) struct EventCapture {
+    level: tracing::Level,
+    opt_target: Option<&'static str>,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl EventCapture {
+    /// Runs `f` with an [`EventCapture`] as the default subscriber, returning the result and
+    /// the events captured.
+    pub(crate) fn capture<R>(
+        level: tracing::Level,
+        opt_target: Option<&'static str>,
+        f: impl FnOnce() -> R,
+    ) -> (R, Vec<String>) {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = EventCapture {
+            level,
+            opt_target,
+            events: events.clone(),
+        };
+        let result = tracing::subscriber::with_default(subscriber, f);
+        let events = events.lock().unwrap().clone();
+        (result, events)
+    }
+}
+
+struct EventFields(BTreeMap<&'static str, String>);
+
+impl tracing::field::Visit for EventFields {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+impl tracing::Subscriber for EventCapture {
+    fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+        *metadata.level() <= self.level
+            && self
+                .opt_target
+                .is_none_or(|target| metadata.target() == target)
+    }
+
+    fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let mut fields = EventFields(BTreeMap::new());
+        event.record(&mut fields);
+        let event = fields
+            .0
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.events.lock().unwrap().push(event);
+    }
+
+    fn enter(&self, _span: &tracing::span::Id) {}
+
+    fn exit(&self, _span: &tracing::span::Id) {}
+}
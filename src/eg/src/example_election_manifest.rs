@@ -13,21 +13,17 @@ use std::collections::BTreeSet;
 
 use crate::{
     ballot_style::BallotStyle,
-    election_manifest::{Contest, ContestIndex, ContestOption, ElectionManifest},
+    election_manifest::{
+        Contest, ContestIndex, ContestOption, ElectionManifest, ELECTION_MANIFEST_SCHEMA_VERSION,
+    },
     vec1::Vec1,
 };
 
 pub fn example_election_manifest() -> ElectionManifest {
-    let referendum_options: Vec1<ContestOption> = [
-        ContestOption {
-            label: "Prō".to_string(),
-        },
-        ContestOption {
-            label: "Ĉontrá".to_string(),
-        },
-    ]
-    .try_into()
-    .unwrap();
+    let referendum_options: Vec1<ContestOption> =
+        [ContestOption::new("Prō"), ContestOption::new("Ĉontrá")]
+            .try_into()
+            .unwrap();
 
     let contests = [
         // Contest index 1:
@@ -35,150 +31,104 @@ pub fn example_election_manifest() -> ElectionManifest {
             label: "For President and Vice President of The United Realms of Imaginaria"
                 .to_string(),
             selection_limit: 1,
+            exactly_one: false,
             options: [
-                ContestOption {
-                    label:
-                        "Thündéroak, Vâlêriana D.\nËverbright, Ålistair R. Jr.\n(Ætherwïng)"
-                            .to_string(),
-                },
-                ContestOption {
-                    label: "Stârførge, Cássánder A.\nMøonfire, Célestïa L.\n(Crystâlheärt)".to_string(),
-                },
+                ContestOption::new("Thündéroak, Vâlêriana D.\nËverbright, Ålistair R. Jr.\n(Ætherwïng)"),
+                ContestOption::new("Stârførge, Cássánder A.\nMøonfire, Célestïa L.\n(Crystâlheärt)"),
             ].try_into().unwrap(),
         },
         // Contest index 2:
         Contest {
             label: "Minister of Arcane Sciences".to_string(),
             selection_limit: 1,
+            exactly_one: false,
             options: [
-                ContestOption {
-                    label: "Élyria Moonshadow\n(Crystâlheärt)".to_string(),
-                },
-                ContestOption {
-                    label: "Archímedes Darkstone\n(Ætherwïng)".to_string(),
-                },
-                ContestOption {
-                    label: "Seraphína Stormbinder\n(Independent)".to_string(),
-                },
-                ContestOption {
-                    label: "Gávrïel Runëbørne\n(Stärsky)".to_string(),
-                },
+                ContestOption::new("Élyria Moonshadow\n(Crystâlheärt)"),
+                ContestOption::new("Archímedes Darkstone\n(Ætherwïng)"),
+                ContestOption::new("Seraphína Stormbinder\n(Independent)"),
+                ContestOption::new("Gávrïel Runëbørne\n(Stärsky)"),
             ].try_into().unwrap(),
         },
         // Contest index 3:
         Contest {
             label: "Minister of Elemental Resources".to_string(),
             selection_limit: 1,
+            exactly_one: false,
             options: [
-                ContestOption {
-                    label: "Tïtus Stormforge\n(Ætherwïng)".to_string(),
-                },
-                ContestOption {
-                    label: "Fæ Willowgrove\n(Crystâlheärt)".to_string(),
-                },
-                ContestOption {
-                    label: "Tèrra Stonebinder\n(Independent)".to_string(),
-                },
+                ContestOption::new("Tïtus Stormforge\n(Ætherwïng)"),
+                ContestOption::new("Fæ Willowgrove\n(Crystâlheärt)"),
+                ContestOption::new("Tèrra Stonebinder\n(Independent)"),
             ].try_into().unwrap(),
         },
         // Contest index 4:
         Contest {
             label: "Minister of Dance".to_string(),
             selection_limit: 1,
+            exactly_one: false,
             options: [
-                ContestOption {
-                    label: "Äeliana Sunsong\n(Crystâlheärt)".to_string(),
-                },
-                ContestOption {
-                    label: "Thâlia Shadowdance\n(Ætherwïng)".to_string(),
-                },
-                ContestOption {
-                    label: "Jasper Moonstep\n(Stärsky)".to_string(),
-                },
+                ContestOption::new("Äeliana Sunsong\n(Crystâlheärt)"),
+                ContestOption::new("Thâlia Shadowdance\n(Ætherwïng)"),
+                ContestOption::new("Jasper Moonstep\n(Stärsky)"),
             ].try_into().unwrap(),
         },
         // Contest index 5:
         Contest {
             label: "Gränd Cøuncil of Arcáne and Technomägical Affairs".to_string(),
             selection_limit: 3,
+            exactly_one: false,
             options: [
-                ContestOption {
-                    label: "Ìgnatius Gearsøul\n(Crystâlheärt)".to_string(),
-                },
-                ContestOption {
-                    label: "Èlena Wîndwhisper\n(Technocrat)".to_string(),
-                },
-                ContestOption {
-                    label: "Bërnard Månesworn\n(Ætherwïng)".to_string(),
-                },
-                ContestOption {
-                    label: "Èmeline Glîmmerwillow\n(Ætherwïng)".to_string(),
-                },
-                ContestOption {
-                    label: "Nikólai Thunderstrîde\n(Independent)".to_string(),
-                },
-                ContestOption {
-                    label: "Lïliana Fîrestone\n(Pęacemaker)".to_string(),
-                },
-                ContestOption {
-                    label: "Émeric Crystálgaze\n(Førestmíst)".to_string(),
-                },
-                ContestOption {
-                    label: "Séraphine Lùmenwing\n(Stärsky)".to_string(),
-                },
-                ContestOption {
-                    label: "Rãfael Stëamheart\n(Ætherwïng)".to_string(),
-                },
-                ContestOption {
-                    label: "Océane Tidecaller\n(Pęacemaker)".to_string(),
-                },
-                ContestOption {
-                    label: "Elysêa Shadowbinder\n(Independent)".to_string(),
-                },
+                ContestOption::new("Ìgnatius Gearsøul\n(Crystâlheärt)"),
+                ContestOption::new("Èlena Wîndwhisper\n(Technocrat)"),
+                ContestOption::new("Bërnard Månesworn\n(Ætherwïng)"),
+                ContestOption::new("Èmeline Glîmmerwillow\n(Ætherwïng)"),
+                ContestOption::new("Nikólai Thunderstrîde\n(Independent)"),
+                ContestOption::new("Lïliana Fîrestone\n(Pęacemaker)"),
+                ContestOption::new("Émeric Crystálgaze\n(Førestmíst)"),
+                ContestOption::new("Séraphine Lùmenwing\n(Stärsky)"),
+                ContestOption::new("Rãfael Stëamheart\n(Ætherwïng)"),
+                ContestOption::new("Océane Tidecaller\n(Pęacemaker)"),
+                ContestOption::new("Elysêa Shadowbinder\n(Independent)"),
             ].try_into().unwrap(),
         },
         // Contest index 6:
         Contest {
             label: "Proposed Amendment No. 1\nEqual Representation for Technological and Magical Profeſsions".to_string(),
             selection_limit: 1,
+            exactly_one: false,
             options: [
-                ContestOption {
-                    label: "For".to_string(),
-                },
-                ContestOption {
-                    label: "Against".to_string(),
-                },
+                ContestOption::new("For"),
+                ContestOption::new("Against"),
             ].try_into().unwrap(),
         },
         // Contest index 7:
         Contest {
             label: "Privacy Protection in Techno-Magical Communications Act".to_string(),
             selection_limit: 1,
+            exactly_one: false,
             options: referendum_options.clone(),
         },
         // Contest index 8:
         Contest {
             label: "Public Transport Modernization and Enchantment Proposal".to_string(),
             selection_limit: 1,
+            exactly_one: false,
             options: referendum_options.clone(),
         },
         // Contest index 9:
         Contest {
             label: "Renewable Ætherwind Infrastructure Initiative".to_string(),
             selection_limit: 1,
+            exactly_one: false,
             options: referendum_options,
         },
         // Contest index 10:
         Contest {
             label: "For Librarian-in-Chief of Smoothstone County".to_string(),
             selection_limit: 1,
+            exactly_one: false,
             options: [
-                ContestOption {
-                    label: "Élise Planetes".to_string(),
-                },
-                ContestOption {
-                    label: "Théodoric Inkdrifter".to_string(),
-                },
+                ContestOption::new("Élise Planetes"),
+                ContestOption::new("Théodoric Inkdrifter"),
             ].try_into().unwrap(),
         },
         // Contest index 11:
@@ -186,13 +136,10 @@ pub fn example_election_manifest() -> ElectionManifest {
             label: "Silvërspîre County Register of Deeds Sébastian Moonglôw to be retained"
                 .to_string(),
             selection_limit: 1,
+            exactly_one: false,
             options: [
-                ContestOption {
-                    label: "Retain".to_string(),
-                },
-                ContestOption {
-                    label: "Remove".to_string(),
-                },
+                ContestOption::new("Retain"),
+                ContestOption::new("Remove"),
             ].try_into().unwrap(),
         },
     ].try_into().unwrap();
@@ -223,6 +170,7 @@ pub fn example_election_manifest() -> ElectionManifest {
     .unwrap();
 
     ElectionManifest {
+        schema_version: ELECTION_MANIFEST_SCHEMA_VERSION,
         label: "General Election - The United Realms of Imaginaria".to_string(),
         contests,
         ballot_styles,
@@ -6,7 +6,7 @@
 #![deny(clippy::manual_assert)]
 
 use crate::{
-    election_parameters::ElectionParameters,
+    election_parameters::{ElectionParameters, ELECTION_PARAMETERS_SCHEMA_VERSION},
     fixed_parameters::FixedParameters,
     guardian::GuardianIndex,
     standard_parameters::STANDARD_PARAMETERS,
@@ -35,7 +35,29 @@ pub fn example_election_parameters() -> ElectionParameters {
     };
 
     ElectionParameters {
+        schema_version: ELECTION_PARAMETERS_SCHEMA_VERSION,
         fixed_parameters,
         varying_parameters,
     }
 }
+
+/// Election parameters based on the toy parameters, with `n` guardians and quorum `k`.
+///
+/// These parameters are insecure, and only make tests faster.
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+pub(crate) fn toy_election_parameters(n: u32, k: u32) -> ElectionParameters {
+    use crate::standard_parameters::test_parameter_do_not_use_in_production::TOY_PARAMETERS_01;
+
+    ElectionParameters {
+        schema_version: ELECTION_PARAMETERS_SCHEMA_VERSION,
+        fixed_parameters: (*TOY_PARAMETERS_01).clone(),
+        varying_parameters: VaryingParameters {
+            n: GuardianIndex::from_one_based_index(n).unwrap(),
+            k: GuardianIndex::from_one_based_index(k).unwrap(),
+            date: "2023-05-02".to_string(),
+            info: "The test election".to_string(),
+            ballot_chaining: BallotChaining::Prohibited,
+        },
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use anyhow::Result;
+use util::csprng::Csprng;
+
+use crate::{
+    device::Device, election_manifest::ElectionManifest, election_parameters::ElectionParameters,
+    election_record::PreVotingData, example_election_manifest::example_election_manifest,
+    example_election_parameters::example_election_parameters,
+    guardian_secret_key::GuardianSecretKey,
+};
+
+/// Generates a secret key for each guardian, in order of guardian index, and computes the
+/// [`PreVotingData`] for their public keys.
+///
+/// The keys are drawn from `csprng`, so anyone knowing its seed can decrypt.
+pub fn example_pre_voting_data_with(
+    csprng: &mut Csprng,
+    election_parameters: ElectionParameters,
+    election_manifest: ElectionManifest,
+) -> Result<(Vec<GuardianSecretKey>, PreVotingData)> {
+    let guardian_secret_keys: Vec<_> = election_parameters
+        .varying_parameters
+        .each_guardian_i()
+        .map(|i| GuardianSecretKey::generate(csprng, &election_parameters, i, None))
+        .collect();
+
+    let guardian_public_keys: Vec<_> = guardian_secret_keys
+        .iter()
+        .map(GuardianSecretKey::make_public_key)
+        .collect();
+
+    let pre_voting_data = PreVotingData::compute(
+        election_manifest,
+        election_parameters,
+        &guardian_public_keys,
+    )?;
+
+    Ok((guardian_secret_keys, pre_voting_data))
+}
+
+/// An example [`PreVotingData`] for the example election parameters and manifest, with guardian
+/// keys drawn from `csprng`.
+pub fn example_pre_voting_data(csprng: &mut Csprng) -> Result<PreVotingData> {
+    let (_, pre_voting_data) = example_pre_voting_data_with(
+        csprng,
+        example_election_parameters(),
+        example_election_manifest(),
+    )?;
+    Ok(pre_voting_data)
+}
+
+/// An example encryption [`Device`] for the [`example_pre_voting_data`].
+pub fn example_device(csprng: &mut Csprng) -> Result<Device> {
+    Ok(Device::new(
+        "Some encryption device",
+        example_pre_voting_data(csprng)?,
+    ))
+}
+
+/// An encryption [`Device`] for the example election manifest with toy parameters, three
+/// guardians and quorum two.
+///
+/// The parameters are insecure, and only make tests faster.
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+pub(crate) fn toy_device(csprng: &mut Csprng) -> Device {
+    use crate::example_election_parameters::toy_election_parameters;
+
+    let (_, pre_voting_data) = example_pre_voting_data_with(
+        csprng,
+        toy_election_parameters(3, 2),
+        example_election_manifest(),
+    )
+    .unwrap();
+    Device::new("Some encryption device", pre_voting_data)
+}
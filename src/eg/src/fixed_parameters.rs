@@ -7,15 +7,20 @@
 
 //! This module provides fixed parameter type.
 
-use anyhow::{ensure, Result};
+use anyhow::{ensure, Context, Result};
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
 use serde::{Deserialize, Serialize};
 
 use util::{
     algebra::{Group, ScalarField},
     algebra_utils::{cnt_bits_repr, leading_ones},
     csprng::Csprng,
+    prime::is_prime,
 };
 
+use crate::{hash::HValue, hashes::ParameterBaseHash, serializable::SerializablePretty};
+
 // "Nothing up my sleeve" numbers for use in fixed parameters.
 #[allow(non_camel_case_types)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -132,4 +137,314 @@ impl FixedParameters {
 
         Ok(())
     }
+
+    /// Reads [`FixedParameters`] from a JSON string and validates them.
+    ///
+    /// Unless the `eg_allow_nonstandard_params` feature is enabled, parameters smaller than
+    /// those specified (a `p` of 4096 bits and a `q` of 256 bits) are rejected as insecure, and
+    /// parameters of the specified sizes are rejected if they differ from the
+    /// [standard parameters](static@crate::standard_parameters::STANDARD_PARAMETERS).
+    pub fn try_validate_from_json(json: &str, csprng: &mut Csprng) -> Result<Self> {
+        let self_: Self = serde_json::from_str(json).context("Reading FixedParameters")?;
+
+        self_.validate(csprng)?;
+
+        #[cfg(feature = "eg_allow_nonstandard_params")]
+        warn_once_if_nonstandard_params_allowed();
+
+        #[cfg(not(feature = "eg_allow_nonstandard_params"))]
+        {
+            use crate::standard_parameters::STANDARD_PARAMETERS;
+
+            let p_bits = cnt_bits_repr(self_.group.modulus());
+            let q_bits = cnt_bits_repr(self_.field.order());
+            ensure!(
+                P_BITS_STANDARD <= p_bits && Q_BITS_STANDARD <= q_bits,
+                "Fixed parameters: reduced-size parameters (p of {p_bits} bits, q of {q_bits} bits) are insecure, and require the `eg_allow_nonstandard_params` feature"
+            );
+            ensure!(
+                self_ == *STANDARD_PARAMETERS,
+                "Fixed parameters: nonstandard parameters require the `eg_allow_nonstandard_params` feature"
+            );
+        }
+
+        Ok(self_)
+    }
+}
+
+impl SerializablePretty for FixedParameters {}
+
+/// The kind of fixed parameters accepted by this build, see
+/// [`FixedParameters::try_validate_from_json`].
+pub const FIXED_PARAMETERS_KIND: &str = if cfg!(feature = "eg_allow_nonstandard_params") {
+    "nonstandard (including insecure reduced-size)"
+} else {
+    "standard"
+};
+
+/// Emits a warning, at most once per process, if this build accepts nonstandard parameters
+/// (the `eg_allow_nonstandard_params` feature). Such a build must not be used for a real
+/// election.
+///
+/// This is called on reading parameters with [`FixedParameters::try_validate_from_json`], and
+/// applications may call it on startup. Returns whether the warning was emitted by this call.
+pub fn warn_once_if_nonstandard_params_allowed() -> bool {
+    static WARNED: std::sync::Once = std::sync::Once::new();
+
+    let mut emitted = false;
+    if cfg!(feature = "eg_allow_nonstandard_params") {
+        WARNED.call_once(|| {
+            warn_nonstandard_params_allowed();
+            emitted = true;
+        });
+    }
+    emitted
+}
+
+fn warn_nonstandard_params_allowed() {
+    tracing::warn!(
+        fixed_parameters_kind = FIXED_PARAMETERS_KIND,
+        "INSECURE: this build accepts {FIXED_PARAMETERS_KIND} fixed parameters (feature `eg_allow_nonstandard_params`), and must not be used for a real election"
+    );
+}
+
+/// The number of bits of the group modulus `p` of the standard parameters.
+#[cfg(not(feature = "eg_allow_nonstandard_params"))]
+const P_BITS_STANDARD: usize = 4096;
+
+/// The number of bits of the field order `q` of the standard parameters.
+#[cfg(not(feature = "eg_allow_nonstandard_params"))]
+const Q_BITS_STANDARD: usize = 256;
+
+/// The checks made by [`FixedParametersVerification::compute`], in order.
+pub const FIXED_PARAMETERS_CHECKS: [&str; 4] = [
+    "p is prime",
+    "q is prime",
+    "q divides p - 1",
+    "q is the order of the generator g",
+];
+
+/// The outcome of one of the checks in a [`FixedParametersVerification`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixedParametersCheck {
+    /// Description of the check.
+    pub check: String,
+
+    /// Whether the check passed.
+    pub passed: bool,
+}
+
+/// An auditable record of the verification of a [`FixedParameters`].
+///
+/// Records each check made and its outcome, along with the parameter base hash `H_P`
+/// computed from the verified parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixedParametersVerification {
+    /// The checks made, in order.
+    pub checks: Vec<FixedParametersCheck>,
+
+    /// The parameter base hash `H_P` computed from the parameters.
+    pub h_p: HValue,
+}
+
+impl FixedParametersVerification {
+    /// Performs each check on the [`FixedParameters`]. Unlike [`FixedParameters::validate`],
+    /// this does not stop at the first failure.
+    pub fn compute(fixed_parameters: &FixedParameters, csprng: &mut Csprng) -> Self {
+        let p = fixed_parameters.group.modulus();
+        let q = fixed_parameters.field.order();
+        let g = fixed_parameters.group.generator();
+        let p_minus_1 = p - BigUint::one();
+
+        let outcomes = [
+            is_prime(p, csprng),
+            is_prime(q, csprng),
+            (&p_minus_1 % q).is_zero(),
+            !g.as_biguint().is_one() && g.as_biguint().modpow(q, p).is_one(),
+        ];
+
+        let checks = FIXED_PARAMETERS_CHECKS
+            .into_iter()
+            .zip(outcomes)
+            .map(|(check, passed)| FixedParametersCheck {
+                check: check.to_string(),
+                passed,
+            })
+            .collect();
+
+        let h_p = ParameterBaseHash::compute(fixed_parameters).h_p;
+
+        Self { checks, h_p }
+    }
+
+    /// Returns `true` iff there is at least one check, and every check passed.
+    pub fn all_passed(&self) -> bool {
+        !self.checks.is_empty() && self.checks.iter().all(|check| check.passed)
+    }
+
+    /// Returns `true` iff the record has exactly the [`FIXED_PARAMETERS_CHECKS`], in order.
+    pub fn is_complete(&self) -> bool {
+        self.checks
+            .iter()
+            .map(|check| check.check.as_str())
+            .eq(FIXED_PARAMETERS_CHECKS)
+    }
+
+    /// Returns `true` iff this records a passed verification of the given [`FixedParameters`],
+    /// i.e., it is complete, every check passed, and the recorded `H_P` matches that of the
+    /// parameters.
+    ///
+    /// The record is not signed, so this is not proof that the checks were made. Rely on a record
+    /// read back instead of verifying again only if it was written by a trusted run.
+    pub fn is_passed_verification_of(&self, fixed_parameters: &FixedParameters) -> bool {
+        self.is_complete()
+            && self.all_passed()
+            && self.h_p == ParameterBaseHash::compute(fixed_parameters).h_p
+    }
+
+    /// Reads a `FixedParametersVerification` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading FixedParametersVerification")
+    }
+}
+
+impl SerializablePretty for FixedParametersVerification {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::standard_parameters::test_parameter_do_not_use_in_production::TOY_PARAMETERS_01;
+
+    #[test]
+    fn test_try_validate_from_json() {
+        use crate::standard_parameters::STANDARD_PARAMETERS;
+
+        let mut csprng = Csprng::new(b"test_try_validate_from_json");
+
+        let json = serde_json::to_string(&*STANDARD_PARAMETERS).unwrap();
+        let fixed_parameters = FixedParameters::try_validate_from_json(&json, &mut csprng).unwrap();
+        assert_eq!(fixed_parameters, *STANDARD_PARAMETERS);
+
+        assert!(FixedParameters::try_validate_from_json("{}", &mut csprng).is_err());
+
+        // The toy parameters have an undersized `p`.
+        let json = serde_json::to_string(&*TOY_PARAMETERS_01).unwrap();
+        let result = FixedParameters::try_validate_from_json(&json, &mut csprng);
+        #[cfg(not(feature = "eg_allow_nonstandard_params"))]
+        assert!(result.unwrap_err().to_string().contains("insecure"));
+        #[cfg(feature = "eg_allow_nonstandard_params")]
+        assert_eq!(result.unwrap(), *TOY_PARAMETERS_01);
+    }
+
+    #[test]
+    fn test_is_passed_verification_of() {
+        let mut csprng = Csprng::new(b"test_is_passed_verification_of");
+        let fixed_parameters = &*TOY_PARAMETERS_01;
+
+        let verification = FixedParametersVerification::compute(fixed_parameters, &mut csprng);
+        assert!(verification.is_passed_verification_of(fixed_parameters));
+
+        // A later run can rely on the verification, as read back from its record.
+        let mut buf = Vec::new();
+        verification.to_stdiowrite_pretty(&mut buf).unwrap();
+        let previous = FixedParametersVerification::from_stdioread(&mut buf.as_slice()).unwrap();
+        assert_eq!(previous, verification);
+        assert!(previous.is_passed_verification_of(fixed_parameters));
+
+        // But not for other parameters, here with the generator `g^2`.
+        let group = &fixed_parameters.group;
+        let g_squared = group.generator().mul(&group.generator(), group);
+        let mut json = serde_json::to_value(fixed_parameters).unwrap();
+        json["group"]["g"] = serde_json::to_value(
+            util::base16::to_string_uppercase_hex_bits(g_squared.as_biguint(), 4096).unwrap(),
+        )
+        .unwrap();
+        let altered_parameters: FixedParameters = serde_json::from_value(json).unwrap();
+        assert!(!previous.is_passed_verification_of(&altered_parameters));
+
+        // Nor if a check failed.
+        let mut failed = previous.clone();
+        failed.checks[0].passed = false;
+        assert!(!failed.is_passed_verification_of(fixed_parameters));
+
+        // Nor if checks are missing, in particular if there are none at all.
+        let mut incomplete = previous;
+        incomplete.checks.pop();
+        assert!(incomplete.all_passed());
+        assert!(!incomplete.is_passed_verification_of(fixed_parameters));
+
+        incomplete.checks.clear();
+        assert!(!incomplete.all_passed());
+        assert!(!incomplete.is_passed_verification_of(fixed_parameters));
+    }
+
+    #[test]
+    fn test_fixed_parameters_verification() {
+        let mut csprng = Csprng::new(b"test_fixed_parameters_verification");
+        let fixed_parameters = &*TOY_PARAMETERS_01;
+
+        let verification = FixedParametersVerification::compute(fixed_parameters, &mut csprng);
+        assert!(verification.all_passed());
+
+        // The artifact has an entry for each check, and `H_P`.
+        let json: serde_json::Value = serde_json::from_str(&verification.to_json_pretty()).unwrap();
+        assert_eq!(
+            json["checks"],
+            serde_json::json!([
+                { "check": "p is prime", "passed": true },
+                { "check": "q is prime", "passed": true },
+                { "check": "q divides p - 1", "passed": true },
+                { "check": "q is the order of the generator g", "passed": true },
+            ])
+        );
+        assert_eq!(
+            json["h_p"],
+            serde_json::to_value(ParameterBaseHash::compute(fixed_parameters).h_p).unwrap()
+        );
+
+        // A generator of the wrong order fails just that check.
+        let mut bad_parameters = fixed_parameters.clone();
+        bad_parameters.group = Group::new_unchecked(
+            fixed_parameters.group.modulus().clone(),
+            fixed_parameters.field.order().clone(),
+            BigUint::from(2_u8),
+        );
+        let verification = FixedParametersVerification::compute(&bad_parameters, &mut csprng);
+        assert!(!verification.all_passed());
+        assert_eq!(
+            verification
+                .checks
+                .iter()
+                .filter(|check| !check.passed)
+                .count(),
+            1
+        );
+    }
+
+    #[cfg(feature = "eg_allow_nonstandard_params")]
+    #[test]
+    fn test_warn_nonstandard_params_allowed() {
+        let ((), events) = crate::event_capture::EventCapture::capture(
+            tracing::Level::WARN,
+            None,
+            warn_nonstandard_params_allowed,
+        );
+        assert_eq!(events.len(), 1);
+        assert!(events[0].starts_with(&format!(
+            "fixed_parameters_kind={FIXED_PARAMETERS_KIND} message=INSECURE"
+        )));
+        assert_ne!(FIXED_PARAMETERS_KIND, "standard");
+
+        // The warning is emitted only once, whether or not by this call.
+        warn_once_if_nonstandard_params_allowed();
+        assert!(!warn_once_if_nonstandard_params_allowed());
+    }
+
+    #[cfg(not(feature = "eg_allow_nonstandard_params"))]
+    #[test]
+    fn test_no_warning_for_standard_params() {
+        assert_eq!(FIXED_PARAMETERS_KIND, "standard");
+        assert!(!warn_once_if_nonstandard_params_allowed());
+    }
 }
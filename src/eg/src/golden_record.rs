@@ -0,0 +1,188 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! A deterministic election record for catching hashing and serialization regressions.
+//!
+//! The record is generated entirely from a seed, so its canonical bytes and hashes are fixed.
+//! A test comparing them to known values fails on any change to a hashed or serialized value,
+//! surfacing unintended drift from the spec. Everything in it is insecure, as anyone knowing the
+//! seed can recompute the guardians' secret keys.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use util::csprng::Csprng;
+
+use crate::{
+    ballot::BallotEncrypted,
+    ballot_style::BallotStyleIndex,
+    contest_selection::ContestSelection,
+    device::Device,
+    election_manifest::ElectionManifest,
+    election_parameters::ElectionParameters,
+    election_record::PreVotingData,
+    example_election_manifest::example_election_manifest,
+    example_election_parameters::example_election_parameters,
+    guardian_public_key::GuardianPublicKey,
+    hash::{eg_h, HValue},
+    hashes::Hashes,
+    hashes_ext::HashesExt,
+    joint_election_public_key::JointElectionPublicKey,
+    key_ceremony::KeyCeremony,
+    serializable::SerializableCanonical,
+};
+
+/// A full election record, up to one encrypted ballot, generated deterministically from a seed
+/// by [`export_golden_record`].
+#[derive(Debug, Serialize)]
+pub struct GoldenRecord {
+    /// The election parameters, those of the example election.
+    pub election_parameters: ElectionParameters,
+
+    /// The election manifest, that of the example election.
+    pub election_manifest: ElectionManifest,
+
+    /// The guardians' public keys, in order of guardian index.
+    pub guardian_public_keys: Vec<GuardianPublicKey>,
+
+    /// The joint election public key.
+    pub joint_election_public_key: JointElectionPublicKey,
+
+    /// Hashes H_P, H_M, H_B.
+    pub hashes: Hashes,
+
+    /// Hash H_E.
+    pub hashes_ext: HashesExt,
+
+    /// A ballot of the first ballot style, with random selections.
+    pub ballot: BallotEncrypted,
+}
+
+impl GoldenRecord {
+    /// Returns `H(0, canonical bytes)` of the whole record, which changes with any of its
+    /// serialized values.
+    pub fn digest(&self) -> Result<HValue> {
+        let canonical_bytes = self.to_canonical_bytes()?;
+        Ok(eg_h(&HValue::default(), &canonical_bytes))
+    }
+}
+
+impl SerializableCanonical for GoldenRecord {}
+
+/// Generates a [`GoldenRecord`] for the example election: runs the key ceremony and encrypts
+/// one ballot, drawing all randomness from a [`Csprng`] seeded with `seed`.
+///
+/// The same seed always gives the same record.
+pub fn export_golden_record(seed: &[u8]) -> Result<GoldenRecord> {
+    let mut csprng = Csprng::new(seed);
+
+    let election_parameters = example_election_parameters();
+    let election_manifest = example_election_manifest();
+
+    let key_ceremony = KeyCeremony::run(&mut csprng, &election_parameters, None)
+        .context("Running the key ceremony")?;
+
+    let pre_voting_data = PreVotingData::compute(
+        election_manifest,
+        election_parameters,
+        &key_ceremony.guardian_public_keys,
+    )?;
+    let device = Device::new("Golden record device", pre_voting_data);
+
+    let ballot_style_ix = BallotStyleIndex::from_one_based_index(1)?;
+    let manifest = device.header.manifest();
+    let ballot_style = manifest
+        .ballot_styles
+        .get(ballot_style_ix)
+        .context("The example election manifest has no ballot styles")?;
+
+    let mut selections = BTreeMap::new();
+    for &contest_ix in &ballot_style.contests {
+        let contest = manifest
+            .contests
+            .get(contest_ix)
+            .with_context(|| format!("Contest {contest_ix} is not in the manifest"))?;
+        let selection = ContestSelection::new_pick_random(
+            &mut csprng,
+            contest.selection_limit,
+            contest.options.len(),
+        );
+        selections.insert(contest_ix, selection);
+    }
+
+    let primary_nonce: [u8; 32] = std::array::from_fn(|_| csprng.next_u8());
+    let ballot = BallotEncrypted::new_from_selections(
+        ballot_style_ix,
+        &device,
+        "2024-01-01",
+        &mut csprng,
+        &primary_nonce,
+        &selections,
+    )?;
+
+    let PreVotingData {
+        manifest: election_manifest,
+        parameters: election_parameters,
+        hashes,
+        hashes_ext,
+        public_key: joint_election_public_key,
+    } = device.header;
+
+    Ok(GoldenRecord {
+        election_parameters,
+        election_manifest,
+        guardian_public_keys: key_ceremony.guardian_public_keys,
+        joint_election_public_key,
+        hashes,
+        hashes_ext,
+        ballot,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_golden_record() {
+        let golden_record = export_golden_record(b"electionguard golden record").unwrap();
+
+        // The record is reproducible.
+        let again = export_golden_record(b"electionguard golden record").unwrap();
+        assert_eq!(
+            golden_record.to_canonical_bytes().unwrap(),
+            again.to_canonical_bytes().unwrap()
+        );
+
+        assert_eq!(
+            golden_record.digest().unwrap().to_hex_string(),
+            "71E074DFDC65151B048CDB075820B81AFDFE92BEA66E117D67C30C56485E865C"
+        );
+        assert_eq!(
+            golden_record.hashes.h_p.to_hex_string(),
+            "2B3B025E50E09C119CBA7E9448ACD1CABC9447EF39BF06327D81C665CDD86296"
+        );
+        assert_eq!(
+            golden_record.hashes.h_m.to_hex_string(),
+            "7CAAAD91B51C5EF9E406EBFACA47AACB5C9BDA82D553719A0279076ED1E284C9"
+        );
+        assert_eq!(
+            golden_record.hashes.h_b.to_hex_string(),
+            "97A6B0AE51E0A28E95B66CEA35AE5A3FDC71C1B1D9BE412ABB7345461E9C76FE"
+        );
+        assert_eq!(
+            golden_record.hashes_ext.h_e.to_hex_string(),
+            "068C08C32E763C0EAAC1A9823922BDE1D37B5F07E17026650A597979569BCB61"
+        );
+        assert_eq!(
+            golden_record.ballot.confirmation_code().to_hex_string(),
+            "5AD6F10657FAB219028625F391DC5886B4FC4E37637DB9FFA8550FC317164FE8"
+        );
+    }
+}
@@ -7,6 +7,8 @@
 
 //! This module implements guardian indices.
 
+use itertools::Itertools;
+
 use crate::index::Index;
 
 #[doc(hidden)]
@@ -23,3 +25,36 @@ pub struct GuardianIndexTag;
 /// - [`GuardianPublicKey::i`](crate::guardian_public_key::GuardianPublicKey::i), 1 <= [`i`](crate::guardian_public_key::GuardianPublicKey::i) <= [`n`](crate::varying_parameters::VaryingParameters::n).
 ///
 pub type GuardianIndex = Index<GuardianIndexTag>;
+
+impl GuardianIndex {
+    /// Returns an iterator over each quorum of `k` of the guardians `1..=n`, i.e., each
+    /// `k`-element subset of the guardians, in lexicographic order. The guardians within each
+    /// quorum are in increasing order.
+    ///
+    /// Useful, e.g., to confirm that every quorum decrypts a tally to the same result.
+    pub fn quorum_combinations(
+        n: GuardianIndex,
+        k: GuardianIndex,
+    ) -> impl Iterator<Item = Vec<GuardianIndex>> {
+        GuardianIndex::iter_range_inclusive(GuardianIndex::MIN, n).combinations(k.as_quantity())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_quorum_combinations() {
+        let gi = |i| GuardianIndex::from_one_based_index(i).unwrap();
+
+        let quorums: Vec<Vec<u32>> = GuardianIndex::quorum_combinations(gi(4), gi(2))
+            .map(|quorum| quorum.iter().map(|i| i.get_one_based_u32()).collect())
+            .collect();
+        assert_eq!(quorums, [[1, 2], [1, 3], [1, 4], [2, 3], [2, 4], [3, 4]]);
+
+        assert_eq!(GuardianIndex::quorum_combinations(gi(5), gi(5)).count(), 1);
+        assert_eq!(GuardianIndex::quorum_combinations(gi(2), gi(3)).count(), 0);
+    }
+}
@@ -9,7 +9,10 @@
 use crate::hash::eg_h;
 use crate::{
     fixed_parameters::FixedParameters,
-    guardian_secret_key::{CoefficientCommitment, SecretCoefficient},
+    guardian_secret_key::{
+        CoefficientCommitment, CoefficientCommitments, SecretCoefficient, SecretCoefficients,
+    },
+    hash::HValue,
     hashes::ParameterBaseHash,
 };
 use serde::{Deserialize, Serialize};
@@ -62,6 +65,19 @@ impl CoefficientProof {
         h: &GroupElement,
     ) -> FieldElement {
         let h_p = ParameterBaseHash::compute(fixed_parameters).h_p;
+        Self::challenge_with_h_p(fixed_parameters, &h_p, i, j, coefficient, h)
+    }
+
+    /// Same as [`CoefficientProof::challenge`], but with the parameter base hash `h_p`
+    /// already computed, so that it can be shared across coefficients.
+    fn challenge_with_h_p(
+        fixed_parameters: &FixedParameters,
+        h_p: &HValue,
+        i: u32,
+        j: u32,
+        coefficient: &GroupElement,
+        h: &GroupElement,
+    ) -> FieldElement {
         let group = &fixed_parameters.group;
         // v = 0x10 | b(i,4) | b(j,4) | b(coefficient,512) | b(h,512) for standard parameters
         let mut v = vec![0x10];
@@ -69,7 +85,7 @@ impl CoefficientProof {
         v.extend_from_slice(j.to_be_bytes().as_slice());
         v.extend_from_slice(coefficient.to_be_bytes_left_pad(group).as_slice());
         v.extend_from_slice(h.to_be_bytes_left_pad(group).as_slice());
-        let c_bytes = eg_h(&h_p, &v);
+        let c_bytes = eg_h(h_p, &v);
         //Get field element from challenge, here the challenge is reduced mod `q`
         FieldElement::from_bytes_be(c_bytes.0.as_slice(), &fixed_parameters.field)
     }
@@ -90,6 +106,67 @@ impl CoefficientProof {
         j: u32,
         coefficient: &SecretCoefficient,
         commitment: &CoefficientCommitment,
+    ) -> Self {
+        let h_p = ParameterBaseHash::compute(fixed_parameters).h_p;
+        Self::new_with_h_p(
+            csprng,
+            fixed_parameters,
+            &h_p,
+            i,
+            j,
+            coefficient,
+            commitment,
+        )
+    }
+
+    /// This function computes the [`CoefficientProof`]s for all of a guardian's [`SecretCoefficients`],
+    /// where the proof for the coefficient at (0-based) position `j` uses the coefficient index `j`.
+    ///
+    /// The parameter base hash is computed only once and shared across all challenges.
+    /// Each resulting proof is still independent and can be verified by [`CoefficientProof::validate`].
+    /// Randomness is drawn from `csprng` in the same order as by repeated calls to [`CoefficientProof::new`].
+    ///
+    /// The arguments are
+    /// - `csprng` - secure randomness generator
+    /// - `fixed_parameters` - the fixed parameters
+    /// - `i` - the guardian index
+    /// - `secret_coefficients` - the guardian's secret coefficients
+    /// - `coefficient_commitments` - the corresponding coefficient commitments
+    pub fn new_batch(
+        csprng: &mut Csprng,
+        fixed_parameters: &FixedParameters,
+        i: u32,
+        secret_coefficients: &SecretCoefficients,
+        coefficient_commitments: &CoefficientCommitments,
+    ) -> Vec<Self> {
+        let h_p = ParameterBaseHash::compute(fixed_parameters).h_p;
+        secret_coefficients
+            .0
+            .iter()
+            .zip(&coefficient_commitments.0)
+            .enumerate()
+            .map(|(j, (coefficient, commitment))| {
+                Self::new_with_h_p(
+                    csprng,
+                    fixed_parameters,
+                    &h_p,
+                    i,
+                    j as u32,
+                    coefficient,
+                    commitment,
+                )
+            })
+            .collect()
+    }
+
+    fn new_with_h_p(
+        csprng: &mut Csprng,
+        fixed_parameters: &FixedParameters,
+        h_p: &HValue,
+        i: u32,
+        j: u32,
+        coefficient: &SecretCoefficient,
+        commitment: &CoefficientCommitment,
     ) -> Self {
         let coefficient = &coefficient.0;
         let commitment = &commitment.0;
@@ -98,7 +175,7 @@ impl CoefficientProof {
         let u = field.random_field_elem(csprng);
         let h = fixed_parameters.group.g_exp(&u);
         // Compute challenge
-        let c = Self::challenge(fixed_parameters, i, j, commitment, &h);
+        let c = Self::challenge_with_h_p(fixed_parameters, h_p, i, j, commitment, &h);
         // Compute response
         let s = c.mul(coefficient, field);
         let v = u.sub(&s, field);
@@ -157,7 +234,9 @@ mod test {
     use crate::{
         example_election_parameters::example_election_parameters,
         fixed_parameters::FixedParameters,
-        guardian_secret_key::{CoefficientCommitment, SecretCoefficient},
+        guardian_secret_key::{
+            CoefficientCommitment, CoefficientCommitments, SecretCoefficient, SecretCoefficients,
+        },
     };
 
     use super::CoefficientProof;
@@ -228,4 +307,53 @@ mod test {
             "Proof should fail"
         );
     }
+
+    #[test]
+    fn test_guardian_proof_generation_batch() {
+        let fixed_parameters = example_election_parameters().fixed_parameters;
+
+        // A polynomial of degree 4.
+        let mut csprng = Csprng::new(b"test_guardian_proof_generation_batch");
+        let secret_coefficients = SecretCoefficients(
+            (0..5)
+                .map(|_| SecretCoefficient(fixed_parameters.field.random_field_elem(&mut csprng)))
+                .collect(),
+        );
+        let coefficient_commitments =
+            CoefficientCommitments::new(&fixed_parameters, &secret_coefficients);
+
+        let i: u32 = 3;
+
+        let mut csprng_batch = Csprng::new(b"proofs");
+        let proofs = CoefficientProof::new_batch(
+            &mut csprng_batch,
+            &fixed_parameters,
+            i,
+            &secret_coefficients,
+            &coefficient_commitments,
+        );
+        assert_eq!(proofs.len(), 5);
+
+        let mut csprng_single = Csprng::new(b"proofs");
+        for (j, proof) in proofs.iter().enumerate() {
+            let j = j as u32;
+            let commitment = &coefficient_commitments.0[j as usize];
+            assert!(
+                proof.validate(&fixed_parameters, i, j, commitment).is_ok(),
+                "Proof should be valid"
+            );
+
+            // The batch gives the same proofs as generating them one at a time.
+            let proof_single = CoefficientProof::new(
+                &mut csprng_single,
+                &fixed_parameters,
+                i,
+                j,
+                &secret_coefficients.0[j as usize],
+                commitment,
+            );
+            assert_eq!(proof.challenge, proof_single.challenge);
+            assert_eq!(proof.response, proof_single.response);
+        }
+    }
 }
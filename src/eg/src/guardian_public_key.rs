@@ -20,14 +20,28 @@ use crate::{
         validate_guardian_public_key_info, GuardianPublicKeyInfo, PublicKeyValidationError,
     },
     guardian_secret_key::CoefficientCommitments,
+    schema_version::{
+        initial_schema_version, is_initial_schema_version, validate_schema_version, SchemaVersion,
+        INITIAL_SCHEMA_VERSION,
+    },
     serializable::SerializablePretty,
 };
 
+/// The version of the schema of [`GuardianPublicKey`] written and supported by this library.
+pub const GUARDIAN_PUBLIC_KEY_SCHEMA_VERSION: SchemaVersion = INITIAL_SCHEMA_VERSION;
+
 /// The public key for a guardian.
 ///
 /// See Section `3.2.2` for details on the generation of public keys.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GuardianPublicKey {
+    /// The version of the schema of the guardian public key, see [`crate::schema_version`].
+    #[serde(
+        default = "initial_schema_version",
+        skip_serializing_if = "is_initial_schema_version"
+    )]
+    pub schema_version: SchemaVersion,
+
     /// Guardian index, 1 <= i <= [`n`](crate::varying_parameters::VaryingParameters::n).
     pub i: GuardianIndex,
 
@@ -103,6 +117,12 @@ impl GuardianPublicKey {
         let self_: Self =
             serde_json::from_reader(stdioread).context("Reading GuardianPublicKey")?;
 
+        validate_schema_version(
+            "GuardianPublicKey",
+            self_.schema_version,
+            GUARDIAN_PUBLIC_KEY_SCHEMA_VERSION,
+        )?;
+
         self_.validate(election_parameters)?;
 
         Ok(self_)
@@ -175,4 +195,95 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_guardian_name() {
+        use crate::{
+            guardian::GuardianIndex,
+            guardian_public_key::GuardianPublicKey,
+            guardian_public_key_info::{GuardianPublicKeyInfo, PublicKeyValidationError},
+            label::{LabelValidationError, LABEL_MAX_LEN_CHARS},
+        };
+
+        let mut csprng = Csprng::new(b"test_guardian_name");
+        let election_parameters = example_election_parameters();
+        let i = GuardianIndex::from_one_based_index(1).unwrap();
+
+        let guardian_secret_key = GuardianSecretKey::generate(
+            &mut csprng,
+            &election_parameters,
+            i,
+            Some("Guardian Ålice".to_string()),
+        );
+        let guardian_public_key = guardian_secret_key.make_public_key();
+        assert_eq!(guardian_public_key.guardian_name(), Some("Guardian Ålice"));
+
+        // The name round-trips with the key.
+        let mut buf = Vec::new();
+        guardian_public_key.to_stdiowrite(&mut buf).unwrap();
+        let loaded =
+            GuardianPublicKey::from_stdioread_validated(&mut buf.as_slice(), &election_parameters)
+                .unwrap();
+        assert_eq!(loaded.guardian_name(), Some("Guardian Ålice"));
+
+        // An illegal name is rejected.
+        let mut guardian_public_key = guardian_public_key;
+        guardian_public_key.opt_name = Some("Guardian\nÅlice".to_string());
+        assert!(matches!(
+            guardian_public_key.validate(&election_parameters),
+            Err(PublicKeyValidationError::NameContainsNewLine)
+        ));
+
+        guardian_public_key.opt_name = Some("x".repeat(LABEL_MAX_LEN_CHARS + 1));
+        assert!(matches!(
+            guardian_public_key.validate(&election_parameters),
+            Err(PublicKeyValidationError::InvalidName(
+                LabelValidationError::LabelTooLong { .. }
+            ))
+        ));
+
+        guardian_public_key.opt_name = None;
+        assert_eq!(guardian_public_key.guardian_name(), None);
+        guardian_public_key.validate(&election_parameters).unwrap();
+    }
+
+    #[test]
+    fn test_guardian_public_key_schema_version() {
+        use crate::{
+            guardian::GuardianIndex,
+            guardian_public_key::{GuardianPublicKey, GUARDIAN_PUBLIC_KEY_SCHEMA_VERSION},
+            schema_version::SchemaVersionError,
+        };
+
+        let mut csprng = Csprng::new(b"test_guardian_public_key_schema_version");
+        let election_parameters = example_election_parameters();
+        let i = GuardianIndex::from_one_based_index(1).unwrap();
+        let guardian_public_key =
+            GuardianSecretKey::generate(&mut csprng, &election_parameters, i, None)
+                .make_public_key();
+
+        // The current version is not written, and a key without one loads.
+        let mut json = serde_json::to_value(&guardian_public_key).unwrap();
+        assert!(json.get("schema_version").is_none());
+        let bytes = serde_json::to_vec(&json).unwrap();
+        GuardianPublicKey::from_stdioread_validated(&mut bytes.as_slice(), &election_parameters)
+            .unwrap();
+
+        // A key of a later version is rejected.
+        json["schema_version"] = (GUARDIAN_PUBLIC_KEY_SCHEMA_VERSION + 1).into();
+        let bytes = serde_json::to_vec(&json).unwrap();
+        let err = GuardianPublicKey::from_stdioread_validated(
+            &mut bytes.as_slice(),
+            &election_parameters,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SchemaVersionError>(),
+            Some(&SchemaVersionError::UnsupportedSchemaVersion {
+                type_name: "GuardianPublicKey".to_string(),
+                found: GUARDIAN_PUBLIC_KEY_SCHEMA_VERSION + 1,
+                supported: GUARDIAN_PUBLIC_KEY_SCHEMA_VERSION,
+            })
+        );
+    }
 }
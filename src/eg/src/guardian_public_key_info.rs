@@ -9,8 +9,11 @@
 //! which allows read access and validation of public key data.
 
 use crate::{
-    election_parameters::ElectionParameters, guardian::GuardianIndex,
-    guardian_coeff_proof::CoefficientProof, guardian_secret_key::CoefficientCommitments,
+    election_parameters::ElectionParameters,
+    guardian::GuardianIndex,
+    guardian_coeff_proof::CoefficientProof,
+    guardian_secret_key::CoefficientCommitments,
+    label::{validate_label, LabelValidationError},
 };
 use thiserror::Error;
 
@@ -25,6 +28,11 @@ pub trait GuardianPublicKeyInfo {
     /// Short name with which to refer to the guardian. Should not have any line breaks.
     fn opt_name(&self) -> &Option<String>;
 
+    /// The guardian's name, if any, e.g. for audit displays.
+    fn guardian_name(&self) -> Option<&str> {
+        self.opt_name().as_deref()
+    }
+
     /// "Published" polynomial coefficient commitments.
     fn coefficient_commitments(&self) -> &CoefficientCommitments;
 
@@ -41,6 +49,9 @@ pub enum PublicKeyValidationError {
     /// Occurs if the guardian's name contains a newline character.
     #[error("The guardian's name must not contain a newline.")]
     NameContainsNewLine,
+    /// Occurs if the guardian's name is not a valid label, e.g., is too long.
+    #[error("The guardian's name is invalid: {0}")]
+    InvalidName(#[from] LabelValidationError),
     /// Occurs if the guardian's commitment vector is not of length [`k`](crate::varying_parameters::VaryingParameters::k).
     #[error("Expected k={k} coefficient commitments, found {c_len}")]
     InadequateNumberOfCommitments { k: usize, c_len: usize },
@@ -76,6 +87,7 @@ pub(crate) fn validate_guardian_public_key_info(
         if name.contains('\n') {
             return Err(PublicKeyValidationError::NameContainsNewLine);
         }
+        validate_label(name, &format!("guardian {i}"))?;
     }
 
     let c_len = gpki.coefficient_commitments().0.len();
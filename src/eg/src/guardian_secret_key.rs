@@ -7,10 +7,10 @@
 
 //! This module provides implementation of guardian secret keys. For more details see Section `3.2` of the Electionguard specification `2.0.0`.
 
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
 use serde::{Deserialize, Serialize};
 use util::{
-    algebra::{FieldElement, GroupElement},
+    algebra::{FieldElement, Group, GroupElement, ScalarField},
     csprng::Csprng,
 };
 
@@ -19,11 +19,15 @@ use crate::{
     fixed_parameters::FixedParameters,
     guardian::GuardianIndex,
     guardian_coeff_proof::CoefficientProof,
-    guardian_public_key::GuardianPublicKey,
+    guardian_public_key::{GuardianPublicKey, GUARDIAN_PUBLIC_KEY_SCHEMA_VERSION},
     guardian_public_key_info::{
         validate_guardian_public_key_info, GuardianPublicKeyInfo, PublicKeyValidationError,
     },
-    serializable::SerializablePretty,
+    schema_version::{
+        initial_schema_version, is_initial_schema_version, validate_schema_version, SchemaVersion,
+        INITIAL_SCHEMA_VERSION,
+    },
+    serializable::ContainsSecretMaterial,
 };
 
 /// A polynomial coefficient used to define a secret key sharing.
@@ -59,6 +63,20 @@ impl SecretCoefficients {
                 .collect(),
         )
     }
+
+    /// This function evaluates the polynomial defined by the [`SecretCoefficients`] at `x`
+    /// using Horner's method.
+    ///
+    /// Evaluating at a guardian index `l` gives the key share `P_i(l)` (cf. Equations `9` and `18`).
+    ///
+    /// The arguments are
+    /// - `x` - the point at which to evaluate the polynomial
+    /// - `field` - the scalar field
+    pub fn evaluate_at(&self, x: &FieldElement, field: &ScalarField) -> FieldElement {
+        self.0.iter().rev().fold(ScalarField::zero(), |acc, coeff| {
+            acc.mul(x, field).add(&coeff.0, field)
+        })
+    }
 }
 
 /// A commitment to a single [`SecretCoefficient`].
@@ -98,13 +116,47 @@ impl CoefficientCommitments {
                 .collect(),
         )
     }
+
+    /// This function evaluates the committed polynomial "in the exponent" at `x`, i.e.,
+    /// it computes `prod_m K_m^(x^m) = g^P(x)`, where `P` is the polynomial defined by the
+    /// corresponding [`SecretCoefficients`].
+    ///
+    /// This is the right-hand side of Equation `21` when `x` is a guardian index.
+    ///
+    /// The arguments are
+    /// - `x` - the point at which to evaluate
+    /// - `group` - the group
+    /// - `field` - the scalar field
+    pub fn evaluate_in_exponent(
+        &self,
+        x: &FieldElement,
+        group: &Group,
+        field: &ScalarField,
+    ) -> GroupElement {
+        let mut x_pow_m = ScalarField::one();
+        self.0.iter().fold(Group::one(), |prod, k_m| {
+            let factor = k_m.0.exp(&x_pow_m, group);
+            x_pow_m = x_pow_m.mul(x, field);
+            prod.mul(&factor, group)
+        })
+    }
 }
 
+/// The version of the schema of [`GuardianSecretKey`] written and supported by this library.
+pub const GUARDIAN_SECRET_KEY_SCHEMA_VERSION: SchemaVersion = INITIAL_SCHEMA_VERSION;
+
 /// The secret key for a guardian.
 ///
 /// See Section `3.2.2` for details on the generation of secret keys.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GuardianSecretKey {
+    /// The version of the schema of the guardian secret key, see [`crate::schema_version`].
+    #[serde(
+        default = "initial_schema_version",
+        skip_serializing_if = "is_initial_schema_version"
+    )]
+    pub schema_version: SchemaVersion,
+
     /// Guardian index, 1 <= i <= [`n`](crate::varying_parameters::VaryingParameters::n).
     pub i: GuardianIndex,
 
@@ -160,24 +212,16 @@ impl GuardianSecretKey {
         let coefficient_commitments =
             CoefficientCommitments::new(fixed_parameters, &secret_coefficients);
 
-        let coefficient_proofs = secret_coefficients
-            .0
-            .iter()
-            .zip(&coefficient_commitments.0)
-            .enumerate()
-            .map(|(j, (coef, com))| {
-                CoefficientProof::new(
-                    csprng,
-                    fixed_parameters,
-                    i.get_one_based_u32(),
-                    j as u32,
-                    coef,
-                    com,
-                )
-            })
-            .collect();
+        let coefficient_proofs = CoefficientProof::new_batch(
+            csprng,
+            fixed_parameters,
+            i.get_one_based_u32(),
+            &secret_coefficients,
+            &coefficient_commitments,
+        );
 
         GuardianSecretKey {
+            schema_version: GUARDIAN_SECRET_KEY_SCHEMA_VERSION,
             secret_coefficients,
             coefficient_commitments,
             coefficient_proofs,
@@ -210,6 +254,7 @@ impl GuardianSecretKey {
     /// This function computes the [`GuardianPublicKey`] corresponding to the [`GuardianSecretKey`].
     pub fn make_public_key(&self) -> GuardianPublicKey {
         GuardianPublicKey {
+            schema_version: GUARDIAN_PUBLIC_KEY_SCHEMA_VERSION,
             i: self.i,
             opt_name: self.opt_name.clone(),
             coefficient_commitments: self.coefficient_commitments.clone(),
@@ -217,6 +262,40 @@ impl GuardianSecretKey {
         }
     }
 
+    /// Computes the [`GuardianPublicKey`]s of all the guardians `1..=n` from their secret keys,
+    /// e.g., when a single machine holds the secret keys of all the guardians for testing or a
+    /// demo.
+    ///
+    /// Fails unless there is exactly one secret key for each guardian. The public keys are
+    /// returned in order of guardian index.
+    pub fn make_all_public_keys(
+        guardian_secret_keys: &[GuardianSecretKey],
+        election_parameters: &ElectionParameters,
+    ) -> Result<Vec<GuardianPublicKey>> {
+        let varying_parameters = &election_parameters.varying_parameters;
+        ensure!(
+            guardian_secret_keys.len() == varying_parameters.n.get_one_based_usize(),
+            "Expected the secret keys of all {} guardians, got {}",
+            varying_parameters.n,
+            guardian_secret_keys.len()
+        );
+
+        varying_parameters
+            .each_guardian_i()
+            .map(|i| {
+                let mut secret_keys = guardian_secret_keys.iter().filter(|sk| sk.i == i);
+                let secret_key = secret_keys
+                    .next()
+                    .with_context(|| format!("Missing the secret key of guardian {i}"))?;
+                ensure!(
+                    secret_keys.next().is_none(),
+                    "Guardian {i} has more than one secret key"
+                );
+                Ok(secret_key.make_public_key())
+            })
+            .collect()
+    }
+
     /// Reads a [`GuardianSecretKey`] from a [`std::io::Read`] and validates it.
     pub fn from_stdioread_validated(
         stdioread: &mut dyn std::io::Read,
@@ -225,10 +304,160 @@ impl GuardianSecretKey {
         let self_: Self =
             serde_json::from_reader(stdioread).context("Reading GuardianSecretKey")?;
 
+        validate_schema_version(
+            "GuardianSecretKey",
+            self_.schema_version,
+            GUARDIAN_SECRET_KEY_SCHEMA_VERSION,
+        )?;
+
         self_.validate(election_parameters)?;
 
         Ok(self_)
     }
 }
 
-impl SerializablePretty for GuardianSecretKey {}
+impl ContainsSecretMaterial for GuardianSecretKey {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::{
+        example_election_parameters::toy_election_parameters,
+        joint_election_public_key::JointElectionPublicKey, serializable::SerializableSecret,
+        standard_parameters::test_parameter_do_not_use_in_production::TOY_PARAMETERS_01,
+    };
+
+    #[test]
+    fn test_write_secret_key_requires_allow_secret() {
+        let mut csprng = Csprng::new(b"test_write_secret_key_requires_allow_secret");
+
+        let election_parameters = toy_election_parameters(3, 2);
+
+        let i = GuardianIndex::from_one_based_index(1).unwrap();
+        let secret_key = GuardianSecretKey::generate(&mut csprng, &election_parameters, i, None);
+
+        let mut buf = Vec::new();
+        assert!(secret_key
+            .to_stdiowrite_pretty_secret(&mut buf, false)
+            .is_err());
+        assert!(buf.is_empty());
+
+        secret_key
+            .to_stdiowrite_pretty_secret(&mut buf, true)
+            .unwrap();
+        assert!(!buf.is_empty());
+    }
+
+    #[test]
+    fn test_secret_coefficients_evaluate_at() {
+        let field = &TOY_PARAMETERS_01.field;
+
+        // P(x) = 3 + 2x + 5x^2 mod 127
+        let secret_coefficients = SecretCoefficients(
+            [3u8, 2, 5]
+                .into_iter()
+                .map(|a| SecretCoefficient(FieldElement::from(a, field)))
+                .collect(),
+        );
+
+        for (x, expected) in [(0u8, 3u8), (1, 10), (2, 27), (3, 54), (10, 15)] {
+            let x = FieldElement::from(x, field);
+            assert_eq!(
+                secret_coefficients.evaluate_at(&x, field),
+                FieldElement::from(expected, field)
+            );
+        }
+
+        // The empty polynomial is zero everywhere.
+        let x = FieldElement::from(5u8, field);
+        assert_eq!(
+            SecretCoefficients(vec![]).evaluate_at(&x, field),
+            ScalarField::zero()
+        );
+    }
+
+    #[test]
+    fn test_coefficient_commitments_evaluate_in_exponent() {
+        let fixed_parameters = &*TOY_PARAMETERS_01;
+        let field = &fixed_parameters.field;
+        let group = &fixed_parameters.group;
+
+        let mut csprng = Csprng::new(b"test_coefficient_commitments_evaluate_in_exponent");
+
+        for cnt_coefficients in 0..5 {
+            let secret_coefficients = SecretCoefficients(
+                (0..cnt_coefficients)
+                    .map(|_| SecretCoefficient(field.random_field_elem(&mut csprng)))
+                    .collect(),
+            );
+            let coefficient_commitments =
+                CoefficientCommitments::new(fixed_parameters, &secret_coefficients);
+
+            for _ in 0..10 {
+                let x = field.random_field_elem(&mut csprng);
+
+                let expected = coefficient_commitments.0.iter().enumerate().fold(
+                    Group::one(),
+                    |prod, (m, k_m)| {
+                        let x_pow_m = x.pow(m, field);
+                        prod.mul(&k_m.0.exp(&x_pow_m, group), group)
+                    },
+                );
+
+                let actual = coefficient_commitments.evaluate_in_exponent(&x, group, field);
+                assert_eq!(actual, expected);
+                assert_eq!(
+                    actual,
+                    group.g_exp(&secret_coefficients.evaluate_at(&x, field))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_make_all_public_keys() {
+        let mut csprng = Csprng::new(b"test_make_all_public_keys");
+
+        let election_parameters = toy_election_parameters(5, 3);
+
+        // In reverse order of guardian index.
+        let mut secret_keys: Vec<_> = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .map(|i| GuardianSecretKey::generate(&mut csprng, &election_parameters, i, None))
+            .collect();
+        secret_keys.reverse();
+
+        let public_keys =
+            GuardianSecretKey::make_all_public_keys(&secret_keys, &election_parameters).unwrap();
+
+        assert_eq!(public_keys.len(), 5);
+        for (public_key, i) in public_keys
+            .iter()
+            .zip(election_parameters.varying_parameters.each_guardian_i())
+        {
+            assert_eq!(public_key.i, i);
+            let secret_key = secret_keys.iter().find(|sk| sk.i == i).unwrap();
+            assert_eq!(
+                public_key.public_key_k_i_0(),
+                secret_key.make_public_key().public_key_k_i_0()
+            );
+        }
+
+        // The public keys verify as a batch.
+        JointElectionPublicKey::compute(&election_parameters, &public_keys).unwrap();
+
+        // A duplicated secret key in place of another is rejected.
+        secret_keys[0] = secret_keys[1].clone();
+        assert!(
+            GuardianSecretKey::make_all_public_keys(&secret_keys, &election_parameters).is_err()
+        );
+
+        // As is a missing one.
+        secret_keys.remove(0);
+        assert!(
+            GuardianSecretKey::make_all_public_keys(&secret_keys, &election_parameters).is_err()
+        );
+    }
+}
@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 use std::iter::zip;
 use thiserror::Error;
 use util::{
-    algebra::{FieldElement, Group, GroupElement, ScalarField},
+    algebra::{FieldElement, GroupElement},
     bitwise::xor,
     csprng::Csprng,
 };
@@ -220,12 +220,11 @@ impl GuardianEncryptedShare {
 
         let l = recipient_public_key.i.get_one_based_u32();
 
-        //Generate key share as P(l) (cf. Equations 9 and 18) using Horner's method
+        //Generate key share as P(l) (cf. Equations 9 and 18)
         let x = FieldElement::from(l, field);
-        let mut p_l = ScalarField::zero();
-        for coeff in dealer_private_key.secret_coefficients.0.iter().rev() {
-            p_l = p_l.mul(&x, field).add(&coeff.0, field);
-        }
+        let p_l = dealer_private_key
+            .secret_coefficients
+            .evaluate_at(&x, field);
 
         //Generate a fresh nonce
         let nonce = field.random_field_elem(csprng);
@@ -300,13 +299,9 @@ impl GuardianEncryptedShare {
         let g_p_l = group.g_exp(&p_l);
         // RHS of Equation `21`
         let l = FieldElement::from(l, field);
-        let vec_k_i_j = &dealer_public_key.coefficient_commitments.0;
-        let rhs = (0u32..)
-            .zip(vec_k_i_j)
-            .fold(Group::one(), |prod, (j, k_i_j)| {
-                let l_pow_j = l.pow(j, field);
-                prod.mul(&k_i_j.0.exp(&l_pow_j, group), group)
-            });
+        let rhs = dealer_public_key
+            .coefficient_commitments
+            .evaluate_in_exponent(&l, group, field);
         if g_p_l != rhs {
             return Err(DecryptionError::InvalidShare);
         }
@@ -358,13 +353,9 @@ impl GuardianEncryptedShare {
         let g_p_l = group.g_exp(&secret.share);
         // RHS of Equation `21`
         let l = &FieldElement::from(l, field);
-        let vec_k_i_j = &dealer_public_key.coefficient_commitments.0;
-        let rhs = (0u32..)
-            .zip(vec_k_i_j)
-            .fold(Group::one(), |prod, (j, k_i_j)| {
-                let l_pow_j = l.pow(j, field);
-                prod.mul(&k_i_j.0.exp(&l_pow_j, group), group)
-            });
+        let rhs = dealer_public_key
+            .coefficient_commitments
+            .evaluate_in_exponent(l, group, field);
         if g_p_l != rhs {
             return false;
         }
@@ -606,7 +597,7 @@ mod test {
         let ys = key_shares.iter().map(|s| s.p_i.clone()).collect::<Vec<_>>();
         let joint_key_2 = field_lagrange_at_zero(&xs, &ys, &fixed_parameters.field);
 
-        assert_eq!(Some(joint_key_1), joint_key_2, "Joint keys should match.")
+        assert_eq!(Ok(joint_key_1), joint_key_2, "Joint keys should match.")
     }
 
     #[test]
@@ -18,6 +18,20 @@ type HmacSha256 = Hmac<sha2::Sha256>;
 pub const HVALUE_BYTE_LEN: usize = 32;
 type HValueByteArray = [u8; HVALUE_BYTE_LEN];
 
+/// Represents errors occurring when parsing an [`HValue`] from hex digits, see
+/// [`HValue::from_hex_str`] and its [`FromStr`](std::str::FromStr) implementation.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum HValueHexError {
+    /// Occurs if the string is not exactly two hex digits per byte long.
+    #[error("The hash value has {len} characters, but {expected} hex digits are required.")]
+    WrongLength { len: usize, expected: usize },
+
+    /// Occurs if the string contains a character which is not a hex digit, or not part of the
+    /// prefix or suffix where expected.
+    #[error("The hash value contains the unexpected character {ch:?} at position {position}.")]
+    InvalidChar { position: usize, ch: char },
+}
+
 #[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct HValue(pub HValueByteArray);
 
@@ -114,10 +128,39 @@ impl HValue {
         Ok(hashes)
     }
 
+    /// Parses an [`HValue`] from exactly 64 hex digits, as the spec writes hash values,
+    /// without a prefix or suffix. Lowercase digits are accepted as well.
+    pub fn from_hex_str(s: &str) -> Result<HValue, HValueHexError> {
+        let prefix = String::from_utf8_lossy(HValue::HVALUE_SERIALIZE_PREFIX);
+        let suffix = String::from_utf8_lossy(HValue::HVALUE_SERIALIZE_SUFFIX);
+        let affix_len = prefix.len() + suffix.len();
+
+        // Report the error in terms of the hex digits.
+        format!("{prefix}{s}{suffix}")
+            .parse()
+            .map_err(|err| match err {
+                HValueHexError::WrongLength { len, expected } => HValueHexError::WrongLength {
+                    len: len - affix_len,
+                    expected: expected - affix_len,
+                },
+                HValueHexError::InvalidChar { position, ch } => HValueHexError::InvalidChar {
+                    position: position - prefix.len(),
+                    ch,
+                },
+            })
+    }
+
+    /// Returns the 64 uppercase hex digits of the [`HValue`], as the spec writes hash values,
+    /// without a prefix or suffix. The inverse of [`HValue::from_hex_str`].
+    pub fn to_hex_string(&self) -> String {
+        self.to_string_hex_no_prefix_suffix()
+    }
+
     pub fn to_string_hex_no_prefix_suffix(&self) -> String {
-        #[allow(clippy::unwrap_used)] //? TODO: Remove temp development code
-        let s = serde_json::to_string_pretty(self).unwrap();
-        s[3..s.len() - 2].to_string()
+        let aa = self.display_as_ascii();
+        let s = aa.as_str();
+        s[HValue::HVALUE_SERIALIZE_PREFIX.len()..s.len() - HValue::HVALUE_SERIALIZE_SUFFIX.len()]
+            .to_string()
     }
 }
 
@@ -168,70 +211,38 @@ impl Serialize for HValue {
 }
 
 impl std::str::FromStr for HValue {
-    type Err = anyhow::Error;
+    type Err = HValueHexError;
 
     /// Parses a string into an HValue.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let prefix_start_ix = 0usize;
-        let prefix_end_ix = HValue::HVALUE_SERIALIZE_PREFIX.len();
-
-        let suffix_start_ix = HValue::HVALUE_SERIALIZE_LEN - HValue::HVALUE_SERIALIZE_SUFFIX.len();
-        let suffix_end_ix = HValue::HVALUE_SERIALIZE_LEN;
-
-        let hex_start_ix = prefix_end_ix;
-        let hex_end_ix = suffix_start_ix;
-
-        let bytes = s.as_bytes();
-
-        let prefix_and_suffix_look_ok = bytes.len() == HValue::HVALUE_SERIALIZE_LEN
-            && &bytes[prefix_start_ix..prefix_end_ix] == HValue::HVALUE_SERIALIZE_PREFIX
-            && &bytes[suffix_start_ix..suffix_end_ix] == HValue::HVALUE_SERIALIZE_SUFFIX;
-
-        let make_error = || anyhow!("Invalid HValue: {}", s);
-
-        if !prefix_and_suffix_look_ok {
-            return Err(make_error());
+        let expected = HValue::HVALUE_SERIALIZE_LEN;
+        let len = s.chars().count();
+        if len != expected {
+            return Err(HValueHexError::WrongLength { len, expected });
         }
 
-        let hex_digits = &bytes[hex_start_ix..hex_end_ix];
+        let hex_start_ix = HValue::HVALUE_SERIALIZE_PREFIX.len();
+        let hex_end_ix = HValue::HVALUE_SERIALIZE_LEN - HValue::HVALUE_SERIALIZE_SUFFIX.len();
 
-        fn hex_digit_to_nibble(hex_digit: u8) -> Option<u8> {
-            match hex_digit {
-                b'0'..=b'9' => Some(hex_digit - b'0'),
-                b'a'..=b'f' => Some(hex_digit - b'a' + 10),
-                b'A'..=b'F' => Some(hex_digit - b'A' + 10),
-                _ => None,
+        if let Some((position, ch)) = s.chars().enumerate().find(|&(ix, ch)| {
+            if ix < hex_start_ix {
+                ch != char::from(HValue::HVALUE_SERIALIZE_PREFIX[ix])
+            } else if hex_end_ix <= ix {
+                ch != char::from(HValue::HVALUE_SERIALIZE_SUFFIX[ix - hex_end_ix])
+            } else {
+                !ch.is_ascii_hexdigit()
             }
+        }) {
+            return Err(HValueHexError::InvalidChar { position, ch });
         }
 
-        let mut bad_digit = false;
-        let mut byte_iterator = hex_digits.chunks_exact(2).map(|hex_digit_pair| {
-            hex_digit_pair
-                .iter()
-                .map(|hex_digit| {
-                    hex_digit_to_nibble(*hex_digit).unwrap_or_else(|| {
-                        bad_digit = true;
-                        0
-                    })
-                })
-                .fold(0u8, |acc, hex_digit| (acc << 4) | hex_digit)
-        });
-
-        //? TODO Use std::array::array_try_from_fn when available https://github.com/rust-lang/rust/issues/89379
-        let mut missing_digit = false;
-        let hvba: HValueByteArray = std::array::from_fn(|_ix| {
-            byte_iterator.next().unwrap_or_else(|| {
-                missing_digit = true;
-                0
-            })
+        // All characters are ASCII now, so the byte indices are those of the characters.
+        let hex_digits = &s.as_bytes()[hex_start_ix..hex_end_ix];
+        let nibble = |hex_digit: u8| char::from(hex_digit).to_digit(16).unwrap_or(0) as u8;
+        let hvba: HValueByteArray = std::array::from_fn(|ix| {
+            (nibble(hex_digits[2 * ix]) << 4) | nibble(hex_digits[2 * ix + 1])
         });
 
-        debug_assert!(byte_iterator.next().is_none());
-
-        if bad_digit || missing_digit {
-            return Err(make_error());
-        }
-
         Ok(HValue(hvba))
     }
 }
@@ -269,6 +280,45 @@ pub fn eg_hmac(key: &HValue, data: &dyn AsRef<[u8]>) -> HValue {
     AsRef::<[u8; 32]>::as_ref(&hmac_sha256.chain(data).finalize_fixed()).into()
 }
 
+/// A [`std::io::Write`] adapter that passes the bytes through to an inner writer while
+/// feeding them to the ElectionGuard `H` hash function, so that an object can be written
+/// and hashed in one pass. Use [`std::io::sink()`] as the inner writer to only hash.
+pub struct HashingWriter<W: std::io::Write> {
+    inner: W,
+    hmac_sha256: HmacSha256,
+}
+
+impl<W: std::io::Write> HashingWriter<W> {
+    /// Creates a `HashingWriter` computing `H(key; ...)` over the bytes written.
+    pub fn new(key: &HValue, inner: W) -> Self {
+        // `unwrap()` is justified here because `HmacSha256::new_from_slice()` seems
+        // to only fail on slice of incorrect size.
+        #[allow(clippy::unwrap_used)]
+        let hmac_sha256 = HmacSha256::new_from_slice(key.as_ref()).unwrap();
+
+        Self { inner, hmac_sha256 }
+    }
+
+    /// Returns the hash of all the bytes written, which is the same as [`eg_h`] of the key
+    /// and those bytes.
+    pub fn finish(self) -> HValue {
+        AsRef::<[u8; 32]>::as_ref(&self.hmac_sha256.finalize_fixed()).into()
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Only the bytes accepted by the inner writer are hashed.
+        let cnt_written = self.inner.write(buf)?;
+        Update::update(&mut self.hmac_sha256, &buf[..cnt_written]);
+        Ok(cnt_written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test_eg_h {
@@ -298,6 +348,51 @@ mod test_eg_h {
         assert_eq!(h2, h);
     }
 
+    #[test]
+    fn test_hvalue_hex_string() {
+        let h: HValue = std::array::from_fn(|ix| (ix as u8) * 7).into();
+
+        let hex = h.to_hex_string();
+        assert_eq!(
+            hex,
+            "00070E151C232A31383F464D545B626970777E858C939AA1A8AFB6BDC4CBD2D9"
+        );
+        assert_eq!(HValue::from_hex_str(&hex).unwrap(), h);
+        assert_eq!(HValue::from_hex_str(&hex.to_lowercase()).unwrap(), h);
+
+        assert_eq!(
+            HValue::from_hex_str(&hex[..62]),
+            Err(HValueHexError::WrongLength {
+                len: 62,
+                expected: 64
+            })
+        );
+        assert_eq!(
+            HValue::from_hex_str(&format!("{hex}00")),
+            Err(HValueHexError::WrongLength {
+                len: 66,
+                expected: 64
+            })
+        );
+        assert_eq!(
+            HValue::from_hex_str(&format!("{}G", &hex[..63])),
+            Err(HValueHexError::InvalidChar {
+                position: 63,
+                ch: 'G'
+            })
+        );
+
+        // The same form is displayed and parsed, with the same typed errors.
+        assert_eq!(h.to_string().parse::<HValue>(), Ok(h));
+        assert_eq!(
+            "Ä".repeat(64).parse::<HValue>(),
+            Err(HValueHexError::InvalidChar {
+                position: 0,
+                ch: 'Ä'
+            })
+        );
+    }
+
     #[test]
     fn test_evaluate_h() {
         let key: HValue = HValue::default();
@@ -312,4 +407,32 @@ mod test_eg_h {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_hashing_writer() {
+        use crate::{
+            example_election_manifest::example_election_manifest,
+            serializable::SerializableCanonical,
+        };
+
+        let key: HValue = std::array::from_fn(|ix| ix as u8).into();
+        let election_manifest = example_election_manifest();
+        let canonical_bytes = election_manifest.to_canonical_bytes().unwrap();
+
+        let mut written = Vec::new();
+        let mut hashing_writer = HashingWriter::new(&key, &mut written);
+        election_manifest
+            .to_canonical_writer(&mut hashing_writer)
+            .unwrap();
+        let h = hashing_writer.finish();
+
+        assert_eq!(h, eg_h(&key, &canonical_bytes));
+        assert_eq!(written, canonical_bytes);
+
+        let mut hashing_writer = HashingWriter::new(&key, std::io::sink());
+        election_manifest
+            .to_canonical_writer(&mut hashing_writer)
+            .unwrap();
+        assert_eq!(hashing_writer.finish(), h);
+    }
 }
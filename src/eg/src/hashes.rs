@@ -13,6 +13,27 @@ use crate::{
     serializable::{SerializableCanonical, SerializablePretty},
 };
 
+/// The version of the ElectionGuard Design Specification implemented.
+pub const EGDS_VERSION: &str = "v2.0.0";
+
+/// The version byte array `ver` hashed into the parameter base hash (Equation 4): the UTF-8
+/// encoding of [`EGDS_VERSION`], padded with zero bytes to a length of 32. For `"v2.0.0"` this
+/// is `0x76322E302E30 | b(0, 26)`.
+pub const EGDS_VER_BYTES: [u8; 32] = ver_bytes(EGDS_VERSION);
+
+/// Pads the UTF-8 encoding of a version string with zero bytes to a length of 32.
+/// Fails to compile if the version string is longer than 32 bytes.
+const fn ver_bytes(version: &str) -> [u8; 32] {
+    let version = version.as_bytes();
+    let mut bytes = [0u8; 32];
+    let mut j = 0;
+    while j < version.len() {
+        bytes[j] = version[j];
+        j += 1;
+    }
+    bytes
+}
+
 /// Parameter base hash (cf. Section 3.1.2 in Specs 2.0.0)
 /// This is used to compute guardian keys which can be independent of the election (manifest).
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -26,13 +47,7 @@ impl ParameterBaseHash {
         let group = &fixed_parameters.group;
 
         // H_V = 0x76322E302E30 | b(0, 26)
-        let h_v: HValue = [
-            // This is the UTF-8 encoding of "v2.0.0"
-            0x76, 0x32, 0x2E, 0x30, 0x2E, 0x30, // Padding
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        ]
-        .into();
+        let h_v: HValue = EGDS_VER_BYTES.into();
 
         // v = 0x00 | b(p,512)| b(q,32) | b(g,512)
         let mut v = vec![0x00];
@@ -154,7 +169,10 @@ mod test {
     use super::*;
     use crate::{
         ballot_style::BallotStyle,
-        election_manifest::{Contest, ContestIndex, ContestOption},
+        election_manifest::{
+            Contest, ContestIndex, ContestOption, ELECTION_MANIFEST_SCHEMA_VERSION,
+        },
+        election_parameters::ELECTION_PARAMETERS_SCHEMA_VERSION,
         example_election_parameters::example_election_parameters,
         guardian::GuardianIndex,
         standard_parameters::STANDARD_PARAMETERS,
@@ -172,19 +190,42 @@ mod test {
         assert_eq!(hash.h_p, expected_h_p);
     }
 
+    #[test]
+    fn test_egds_ver_bytes() {
+        assert_eq!(EGDS_VER_BYTES.len(), 32);
+
+        let version_len = EGDS_VERSION.len();
+        assert_eq!(
+            std::str::from_utf8(&EGDS_VER_BYTES[..version_len]).unwrap(),
+            EGDS_VERSION
+        );
+        assert!(EGDS_VER_BYTES[version_len..].iter().all(|&b| b == 0));
+        assert_eq!(
+            EGDS_VER_BYTES,
+            hex!("76322E302E300000000000000000000000000000000000000000000000000000")
+        );
+
+        // The published `H_P` for the standard parameters.
+        let fixed_parameters = example_election_parameters().fixed_parameters;
+        let h_p = ParameterBaseHash::compute(&fixed_parameters).h_p;
+        assert_eq!(
+            h_p,
+            HValue::from(hex!(
+                "2B3B025E50E09C119CBA7E9448ACD1CABC9447EF39BF06327D81C665CDD86296"
+            ))
+        );
+    }
+
     fn simple_election_manifest() -> ElectionManifest {
         let contests = [
             // Contest index 1:
             Contest {
                 label: "Contest01".to_string(),
                 selection_limit: 1,
+                exactly_one: false,
                 options: [
-                    ContestOption {
-                        label: "SelectionA".to_string(),
-                    },
-                    ContestOption {
-                        label: "SelectionB".to_string(),
-                    },
+                    ContestOption::new("SelectionA"),
+                    ContestOption::new("SelectionB"),
                 ]
                 .try_into()
                 .unwrap(),
@@ -202,6 +243,7 @@ mod test {
         .unwrap();
 
         ElectionManifest {
+            schema_version: ELECTION_MANIFEST_SCHEMA_VERSION,
             label: "AElection".to_string(),
             contests,
             ballot_styles,
@@ -229,6 +271,7 @@ mod test {
         };
 
         ElectionParameters {
+            schema_version: ELECTION_PARAMETERS_SCHEMA_VERSION,
             fixed_parameters,
             varying_parameters,
         }
@@ -0,0 +1,160 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! Identifiers, such as the IDs of candidates or devices in external systems.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The maximum length of an [`Ident`], in characters.
+pub const IDENT_MAX_LEN_CHARS: usize = 256;
+
+/// Represents errors occurring if a string is not a valid [`Ident`].
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum IdentError {
+    /// Occurs if the identifier is empty.
+    #[error("An identifier must not be empty.")]
+    Empty,
+
+    /// Occurs if the identifier is longer than allowed.
+    #[error("The identifier is {len} characters long, exceeding the maximum of {max}.")]
+    TooLong { len: usize, max: usize },
+
+    /// Occurs if the identifier contains a character which is not allowed.
+    #[error("The identifier contains the character {ch:?} at position {position}, which is not one of the allowed ASCII letters, digits, '-', '_' and '.'.")]
+    InvalidChar { position: usize, ch: char },
+}
+
+/// An identifier, e.g., of a candidate or device in an external system.
+///
+/// It is non-empty, at most [`IDENT_MAX_LEN_CHARS`] long, and consists only of ASCII letters,
+/// digits, `-`, `_` and `.`. So it can be embedded in file names, URLs and logs without escaping.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Ident(String);
+
+impl Ident {
+    /// Returns whether the character may appear in an [`Ident`].
+    pub fn is_allowed_char(ch: char) -> bool {
+        ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.')
+    }
+
+    /// Creates an [`Ident`] from a string, or returns an error describing the first violation.
+    /// The position of an invalid character is the 0-based index of the character.
+    pub fn try_from_str(s: &str) -> Result<Ident, IdentError> {
+        if s.is_empty() {
+            return Err(IdentError::Empty);
+        }
+
+        if let Some((position, ch)) = s
+            .chars()
+            .enumerate()
+            .find(|&(_, ch)| !Self::is_allowed_char(ch))
+        {
+            return Err(IdentError::InvalidChar { position, ch });
+        }
+
+        // All the allowed characters are ASCII, so the length in bytes is the length in characters.
+        let len = s.len();
+        if IDENT_MAX_LEN_CHARS < len {
+            return Err(IdentError::TooLong {
+                len,
+                max: IDENT_MAX_LEN_CHARS,
+            });
+        }
+
+        Ok(Ident(s.to_string()))
+    }
+
+    /// Returns the identifier as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for Ident {
+    type Err = IdentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ident::try_from_str(s)
+    }
+}
+
+impl TryFrom<String> for Ident {
+    type Error = IdentError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Ident::try_from_str(&s)
+    }
+}
+
+impl From<Ident> for String {
+    fn from(ident: Ident) -> Self {
+        ident.0
+    }
+}
+
+impl AsRef<str> for Ident {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Ident {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ident_try_from_str() {
+        let ident = Ident::try_from_str("cand-0042.A_1").unwrap();
+        assert_eq!(ident.as_str(), "cand-0042.A_1");
+        assert_eq!(ident.to_string(), "cand-0042.A_1");
+
+        assert_eq!(
+            Ident::try_from_str("cand 42").unwrap_err(),
+            IdentError::InvalidChar {
+                position: 4,
+                ch: ' '
+            }
+        );
+        assert_eq!(
+            Ident::try_from_str("ĉand").unwrap_err(),
+            IdentError::InvalidChar {
+                position: 0,
+                ch: 'ĉ'
+            }
+        );
+
+        assert_eq!(Ident::try_from_str("").unwrap_err(), IdentError::Empty);
+
+        assert!(Ident::try_from_str(&"x".repeat(IDENT_MAX_LEN_CHARS)).is_ok());
+        assert_eq!(
+            Ident::try_from_str(&"x".repeat(IDENT_MAX_LEN_CHARS + 1)).unwrap_err(),
+            IdentError::TooLong {
+                len: IDENT_MAX_LEN_CHARS + 1,
+                max: IDENT_MAX_LEN_CHARS
+            }
+        );
+    }
+
+    #[test]
+    fn test_ident_serde() {
+        let ident = Ident::try_from_str("device_7").unwrap();
+        let json = serde_json::to_string(&ident).unwrap();
+        assert_eq!(json, "\"device_7\"");
+        assert_eq!(serde_json::from_str::<Ident>(&json).unwrap(), ident);
+
+        assert!(serde_json::from_str::<Ident>("\"device 7\"").is_err());
+    }
+}
@@ -198,6 +198,14 @@ impl<T> std::str::FromStr for Index<T> {
     }
 }
 
+impl<T> TryFrom<u32> for Index<T> {
+    type Error = Error;
+
+    fn try_from(ix1: u32) -> Result<Self, Self::Error> {
+        Self::from_one_based_index(ix1)
+    }
+}
+
 impl<T> Serialize for Index<T> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
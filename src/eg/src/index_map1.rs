@@ -0,0 +1,140 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::index::Index;
+
+/// A map keyed by 1-based [`Index`]`<T>` values, e.g., contest selections keyed by
+/// [`ContestIndex`](crate::election_manifest::ContestIndex).
+///
+/// Entries can be inserted and looked up by plain 1-based numbers, with the conversion to
+/// [`Index`] (and the rejection of `0` and out-of-range values) handled internally.
+pub struct IndexMap1<T, V>(BTreeMap<Index<T>, V>);
+
+impl<T, V> IndexMap1<T, V> {
+    /// Creates a new, empty `IndexMap1`.
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Inserts a value at the supplied index, returning the previous value, if any.
+    pub fn insert(&mut self, ix: Index<T>, value: V) -> Option<V> {
+        self.0.insert(ix, value)
+    }
+
+    /// Inserts a value at the supplied 1-based index, returning the previous value, if any.
+    /// Returns an error if `one_based` is `0` or otherwise not a valid [`Index`].
+    pub fn insert_at(&mut self, one_based: usize, value: V) -> Result<Option<V>> {
+        let ix = Self::index_from_one_based(one_based)?;
+        Ok(self.insert(ix, value))
+    }
+
+    /// Returns a ref to the value at the supplied index, if one exists.
+    pub fn get(&self, ix: Index<T>) -> Option<&V> {
+        self.0.get(&ix)
+    }
+
+    /// Returns a ref to the value at the supplied 1-based index, if one exists.
+    /// Returns `None` if `one_based` is `0` or otherwise not a valid [`Index`].
+    pub fn get_at(&self, one_based: usize) -> Option<&V> {
+        let ix = Self::index_from_one_based(one_based).ok()?;
+        self.0.get(&ix)
+    }
+
+    /// Returns an iterator over the entries, in order of their indices.
+    pub fn iter(&self) -> impl Iterator<Item = (Index<T>, &V)> {
+        self.0.iter().map(|(&ix, v)| (ix, v))
+    }
+
+    /// Returns the contained map.
+    pub fn into_inner(self) -> BTreeMap<Index<T>, V> {
+        self.0
+    }
+
+    fn index_from_one_based(one_based: usize) -> Result<Index<T>> {
+        u32::try_from(one_based)
+            .ok()
+            .and_then(Index::from_one_based_index_const)
+            .ok_or_else(|| anyhow!("Index value {one_based} out of range"))
+    }
+}
+
+impl<T, V> Default for IndexMap1<T, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, V> From<IndexMap1<T, V>> for BTreeMap<Index<T>, V> {
+    fn from(index_map1: IndexMap1<T, V>) -> Self {
+        index_map1.into_inner()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::{
+        contest_selection::ContestSelection,
+        election_manifest::{Contest, ContestIndex},
+    };
+
+    #[test]
+    fn test_index_map1_contest_selections() {
+        let mut selections = IndexMap1::<Contest, ContestSelection>::new();
+        assert!(selections.is_empty());
+
+        assert!(selections
+            .insert_at(1, ContestSelection::new(vec![1, 0]).unwrap())
+            .unwrap()
+            .is_none());
+        assert!(selections
+            .insert_at(3, ContestSelection::new(vec![0, 0, 1]).unwrap())
+            .unwrap()
+            .is_none());
+
+        // Zero and out-of-range indices are rejected.
+        assert!(selections
+            .insert_at(0, ContestSelection::new(vec![1]).unwrap())
+            .is_err());
+        assert!(selections
+            .insert_at(1 << 31, ContestSelection::new(vec![1]).unwrap())
+            .is_err());
+        assert!(selections.get_at(0).is_none());
+
+        assert_eq!(selections.len(), 2);
+        assert_eq!(selections.get_at(1).unwrap().get_vote(), &[1, 0]);
+        assert!(selections.get_at(2).is_none());
+
+        let ix3 = ContestIndex::from_one_based_index(3).unwrap();
+        assert_eq!(selections.get(ix3).unwrap().get_vote(), &[0, 0, 1]);
+
+        let ixs: Vec<u32> = selections
+            .iter()
+            .map(|(ix, _)| ix.get_one_based_u32())
+            .collect();
+        assert_eq!(ixs, [1, 3]);
+
+        let map: BTreeMap<ContestIndex, ContestSelection> = selections.into();
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key(&ix3));
+    }
+}
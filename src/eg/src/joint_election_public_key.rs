@@ -13,13 +13,38 @@ use serde::{Deserialize, Serialize};
 use util::algebra::{FieldElement, Group, GroupElement, ScalarField};
 
 use crate::{
-    election_parameters::ElectionParameters, fixed_parameters::FixedParameters,
-    guardian_public_key::GuardianPublicKey, index::Index, serializable::SerializablePretty,
+    election_parameters::ElectionParameters,
+    fixed_parameters::FixedParameters,
+    guardian_public_key::GuardianPublicKey,
+    index::Index,
+    schema_version::{
+        initial_schema_version, is_initial_schema_version, validate_schema_version, SchemaVersion,
+        INITIAL_SCHEMA_VERSION,
+    },
+    serializable::SerializablePretty,
 };
 
+#[cfg(test)]
+thread_local! {
+    /// The number of exponentiations performed by [`Ciphertext::scale`] on this thread.
+    pub(crate) static CNT_SCALE_EXPONENTIATIONS: std::cell::Cell<usize> =
+        const { std::cell::Cell::new(0) };
+}
+
+/// The version of the schema of [`JointElectionPublicKey`] written and supported by this library.
+pub const JOINT_ELECTION_PUBLIC_KEY_SCHEMA_VERSION: SchemaVersion = INITIAL_SCHEMA_VERSION;
+
 /// The joint election public key.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JointElectionPublicKey {
+    /// The version of the schema of the joint election public key, see [`crate::schema_version`].
+    #[serde(
+        default = "initial_schema_version",
+        skip_serializing_if = "is_initial_schema_version"
+    )]
+    pub schema_version: SchemaVersion,
+
+    /// The joint election public key `K`.
     pub joint_election_public_key: GroupElement,
 }
 
@@ -27,7 +52,10 @@ pub struct JointElectionPublicKey {
 pub type CiphertextIndex = Index<Ciphertext>;
 
 /// The ciphertext used to store a vote value corresponding to one option.
-#[derive(Debug, Clone, Serialize, Deserialize, Eq)]
+///
+/// Like [`GroupElement`], its `Debug` representation is abbreviated unless the alternate
+/// form `{:#?}` is used.
+#[derive(Clone, Serialize, Deserialize, Eq)]
 pub struct Ciphertext {
     pub alpha: GroupElement,
     pub beta: GroupElement,
@@ -63,14 +91,56 @@ impl Ciphertext {
         }
     }
 
+    /// Checks that both `alpha` and `beta` are valid members of the group `Z_p^r`, i.e., are
+    /// less than `p` and have order `q`. Ciphertexts received from elsewhere must pass this check
+    /// before being used, e.g., to rule out elements of small subgroups.
+    pub fn is_valid_group_member(&self, group: &Group) -> bool {
+        self.alpha.is_valid(group) && self.beta.is_valid(group)
+    }
+
     /// Scale a ciphertext by a factor. The scaling of an encryption of `x` with a factor `k`
     /// gives an encryption of `k*x`.
     pub fn scale(&self, fixed_parameters: &FixedParameters, factor: &FieldElement) -> Ciphertext {
         let alpha = self.alpha.exp(factor, &fixed_parameters.group);
         let beta = self.beta.exp(factor, &fixed_parameters.group);
 
+        #[cfg(test)]
+        CNT_SCALE_EXPONENTIATIONS.with(|cnt| cnt.set(cnt.get() + 2));
+
         Ciphertext { alpha, beta }
     }
+
+    /// Homomorphically subtract a ciphertext. The subtraction of an encryption of `y` from
+    /// an encryption of `x` gives an encryption of `x - y` (with nonce the difference of the nonces).
+    ///
+    /// Returns `None` if a component of `other` is not invertible, i.e., if it is not a valid group element.
+    pub fn sub(&self, other: &Ciphertext, group: &Group) -> Option<Ciphertext> {
+        let alpha = self.alpha.mul(&other.alpha.inv(group)?, group);
+        let beta = self.beta.mul(&other.beta.inv(group)?, group);
+
+        Some(Ciphertext { alpha, beta })
+    }
+
+    /// The encryption of a publicly known constant, such as a contest selection limit, using the nonce 0.
+    /// This allows the constant to be combined homomorphically with other ciphertexts, e.g.,
+    /// to compute the encrypted difference between the selection limit and the sum of the selections.
+    pub fn encrypt_constant(
+        joint_election_public_key: &JointElectionPublicKey,
+        fixed_parameters: &FixedParameters,
+        value: usize,
+    ) -> Ciphertext {
+        joint_election_public_key.encrypt_with(fixed_parameters, &ScalarField::zero(), value)
+    }
+}
+
+impl std::fmt::Debug for Ciphertext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The alternate flag is passed on to the `GroupElement`s.
+        f.debug_struct("Ciphertext")
+            .field("alpha", &self.alpha)
+            .field("beta", &self.beta)
+            .finish()
+    }
 }
 
 impl PartialEq for Ciphertext {
@@ -138,21 +208,39 @@ impl JointElectionPublicKey {
         );
 
         Ok(Self {
+            schema_version: JOINT_ELECTION_PUBLIC_KEY_SCHEMA_VERSION,
             joint_election_public_key,
         })
     }
 
+    /// Encrypts a vote with the supplied nonce. Same as [`Self::encrypt_value`].
     pub fn encrypt_with(
         &self,
         fixed_parameters: &FixedParameters,
         nonce: &FieldElement,
         vote: usize,
+    ) -> Ciphertext {
+        self.encrypt_value(fixed_parameters, nonce, vote as u64)
+    }
+
+    /// Encrypts `message` with the supplied nonce `ξ`, using the exponential form of ElGamal
+    /// encryption of Section 3.3 (Equation 24): `(α, β) = (g^ξ mod p, K^(m + ξ) mod p)`.
+    ///
+    /// The message is encoded in the exponent, which makes the encryption additively
+    /// homomorphic, but decryption then requires a discrete logarithm. Only small messages
+    /// can be decrypted: [`VerifiableDecryption`](crate::verifiable_decryption::VerifiableDecryption)
+    /// recovers `m` only if `0 <= m < 2^38`, and `m` must also be less than the field order `q`.
+    pub fn encrypt_value(
+        &self,
+        fixed_parameters: &FixedParameters,
+        nonce: &FieldElement,
+        message: u64,
     ) -> Ciphertext {
         let field = &fixed_parameters.field;
         let group = &fixed_parameters.group;
 
         let alpha = group.g_exp(nonce);
-        let exponent = &nonce.add(&FieldElement::from(vote, field), field);
+        let exponent = &nonce.add(&FieldElement::from(message, field), field);
         let beta = self.joint_election_public_key.exp(exponent, group);
 
         Ciphertext { alpha, beta }
@@ -174,6 +262,12 @@ impl JointElectionPublicKey {
     /// Verifies that the `JointElectionPublicKey` conforms to the election parameters.
     /// Useful after deserialization.
     pub fn validate(&self, election_parameters: &ElectionParameters) -> Result<()> {
+        validate_schema_version(
+            "JointElectionPublicKey",
+            self.schema_version,
+            JOINT_ELECTION_PUBLIC_KEY_SCHEMA_VERSION,
+        )?;
+
         let group = &election_parameters.fixed_parameters.group;
         ensure!(self.joint_election_public_key.is_valid(group));
         ensure!(self.joint_election_public_key != Group::one());
@@ -201,7 +295,7 @@ impl AsRef<GroupElement> for JointElectionPublicKey {
 mod test {
     use num_bigint::BigUint;
     use util::{
-        algebra::{FieldElement, ScalarField},
+        algebra::{FieldElement, Group, ScalarField},
         algebra_utils::DiscreteLog,
         csprng::Csprng,
     };
@@ -213,7 +307,7 @@ mod test {
         index::Index,
     };
 
-    use super::{Ciphertext, JointElectionPublicKey};
+    use super::{Ciphertext, JointElectionPublicKey, JOINT_ELECTION_PUBLIC_KEY_SCHEMA_VERSION};
 
     fn g_key(i: u32) -> GuardianSecretKey {
         let mut seed = Vec::new();
@@ -281,4 +375,115 @@ mod test {
 
         assert_eq!(result, factor);
     }
+
+    #[test]
+    fn test_encrypt_value_round_trip() {
+        use crate::standard_parameters::test_parameter_do_not_use_in_production::TOY_PARAMETERS_01;
+
+        let mut csprng = Csprng::new(b"test_encrypt_value_round_trip");
+        let fixed_parameters = &*TOY_PARAMETERS_01;
+        let field = &fixed_parameters.field;
+        let group = &fixed_parameters.group;
+
+        let s = field.random_field_elem(&mut csprng);
+        let joint_election_public_key = JointElectionPublicKey {
+            schema_version: JOINT_ELECTION_PUBLIC_KEY_SCHEMA_VERSION,
+            joint_election_public_key: group.g_exp(&s),
+        };
+        let dlog =
+            DiscreteLog::from_group(&joint_election_public_key.joint_election_public_key, group);
+
+        // The toy field has order 127, so 100 is about as large a count as it can hold.
+        for message in [0_u64, 1, 100] {
+            let nonce = field.random_field_elem(&mut csprng);
+            let ciphertext =
+                joint_election_public_key.encrypt_value(fixed_parameters, &nonce, message);
+
+            // K^m = β / α^s
+            let alpha_s_inv = ciphertext.alpha.exp(&s, group).inv(group).unwrap();
+            let k_m = ciphertext.beta.mul(&alpha_s_inv, group);
+            assert_eq!(
+                dlog.ff_find(&k_m, field).unwrap(),
+                FieldElement::from(message, field)
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_undervote_difference() {
+        let election_parameters = example_election_parameters();
+        let fixed_parameters = &election_parameters.fixed_parameters;
+        let field = &fixed_parameters.field;
+        let group = &fixed_parameters.group;
+
+        let sks: Vec<_> = (1..6).map(g_key).collect();
+        let guardian_public_keys: Vec<_> = sks.iter().map(|sk| sk.make_public_key()).collect();
+
+        let sk = sks.iter().fold(ScalarField::zero(), |a, b| {
+            a.add(&b.secret_coefficients.0[0].0, field)
+        });
+        let s = SecretCoefficient(sk);
+
+        let joint_election_public_key =
+            JointElectionPublicKey::compute(&election_parameters, guardian_public_keys.as_slice())
+                .unwrap();
+
+        // A contest with selection limit 3 in which two options are selected.
+        let selection_limit = 3;
+        let votes = [1, 0, 1, 0];
+        let sum = votes
+            .iter()
+            .enumerate()
+            .fold(Ciphertext::one(), |acc, (ix, &vote)| {
+                let nonce = FieldElement::from(BigUint::from(ix + 7), field);
+                let encryption =
+                    joint_election_public_key.encrypt_with(fixed_parameters, &nonce, vote);
+                Ciphertext {
+                    alpha: acc.alpha.mul(&encryption.alpha, group),
+                    beta: acc.beta.mul(&encryption.beta, group),
+                }
+            });
+
+        let limit = Ciphertext::encrypt_constant(
+            &joint_election_public_key,
+            fixed_parameters,
+            selection_limit,
+        );
+        assert_eq!(limit.alpha, Group::one());
+
+        let difference = limit.sub(&sum, group).unwrap();
+        let result = decrypt_ciphertext(
+            &difference,
+            &joint_election_public_key,
+            &s,
+            fixed_parameters,
+        );
+
+        assert_eq!(result, FieldElement::from(BigUint::from(1u8), field));
+
+        // Subtracting a ciphertext from itself gives an encryption of zero with nonce zero.
+        assert_eq!(sum.sub(&sum, group).unwrap(), Ciphertext::one());
+    }
+
+    #[test]
+    pub fn test_ciphertext_debug() {
+        let election_parameters = example_election_parameters();
+        let fixed_parameters = &election_parameters.fixed_parameters;
+        let field = &fixed_parameters.field;
+
+        let joint_election_public_key = JointElectionPublicKey {
+            schema_version: JOINT_ELECTION_PUBLIC_KEY_SCHEMA_VERSION,
+            joint_election_public_key: fixed_parameters.group.generator(),
+        };
+        let nonce = FieldElement::from(BigUint::from(5u8), field);
+        let ciphertext = joint_election_public_key.encrypt_with(fixed_parameters, &nonce, 1);
+
+        let debug = format!("{ciphertext:?}");
+        assert!(debug.len() < 150, "{debug}");
+
+        // Both 4096-bit values are shown in full.
+        let debug_alternate = format!("{ciphertext:#?}");
+        assert!(debug_alternate.len() > 2000, "{debug_alternate}");
+        assert!(!debug_alternate.contains('…'));
+    }
 }
@@ -0,0 +1,218 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module runs the key generation ceremony of Section `3.2` for all guardians in a
+//! single process, e.g., for testing or for a setup in which one operator acts for every
+//! guardian.
+
+use anyhow::{Context, Result};
+use util::csprng::Csprng;
+
+use crate::{
+    election_parameters::ElectionParameters,
+    guardian::GuardianIndex,
+    guardian_public_key::GuardianPublicKey,
+    guardian_secret_key::GuardianSecretKey,
+    guardian_share::{GuardianEncryptedShare, GuardianSecretKeyShare},
+    joint_election_public_key::JointElectionPublicKey,
+};
+
+/// A round of the key generation ceremony.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCeremonyRound {
+    /// Each guardian generates its secret key.
+    KeyGeneration,
+
+    /// Each guardian encrypts a share of its secret key for every guardian.
+    ShareEncryption,
+
+    /// Each guardian decrypts and combines its shares into its secret key share.
+    KeyShareComputation,
+}
+
+/// A milestone reached while running the key generation ceremony, reported to the optional
+/// progress callback of [`KeyCeremony::run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// A round of the ceremony started.
+    RoundStarted(KeyCeremonyRound),
+
+    /// The secret key of guardian `i` was generated.
+    GuardianKeyGenerated { i: GuardianIndex },
+
+    /// Guardian `dealer` encrypted the shares of its secret key for all the guardians.
+    SharesEncrypted { dealer: GuardianIndex },
+
+    /// The secret key share of guardian `i` was computed.
+    KeyShareComputed { i: GuardianIndex },
+
+    /// The joint election public key was computed, completing the ceremony.
+    JointElectionPublicKeyComputed,
+}
+
+/// The outcome of the key generation ceremony.
+pub struct KeyCeremony {
+    /// The guardians' secret keys, in order of guardian index.
+    pub guardian_secret_keys: Vec<GuardianSecretKey>,
+
+    /// The guardians' public keys, in order of guardian index.
+    pub guardian_public_keys: Vec<GuardianPublicKey>,
+
+    /// The guardians' secret key shares, in order of guardian index.
+    pub guardian_secret_key_shares: Vec<GuardianSecretKeyShare>,
+
+    /// The joint election public key.
+    pub joint_election_public_key: JointElectionPublicKey,
+}
+
+impl KeyCeremony {
+    /// Runs the key generation ceremony for the guardians `1..=n`.
+    ///
+    /// The arguments are
+    /// - `csprng` - secure randomness generator
+    /// - `election_parameters` - the election parameters
+    /// - `opt_progress` - an optional callback, invoked as each [`ProgressEvent`] is reached
+    pub fn run(
+        csprng: &mut Csprng,
+        election_parameters: &ElectionParameters,
+        mut opt_progress: Option<&mut dyn FnMut(ProgressEvent)>,
+    ) -> Result<Self> {
+        let mut progress = |event: ProgressEvent| {
+            if let Some(f) = opt_progress.as_mut() {
+                f(event);
+            }
+        };
+
+        progress(ProgressEvent::RoundStarted(KeyCeremonyRound::KeyGeneration));
+        let guardian_secret_keys: Vec<_> = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .map(|i| {
+                let secret_key = GuardianSecretKey::generate(csprng, election_parameters, i, None);
+                progress(ProgressEvent::GuardianKeyGenerated { i });
+                secret_key
+            })
+            .collect();
+
+        let guardian_public_keys: Vec<_> = guardian_secret_keys
+            .iter()
+            .map(GuardianSecretKey::make_public_key)
+            .collect();
+
+        // `encrypted_shares[l][j]` is the share from dealer `j + 1` for recipient `l + 1`.
+        progress(ProgressEvent::RoundStarted(
+            KeyCeremonyRound::ShareEncryption,
+        ));
+        let mut encrypted_shares = vec![Vec::new(); guardian_public_keys.len()];
+        for dealer_secret_key in &guardian_secret_keys {
+            for (recipient_public_key, shares) in
+                guardian_public_keys.iter().zip(&mut encrypted_shares)
+            {
+                shares.push(
+                    GuardianEncryptedShare::encrypt(
+                        csprng,
+                        election_parameters,
+                        dealer_secret_key,
+                        recipient_public_key,
+                    )
+                    .ciphertext,
+                );
+            }
+            progress(ProgressEvent::SharesEncrypted {
+                dealer: dealer_secret_key.i,
+            });
+        }
+
+        progress(ProgressEvent::RoundStarted(
+            KeyCeremonyRound::KeyShareComputation,
+        ));
+        let guardian_secret_key_shares = guardian_secret_keys
+            .iter()
+            .zip(&encrypted_shares)
+            .map(|(secret_key, shares)| {
+                let i = secret_key.i;
+                let key_share = GuardianSecretKeyShare::compute(
+                    election_parameters,
+                    &guardian_public_keys,
+                    shares,
+                    secret_key,
+                )
+                .with_context(|| format!("Computing the secret key share of guardian {i}"))?;
+                progress(ProgressEvent::KeyShareComputed { i });
+                Ok(key_share)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let joint_election_public_key =
+            JointElectionPublicKey::compute(election_parameters, &guardian_public_keys)?;
+        progress(ProgressEvent::JointElectionPublicKeyComputed);
+
+        Ok(Self {
+            guardian_secret_keys,
+            guardian_public_keys,
+            guardian_secret_key_shares,
+            joint_election_public_key,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::example_election_parameters::toy_election_parameters;
+
+    #[test]
+    fn test_key_ceremony_progress() {
+        let election_parameters = toy_election_parameters(3, 2);
+
+        let mut events = Vec::new();
+        let mut csprng = Csprng::new(b"test_key_ceremony_progress");
+        let key_ceremony = KeyCeremony::run(
+            &mut csprng,
+            &election_parameters,
+            Some(&mut |event| events.push(event)),
+        )
+        .unwrap();
+
+        let gi = |i| GuardianIndex::from_one_based_index(i).unwrap();
+        assert_eq!(
+            events,
+            [
+                ProgressEvent::RoundStarted(KeyCeremonyRound::KeyGeneration),
+                ProgressEvent::GuardianKeyGenerated { i: gi(1) },
+                ProgressEvent::GuardianKeyGenerated { i: gi(2) },
+                ProgressEvent::GuardianKeyGenerated { i: gi(3) },
+                ProgressEvent::RoundStarted(KeyCeremonyRound::ShareEncryption),
+                ProgressEvent::SharesEncrypted { dealer: gi(1) },
+                ProgressEvent::SharesEncrypted { dealer: gi(2) },
+                ProgressEvent::SharesEncrypted { dealer: gi(3) },
+                ProgressEvent::RoundStarted(KeyCeremonyRound::KeyShareComputation),
+                ProgressEvent::KeyShareComputed { i: gi(1) },
+                ProgressEvent::KeyShareComputed { i: gi(2) },
+                ProgressEvent::KeyShareComputed { i: gi(3) },
+                ProgressEvent::JointElectionPublicKeyComputed,
+            ]
+        );
+        assert_eq!(key_ceremony.guardian_secret_key_shares.len(), 3);
+
+        // Without a callback, the outcome is the same.
+        let mut csprng = Csprng::new(b"test_key_ceremony_progress");
+        let key_ceremony_2 = KeyCeremony::run(&mut csprng, &election_parameters, None).unwrap();
+        assert_eq!(
+            key_ceremony_2.joint_election_public_key,
+            key_ceremony.joint_election_public_key
+        );
+        for (key_share_2, key_share) in key_ceremony_2
+            .guardian_secret_key_shares
+            .iter()
+            .zip(&key_ceremony.guardian_secret_key_shares)
+        {
+            assert_eq!(key_share_2.p_i, key_share.p_i);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! Validation of the labels of election manifest items.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::ident::{Ident, IdentError, IDENT_MAX_LEN_CHARS};
+
+/// The default maximum length of a label, in characters.
+///
+/// This is generous enough for any realistic contest or candidate description, but keeps a
+/// pathologically long label from bloating the election record and user interfaces.
+pub const LABEL_MAX_LEN_CHARS: usize = 1024;
+
+/// The maximum length of an external identifier, in characters.
+pub const EXTERNAL_ID_MAX_LEN_CHARS: usize = IDENT_MAX_LEN_CHARS;
+
+/// Represents errors occurring during the validation of a label.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum LabelValidationError {
+    /// Occurs if the label is longer than allowed.
+    #[error(
+        "The label of {labeled_item} is {len} characters long, exceeding the maximum of {max}."
+    )]
+    LabelTooLong {
+        labeled_item: String,
+        len: usize,
+        max: usize,
+    },
+
+    /// Occurs if two items which must be distinguishable have the same label.
+    #[error("The label of {labeled_item} duplicates that of {first_labeled_item}: {label:?}")]
+    DuplicateLabel {
+        labeled_item: String,
+        first_labeled_item: String,
+        label: String,
+    },
+
+    /// Occurs if an external identifier is empty, too long, or contains characters other than
+    /// ASCII letters, digits, `-`, `_` and `.`.
+    #[error("The external identifier of {labeled_item} is malformed: {external_id:?}")]
+    MalformedExternalId {
+        labeled_item: String,
+        external_id: String,
+        #[source]
+        source: IdentError,
+    },
+}
+
+/// Validates a label against the default maximum length [`LABEL_MAX_LEN_CHARS`].
+///
+/// The arguments are
+/// - `label` - the label
+/// - `labeled_item` - a description of the item having the label, for the error message
+pub fn validate_label(label: &str, labeled_item: &str) -> Result<(), LabelValidationError> {
+    validate_label_max_len(label, labeled_item, LABEL_MAX_LEN_CHARS)
+}
+
+/// Validates a label against the specified maximum length, in characters.
+///
+/// The arguments are
+/// - `label` - the label
+/// - `labeled_item` - a description of the item having the label, for the error message
+/// - `max` - the maximum length of the label, in characters
+pub fn validate_label_max_len(
+    label: &str,
+    labeled_item: &str,
+    max: usize,
+) -> Result<(), LabelValidationError> {
+    let len = label.chars().count();
+    if max < len {
+        return Err(LabelValidationError::LabelTooLong {
+            labeled_item: labeled_item.to_string(),
+            len,
+            max,
+        });
+    }
+
+    Ok(())
+}
+
+/// Validates an external identifier, e.g., a candidate ID from an external election management
+/// system, and returns it as an [`Ident`]. It must be non-empty, at most
+/// [`EXTERNAL_ID_MAX_LEN_CHARS`] long, and consist only of ASCII letters, digits, `-`, `_` and `.`.
+/// The error keeps the [`IdentError`] as its source, e.g., the position of an invalid character.
+///
+/// The arguments are
+/// - `external_id` - the external identifier
+/// - `labeled_item` - a description of the item having the external identifier, for the error message
+pub fn validate_external_id(
+    external_id: &str,
+    labeled_item: &str,
+) -> Result<Ident, LabelValidationError> {
+    Ident::try_from_str(external_id).map_err(|source| LabelValidationError::MalformedExternalId {
+        labeled_item: labeled_item.to_string(),
+        external_id: external_id.to_string(),
+        source,
+    })
+}
+
+/// Validates that no two of the supplied labels are the same.
+///
+/// The argument is an iterator of pairs of
+/// - a label
+/// - a description of the item having the label, for the error message
+pub fn validate_labels_unique<'a, I>(labels: I) -> Result<(), LabelValidationError>
+where
+    I: IntoIterator<Item = (&'a str, String)>,
+{
+    match find_duplicate_labels(labels).into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Finds every label which is the same as an earlier one, in order. Unlike
+/// [`validate_labels_unique`], this reports all the duplicates, e.g., for a lint.
+///
+/// The argument is an iterator of pairs of
+/// - a label
+/// - a description of the item having the label, for the error message
+pub fn find_duplicate_labels<'a, I>(labels: I) -> Vec<LabelValidationError>
+where
+    I: IntoIterator<Item = (&'a str, String)>,
+{
+    let mut labeled_items: HashMap<&str, String> = HashMap::new();
+    let mut duplicates = Vec::new();
+    for (label, labeled_item) in labels {
+        if let Some(first_labeled_item) = labeled_items.get(label) {
+            duplicates.push(LabelValidationError::DuplicateLabel {
+                labeled_item,
+                first_labeled_item: first_labeled_item.clone(),
+                label: label.to_string(),
+            });
+        } else {
+            labeled_items.insert(label, labeled_item);
+        }
+    }
+
+    duplicates
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate_label_len() {
+        let label = |len: usize| "é".repeat(len);
+
+        assert!(validate_label(&label(LABEL_MAX_LEN_CHARS - 1), "contest 1").is_ok());
+        assert!(validate_label(&label(LABEL_MAX_LEN_CHARS), "contest 1").is_ok());
+        assert_eq!(
+            validate_label(&label(LABEL_MAX_LEN_CHARS + 1), "contest 1").unwrap_err(),
+            LabelValidationError::LabelTooLong {
+                labeled_item: "contest 1".to_string(),
+                len: LABEL_MAX_LEN_CHARS + 1,
+                max: LABEL_MAX_LEN_CHARS,
+            }
+        );
+
+        assert!(validate_label_max_len("abc", "option 2", 3).is_ok());
+        assert!(validate_label_max_len("abcd", "option 2", 3).is_err());
+    }
+
+    #[test]
+    fn test_validate_labels_unique() {
+        let labeled = |labels: &[&'static str]| {
+            labels
+                .iter()
+                .enumerate()
+                .map(|(ix, &label)| (label, format!("contest {}", ix + 1)))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(validate_labels_unique(labeled(&["A", "B", "C"])).is_ok());
+        assert_eq!(
+            validate_labels_unique(labeled(&["A", "B", "A"])).unwrap_err(),
+            LabelValidationError::DuplicateLabel {
+                labeled_item: "contest 3".to_string(),
+                first_labeled_item: "contest 1".to_string(),
+                label: "A".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_find_duplicate_labels() {
+        let labeled = ["A", "B", "A", "B", "A"]
+            .iter()
+            .enumerate()
+            .map(|(ix, &label)| (label, format!("option {}", ix + 1)));
+
+        let duplicates = find_duplicate_labels(labeled);
+        assert_eq!(
+            duplicates
+                .iter()
+                .map(|err| match err {
+                    LabelValidationError::DuplicateLabel {
+                        labeled_item,
+                        first_labeled_item,
+                        ..
+                    } => (labeled_item.as_str(), first_labeled_item.as_str()),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>(),
+            [
+                ("option 3", "option 1"),
+                ("option 4", "option 2"),
+                ("option 5", "option 1"),
+            ]
+        );
+    }
+}
@@ -60,7 +60,9 @@
 //!
 //! - [VerifiableDecryption](crate::verifiable_decryption::VerifiableDecryption) A decrypted plain-text with a [proof of correct decryption](crate::verifiable_decryption::DecryptionProof)
 
+pub mod audit;
 pub mod ballot;
+pub mod ballot_encryption_precompute;
 pub mod ballot_style;
 pub mod confirmation_code;
 pub mod contest_encrypted;
@@ -70,9 +72,15 @@ pub mod device;
 pub mod election_manifest;
 pub mod election_parameters;
 pub mod election_record;
+pub mod error_code;
+#[cfg(test)]
+mod event_capture;
 pub mod example_election_manifest;
 pub mod example_election_parameters;
+pub mod example_pre_voting_data;
 pub mod fixed_parameters;
+#[cfg(feature = "eg_allow_test_data_generation")]
+pub mod golden_record;
 pub mod guardian;
 pub mod guardian_coeff_proof;
 pub mod guardian_public_key;
@@ -82,12 +90,20 @@ pub mod guardian_share;
 pub mod hash;
 pub mod hashes;
 pub mod hashes_ext;
+pub mod ident;
 pub mod index;
+pub mod index_map1;
 pub mod joint_election_public_key;
+pub mod key_ceremony;
+pub mod label;
+pub mod manifest_lint;
 pub mod nonce;
+pub mod schema_version;
 pub mod serializable;
+pub mod spoiled_ballot;
 pub mod standard_parameters;
 pub mod varying_parameters;
 pub mod vec1;
 pub mod verifiable_decryption;
+pub mod verifier;
 pub mod zk;
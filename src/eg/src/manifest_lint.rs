@@ -0,0 +1,390 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! Linting of election manifests.
+//!
+//! The lint and [`ElectionManifest::validate_with_limits`] apply the same rules, but unlike
+//! validation, which stops at the first error, the lint reports every finding, including warnings
+//! about things which are allowed but likely mistakes.
+
+use std::collections::BTreeSet;
+
+use anyhow::anyhow;
+use serde::Serialize;
+
+use crate::{
+    election_manifest::{
+        ContestIndex, ElectionManifest, ElectionManifestLimitError, ElectionManifestLimits,
+        ELECTION_MANIFEST_SCHEMA_VERSION,
+    },
+    label::{find_duplicate_labels, validate_label},
+    schema_version::validate_schema_version,
+};
+
+/// The severity of a [`ManifestLintFinding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum ManifestLintSeverity {
+    /// The manifest is allowed, but this is likely a mistake.
+    Warning,
+
+    /// The manifest would be rejected by [`ElectionManifest::validate_with_limits`].
+    Error,
+}
+
+impl std::fmt::Display for ManifestLintSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManifestLintSeverity::Warning => f.write_str("warning"),
+            ManifestLintSeverity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// A problem found in an [`ElectionManifest`] by [`lint_election_manifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManifestLintFinding {
+    /// The severity of the finding.
+    pub severity: ManifestLintSeverity,
+
+    /// A description of the finding.
+    pub message: String,
+}
+
+impl std::fmt::Display for ManifestLintFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+/// Returns every finding about the [`ElectionManifest`], in the order of the items concerned.
+///
+/// The manifest is valid under the specified limits exactly if no finding is an error.
+/// In addition, warnings are reported for
+/// - contests which appear on no ballot style, and so can never be voted on
+/// - contests whose selection limit exceeds the count of options, so it is effectively clamped
+/// - options of a contest which share a label
+/// - ballot styles without contests, if allowed by the limits
+pub fn lint_election_manifest(
+    manifest: &ElectionManifest,
+    limits: &ElectionManifestLimits,
+) -> Vec<ManifestLintFinding> {
+    manifest_problems(manifest, limits)
+        .into_iter()
+        .map(|(severity, err)| ManifestLintFinding {
+            severity,
+            message: format!("{err:#}"),
+        })
+        .collect()
+}
+
+/// The rules shared by [`lint_election_manifest`] and
+/// [`ElectionManifest::validate_with_limits`], which rejects the first error.
+///
+/// The errors keep their types, so that, e.g., [`crate::error_code::error_code_of`] can
+/// classify them.
+pub(crate) fn manifest_problems(
+    manifest: &ElectionManifest,
+    limits: &ElectionManifestLimits,
+) -> Vec<(ManifestLintSeverity, anyhow::Error)> {
+    use ManifestLintSeverity::*;
+
+    let mut problems: Vec<(ManifestLintSeverity, anyhow::Error)> = Vec::new();
+
+    if let Err(err) = validate_schema_version(
+        "ElectionManifest",
+        manifest.schema_version,
+        ELECTION_MANIFEST_SCHEMA_VERSION,
+    ) {
+        problems.push((Error, err.into()));
+    }
+
+    if let Err(err) = validate_label(&manifest.label, "the election manifest") {
+        problems.push((Error, err.into()));
+    }
+
+    let cnt = manifest.contests.len();
+    if limits.max_contests < cnt {
+        problems.push((
+            Error,
+            ElectionManifestLimitError::TooManyContests {
+                cnt,
+                max: limits.max_contests,
+            }
+            .into(),
+        ));
+    }
+
+    let contests_on_ballot_styles: BTreeSet<ContestIndex> = manifest
+        .ballot_styles
+        .iter()
+        .flat_map(|ballot_style| ballot_style.contests.iter().copied())
+        .collect();
+
+    for (contest_ix, contest) in manifest.contests.indices().zip(manifest.contests.iter()) {
+        if let Err(err) = validate_label(&contest.label, &format!("contest {contest_ix}")) {
+            problems.push((Error, err.into()));
+        }
+
+        let cnt = contest.options.len();
+        if limits.max_options_per_contest < cnt {
+            problems.push((
+                Error,
+                ElectionManifestLimitError::TooManyOptions {
+                    contest_ix,
+                    cnt,
+                    max: limits.max_options_per_contest,
+                }
+                .into(),
+            ));
+        }
+
+        if contest.exactly_one && contest.selection_limit != 1 {
+            problems.push((
+                Error,
+                anyhow!(
+                    "Contest {contest_ix} requires exactly one selection, but has selection limit {}",
+                    contest.selection_limit
+                ),
+            ));
+        }
+
+        if cnt < contest.selection_limit {
+            problems.push((
+                Warning,
+                anyhow!(
+                    "Contest {contest_ix} has selection limit {}, but only {cnt} options, so at most {cnt} can be selected.",
+                    contest.selection_limit
+                ),
+            ));
+        }
+
+        if !contests_on_ballot_styles.contains(&contest_ix) {
+            problems.push((
+                Warning,
+                anyhow!(
+                    "Contest {contest_ix} appears on no ballot style, so it can never be voted on."
+                ),
+            ));
+        }
+
+        for (option_ix, option) in contest.options.indices().zip(contest.options.iter()) {
+            let labeled_item = format!("option {option_ix} of contest {contest_ix}");
+            if let Err(err) = validate_label(&option.label, &labeled_item) {
+                problems.push((Error, err.into()));
+            }
+            if let Some(party) = &option.party {
+                if let Err(err) = validate_label(party, &format!("the party of {labeled_item}")) {
+                    problems.push((Error, err.into()));
+                }
+            }
+        }
+
+        problems.extend(
+            find_duplicate_labels(contest.options.indices().zip(contest.options.iter()).map(
+                |(option_ix, option)| {
+                    (
+                        option.label.as_str(),
+                        format!("option {option_ix} of contest {contest_ix}"),
+                    )
+                },
+            ))
+            .into_iter()
+            .map(|err| (Warning, err.into())),
+        );
+    }
+
+    for (ballot_style_ix, ballot_style) in manifest
+        .ballot_styles
+        .indices()
+        .zip(manifest.ballot_styles.iter())
+    {
+        if let Err(err) = validate_label(
+            &ballot_style.label,
+            &format!("ballot style {ballot_style_ix}"),
+        ) {
+            problems.push((Error, err.into()));
+        }
+
+        let contests: Vec<ContestIndex> = ballot_style.contests.iter().copied().collect();
+        if let Err(err) = manifest.validate_indices(&contests, &[]) {
+            problems.push((
+                Error,
+                anyhow::Error::new(err).context(format!("Ballot style {ballot_style_ix}")),
+            ));
+        }
+
+        if ballot_style.contests.is_empty() {
+            let severity = if limits.allow_empty_ballot_styles {
+                Warning
+            } else {
+                Error
+            };
+            problems.push((
+                severity,
+                ElectionManifestLimitError::BallotStyleHasNoContests { ballot_style_ix }.into(),
+            ));
+        }
+    }
+
+    problems.extend(
+        find_duplicate_labels(
+            manifest
+                .contests
+                .indices()
+                .zip(manifest.contests.iter())
+                .map(|(contest_ix, contest)| {
+                    (contest.label.as_str(), format!("contest {contest_ix}"))
+                }),
+        )
+        .into_iter()
+        .map(|err| (Error, err.into())),
+    );
+
+    problems.extend(
+        find_duplicate_labels(
+            manifest
+                .ballot_styles
+                .indices()
+                .zip(manifest.ballot_styles.iter())
+                .map(|(ballot_style_ix, ballot_style)| {
+                    (
+                        ballot_style.label.as_str(),
+                        format!("ballot style {ballot_style_ix}"),
+                    )
+                }),
+        )
+        .into_iter()
+        .map(|err| (Error, err.into())),
+    );
+
+    problems
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::{
+        ballot_style::{BallotStyle, BallotStyleIndex},
+        election_manifest::{Contest, ContestOption},
+        example_election_manifest::example_election_manifest,
+        vec1::Vec1,
+    };
+
+    /// A manifest with several problems, as found by the lint below.
+    fn seeded_manifest() -> ElectionManifest {
+        let contest = |label: &str, selection_limit: usize, options: &[&str]| Contest {
+            label: label.to_string(),
+            selection_limit,
+            exactly_one: false,
+            options: options
+                .iter()
+                .map(|&label| ContestOption::new(label))
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        };
+        let ballot_style = |label: &str, contests: &[u32]| BallotStyle {
+            label: label.to_string(),
+            contests: contests
+                .iter()
+                .map(|&ix| ContestIndex::from_one_based_index(ix).unwrap())
+                .collect(),
+        };
+
+        let contests: Vec1<Contest> = [
+            contest("Mayor", 1, &["Alice", "Bob"]),
+            contest("Council", 3, &["Carol", "Dave"]),
+            contest("Mayor", 1, &["Erin", "Erin"]),
+            contest("Dogcatcher", 1, &["Frank", "Grace"]),
+        ]
+        .try_into()
+        .unwrap();
+        let ballot_styles: Vec1<BallotStyle> = [
+            ballot_style("North", &[1, 2]),
+            ballot_style("South", &[]),
+            ballot_style("East", &[1, 3]),
+        ]
+        .try_into()
+        .unwrap();
+
+        ElectionManifest {
+            schema_version: ELECTION_MANIFEST_SCHEMA_VERSION,
+            label: "Seeded election".to_string(),
+            contests,
+            ballot_styles,
+        }
+    }
+
+    #[test]
+    fn test_lint_election_manifest() {
+        use ManifestLintSeverity::*;
+
+        let limits = ElectionManifestLimits::default();
+
+        // The example manifest is valid.
+        let findings = lint_election_manifest(&example_election_manifest(), &limits);
+        assert!(findings.iter().all(|finding| finding.severity == Warning));
+
+        let manifest = seeded_manifest();
+
+        // Validation rejects the first error the lint finds.
+        let err = manifest.validate_with_limits(&limits).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ElectionManifestLimitError>(),
+            Some(&ElectionManifestLimitError::BallotStyleHasNoContests {
+                ballot_style_ix: BallotStyleIndex::from_one_based_index(2).unwrap()
+            })
+        );
+
+        let findings = lint_election_manifest(&manifest, &limits);
+        let findings: Vec<_> = findings
+            .iter()
+            .map(|finding| (finding.severity, finding.message.as_str()))
+            .collect();
+        assert_eq!(
+            findings,
+            [
+                (
+                    Warning,
+                    "Contest 2 has selection limit 3, but only 2 options, so at most 2 can be selected."
+                ),
+                (
+                    Warning,
+                    "The label of option 2 of contest 3 duplicates that of option 1 of contest 3: \"Erin\""
+                ),
+                (
+                    Warning,
+                    "Contest 4 appears on no ballot style, so it can never be voted on."
+                ),
+                (Error, "Ballot style 2 has no contests."),
+                (
+                    Error,
+                    "The label of contest 3 duplicates that of contest 1: \"Mayor\""
+                ),
+            ]
+        );
+
+        // Allowing empty ballot styles makes that finding a warning.
+        let limits = ElectionManifestLimits {
+            allow_empty_ballot_styles: true,
+            ..limits
+        };
+        let findings = lint_election_manifest(&manifest, &limits);
+        assert_eq!(
+            findings
+                .iter()
+                .filter(|finding| finding.severity == Error)
+                .count(),
+            1
+        );
+        assert_eq!(
+            findings[3].to_string(),
+            "warning: Ballot style 2 has no contests."
+        );
+    }
+}
@@ -34,3 +34,17 @@ pub fn encrypted(
     let nonce = eg_h(&header.hashes_ext.h_e, &v);
     FieldElement::from_bytes_be(nonce.0.as_slice(), field)
 }
+
+/// Derives a primary nonce `ξ_B` from a seed string, so that ballots encrypted with the same
+/// seed and selections have identical ciphertexts across runs.
+///
+/// This is completely insecure, as anyone knowing the seed can decrypt the ballots, and should
+/// only be used for testing and demos.
+#[cfg(feature = "eg_insecure_deterministic_nonces")]
+pub fn primary_nonce_from_seed_str(seed: &str) -> crate::hash::HValue {
+    // The key separates this use of H from those of the spec, which are keyed by election hashes.
+    let key = crate::hash::HValue::default();
+    let mut v = b"insecure_deterministic_primary_nonce".to_vec();
+    v.extend_from_slice(seed.as_bytes());
+    eg_h(&key, &v)
+}
@@ -0,0 +1,65 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! Schema versions of serialized records.
+//!
+//! A record carries the version of the schema it was written with, so that a record written by
+//! a later version of this library is rejected with a clear error instead of being misread.
+//!
+//! Records written before they carried a schema version are of the [`INITIAL_SCHEMA_VERSION`].
+//! That version is not written out, so the canonical bytes of such records, and any hashes
+//! computed from them, are the same as before schema versions were introduced. A record of any
+//! later version carries its version explicitly.
+
+use serde::Serialize;
+use thiserror::Error;
+
+/// The version of the schema of a serialized record.
+pub type SchemaVersion = u32;
+
+/// The schema version of records written before they carried a schema version.
+pub const INITIAL_SCHEMA_VERSION: SchemaVersion = 1;
+
+/// Returns the [`INITIAL_SCHEMA_VERSION`], for use as `#[serde(default = ...)]`.
+pub fn initial_schema_version() -> SchemaVersion {
+    INITIAL_SCHEMA_VERSION
+}
+
+/// Returns true iff `schema_version` is the [`INITIAL_SCHEMA_VERSION`], for use as
+/// `#[serde(skip_serializing_if = ...)]`.
+pub fn is_initial_schema_version(schema_version: &SchemaVersion) -> bool {
+    *schema_version == INITIAL_SCHEMA_VERSION
+}
+
+/// Represents errors occurring if a record has a schema version which is not supported.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum SchemaVersionError {
+    /// Occurs if the schema version of a record is not the supported one.
+    #[error("{type_name} has schema version {found}, but only version {supported} is supported.")]
+    UnsupportedSchemaVersion {
+        type_name: String,
+        found: SchemaVersion,
+        supported: SchemaVersion,
+    },
+}
+
+/// Checks that the schema version `found` of a record of type `type_name` is the `supported`
+/// version.
+pub fn validate_schema_version(
+    type_name: &str,
+    found: SchemaVersion,
+    supported: SchemaVersion,
+) -> Result<(), SchemaVersionError> {
+    if found != supported {
+        return Err(SchemaVersionError::UnsupportedSchemaVersion {
+            type_name: type_name.to_string(),
+            found,
+            supported,
+        });
+    }
+    Ok(())
+}
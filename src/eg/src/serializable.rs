@@ -1,15 +1,25 @@
 use std::io::Cursor;
 
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
 
 pub trait SerializableCanonical {
+    /// Streams the canonical bytes of an entity to a [`std::io::Write`], such as a file,
+    /// a socket, or a hashing writer, without buffering them all in memory.
+    /// This uses a more compact JSON format.
+    fn to_canonical_writer<W: std::io::Write>(&self, writer: W) -> Result<()>
+    where
+        Self: serde::Serialize,
+    {
+        serde_json::ser::to_writer(writer, self).context("Writing canonical")
+    }
+
     /// Writes an entity to a [`std::io::Write`] as canonical bytes.
     /// This uses a more compact JSON format.
     fn to_stdiowrite_canonical(&self, stdiowrite: &mut dyn std::io::Write) -> Result<()>
     where
         Self: serde::Serialize,
     {
-        serde_json::ser::to_writer(stdiowrite, self).context("Writing canonical")
+        self.to_canonical_writer(stdiowrite)
     }
 
     /// Returns the canonical byte sequence representation of the entity.
@@ -19,24 +29,22 @@ pub trait SerializableCanonical {
         Self: serde::Serialize,
     {
         let mut buf = Cursor::new(Vec::new());
-        self.to_stdiowrite_canonical(&mut buf)
-            .context("Writing canonical")?;
+        self.to_canonical_writer(&mut buf)?;
         Ok(buf.into_inner())
     }
 }
 
+/// The pretty JSON representation differs from the canonical one only in whitespace, so it is
+/// just as deterministic and can be diffed in version control. In both, the fields of a struct
+/// are written in the order of their declaration and the keys of a map in sorted order, as maps
+/// are `BTreeMap`s.
 pub trait SerializablePretty {
     /// Writes an entity to a [`std::io::Write`] as pretty JSON.
     fn to_stdiowrite_pretty(&self, stdiowrite: &mut dyn std::io::Write) -> Result<()>
     where
         Self: serde::Serialize,
     {
-        let mut ser = serde_json::Serializer::pretty(stdiowrite);
-
-        self.serialize(&mut ser)
-            .map_err(Into::<anyhow::Error>::into)
-            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
-            .context("Writing pretty")
+        stdiowrite_pretty(self, stdiowrite)
     }
 
     /// Returns a pretty JSON `String` representation of the entity.
@@ -52,3 +60,105 @@ pub trait SerializablePretty {
         s
     }
 }
+
+/// Marker trait for types containing secret material, such as a guardian's secret key.
+/// These are written only through [`SerializableSecret`], not [`SerializablePretty`].
+pub trait ContainsSecretMaterial {}
+
+pub trait SerializableSecret: ContainsSecretMaterial {
+    /// Writes an entity containing secret material to a [`std::io::Write`] as pretty JSON.
+    /// Returns an error unless `allow_secret` is `true`.
+    fn to_stdiowrite_pretty_secret(
+        &self,
+        stdiowrite: &mut dyn std::io::Write,
+        allow_secret: bool,
+    ) -> Result<()>
+    where
+        Self: serde::Serialize,
+    {
+        ensure!(
+            allow_secret,
+            "Refusing to write secret material without explicit permission"
+        );
+        stdiowrite_pretty(self, stdiowrite)
+    }
+}
+
+impl<T: ContainsSecretMaterial> SerializableSecret for T {}
+
+fn stdiowrite_pretty<T>(value: &T, stdiowrite: &mut dyn std::io::Write) -> Result<()>
+where
+    T: serde::Serialize + ?Sized,
+{
+    let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+    value
+        .serialize(&mut ser)
+        .map_err(Into::<anyhow::Error>::into)
+        .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+        .context("Writing pretty")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::example_election_manifest::example_election_manifest;
+
+    /// Removes the whitespace outside of the strings of a JSON text.
+    fn strip_json_whitespace(json: &str) -> String {
+        let mut stripped = String::new();
+        let (mut in_string, mut escaped) = (false, false);
+        for ch in json.chars() {
+            if in_string || !ch.is_whitespace() {
+                stripped.push(ch);
+            }
+            if in_string && !escaped && ch == '"' {
+                in_string = false;
+            } else if !in_string && ch == '"' {
+                in_string = true;
+            }
+            escaped = in_string && !escaped && ch == '\\';
+        }
+        stripped
+    }
+
+    #[test]
+    fn test_pretty_deterministic() {
+        let election_manifest = example_election_manifest();
+
+        let mut pretty_1 = Vec::new();
+        election_manifest
+            .to_stdiowrite_pretty(&mut pretty_1)
+            .unwrap();
+        let mut pretty_2 = Vec::new();
+        example_election_manifest()
+            .to_stdiowrite_pretty(&mut pretty_2)
+            .unwrap();
+        assert_eq!(pretty_1, pretty_2);
+        assert_eq!(pretty_1, election_manifest.to_json_pretty().into_bytes());
+
+        // The keys are in the same order as in the canonical form.
+        let pretty = String::from_utf8(pretty_1).unwrap();
+        let canonical = String::from_utf8(election_manifest.to_canonical_bytes().unwrap()).unwrap();
+        assert_ne!(pretty, canonical);
+        assert_eq!(strip_json_whitespace(&pretty), canonical);
+    }
+
+    #[test]
+    fn test_to_canonical_writer() {
+        let election_manifest = example_election_manifest();
+
+        let mut streamed = Vec::new();
+        election_manifest
+            .to_canonical_writer(&mut streamed)
+            .unwrap();
+        assert_eq!(streamed, election_manifest.to_canonical_bytes().unwrap());
+
+        let mut streamed_buffered = Vec::new();
+        election_manifest
+            .to_canonical_writer(std::io::BufWriter::new(&mut streamed_buffered))
+            .unwrap();
+        assert_eq!(streamed_buffered, streamed);
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! Spoiled (challenged) ballots, published with their revealed selections so that anyone can
+//! check that the device encrypted the voter's selections faithfully.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    ballot::{BallotEncrypted, BallotState},
+    contest_selection::ContestSelection,
+    election_manifest::{ContestIndex, ContestOptionIndex},
+    election_record::PreVotingData,
+    hash::HValue,
+    nonce::encrypted as nonce,
+    serializable::{SerializableCanonical, SerializablePretty},
+};
+
+/// The reason a [`SpoiledBallot`] is invalid, as found by [`SpoiledBallot::validate`].
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum SpoiledBallotError {
+    /// The ballot is not in the challenged state
+    #[error("The spoiled ballot is not in the challenged state.")]
+    NotChallenged,
+
+    /// A contest of the ballot has no revealed selection, or vice versa
+    #[error("Contest (index {idx}) is not both encrypted on the ballot and revealed.")]
+    ContestMismatch { idx: ContestIndex },
+
+    /// The revealed selection of a contest does not have one vote per ciphertext
+    #[error("Contest (index {idx}) has {actual} revealed votes, but {expected} encrypted votes.")]
+    SelectionCountMismatch {
+        idx: ContestIndex,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A revealed vote does not re-encrypt to the ciphertext on the ballot
+    #[error("The revealed vote for option {option_ix} of contest (index {idx}) does not match its ciphertext.")]
+    SelectionMismatch {
+        idx: ContestIndex,
+        option_ix: ContestOptionIndex,
+    },
+}
+
+/// A spoiled, i.e., challenged, ballot together with its revealed selections.
+///
+/// Rather than decryptions with proofs, a spoiled ballot reveals its primary nonce `ξ_B`. From it
+/// anyone can derive the nonce of every option (Equation 22) and re-encrypt the revealed
+/// selections, which must reproduce the ciphertexts of the ballot exactly. This is as
+/// convincing as a proof of equivalence, and needs no guardians.
+///
+/// A spoiled ballot must never be counted, as its selections are public.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpoiledBallot {
+    /// The encrypted ballot, in the challenged state.
+    pub ballot: BallotEncrypted,
+
+    /// The primary nonce `ξ_B` with which the ballot was encrypted.
+    pub primary_nonce: HValue,
+
+    /// The revealed selections, for each contest of the ballot.
+    #[serde(deserialize_with = "util::serde::deserialize_one_based_index_map")]
+    pub selections: BTreeMap<ContestIndex, ContestSelection>,
+}
+
+impl SpoiledBallot {
+    /// Spoils an encrypted ballot by revealing the primary nonce and selections with which it
+    /// was encrypted. The ballot is put into the challenged state.
+    ///
+    /// Fails if the revealed selections do not match the ciphertexts of the ballot.
+    pub fn new(
+        mut ballot: BallotEncrypted,
+        primary_nonce: HValue,
+        selections: BTreeMap<ContestIndex, ContestSelection>,
+        header: &PreVotingData,
+    ) -> Result<Self, SpoiledBallotError> {
+        ballot.state = BallotState::Challenged;
+
+        let self_ = SpoiledBallot {
+            ballot,
+            primary_nonce,
+            selections,
+        };
+
+        self_.validate(header)?;
+
+        Ok(self_)
+    }
+
+    /// Verifies that the ballot is challenged, and that re-encrypting the revealed selections
+    /// with the nonces derived from the primary nonce reproduces the ciphertexts of the ballot.
+    pub fn validate(&self, header: &PreVotingData) -> Result<(), SpoiledBallotError> {
+        if self.ballot.state != BallotState::Challenged {
+            return Err(SpoiledBallotError::NotChallenged);
+        }
+
+        if let Some(&idx) = self
+            .selections
+            .keys()
+            .find(|idx| !self.ballot.contests.contains_key(idx))
+        {
+            return Err(SpoiledBallotError::ContestMismatch { idx });
+        }
+
+        let fixed_parameters = &header.parameters.fixed_parameters;
+        for (&idx, contest) in &self.ballot.contests {
+            let selection = self
+                .selections
+                .get(&idx)
+                .ok_or(SpoiledBallotError::ContestMismatch { idx })?;
+
+            let votes = selection.get_vote();
+            if votes.len() != contest.cnt_selections() {
+                return Err(SpoiledBallotError::SelectionCountMismatch {
+                    idx,
+                    expected: contest.cnt_selections(),
+                    actual: votes.len(),
+                });
+            }
+
+            for ((option_ix, ciphertext), &vote) in contest.iter_enumerate_selection().zip(votes) {
+                let nonce = nonce(header, &self.primary_nonce.0, idx, option_ix);
+                let reencrypted =
+                    header
+                        .public_key
+                        .encrypt_with(fixed_parameters, &nonce, vote as usize);
+                if reencrypted != *ciphertext {
+                    return Err(SpoiledBallotError::SelectionMismatch { idx, option_ix });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads a [`SpoiledBallot`] from a [`std::io::Read`] and validates it.
+    /// It can be either the canonical or pretty JSON representation.
+    pub fn from_stdioread_validated(
+        stdioread: &mut dyn std::io::Read,
+        header: &PreVotingData,
+    ) -> Result<Self> {
+        let self_: Self = serde_json::from_reader(stdioread).context("Reading SpoiledBallot")?;
+
+        self_.validate(header)?;
+
+        Ok(self_)
+    }
+}
+
+impl SerializableCanonical for SpoiledBallot {}
+
+impl SerializablePretty for SpoiledBallot {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{ballot_style::BallotStyleIndex, example_pre_voting_data::toy_device};
+
+    fn spoiled_ballot(csprng: &mut Csprng) -> (SpoiledBallot, PreVotingData) {
+        let device = toy_device(csprng);
+        let pre_voting_data = device.header.clone();
+
+        let ballot_style_index = BallotStyleIndex::from_one_based_index(1).unwrap();
+        let manifest = device.header.manifest();
+        let selections: BTreeMap<_, _> = manifest
+            .ballot_styles
+            .get(ballot_style_index)
+            .unwrap()
+            .contests
+            .iter()
+            .map(|&contest_ix| {
+                let contest = manifest.contests.get(contest_ix).unwrap();
+                let selection = ContestSelection::new_pick_random(
+                    csprng,
+                    contest.selection_limit,
+                    contest.options.len(),
+                );
+                (contest_ix, selection)
+            })
+            .collect();
+
+        let primary_nonce = HValue::from([7u8; 32]);
+        let ballot = BallotEncrypted::new_from_selections(
+            ballot_style_index,
+            &device,
+            "2023-05-02",
+            csprng,
+            &primary_nonce.0,
+            &selections,
+        )
+        .unwrap();
+
+        let spoiled_ballot =
+            SpoiledBallot::new(ballot, primary_nonce, selections, &pre_voting_data).unwrap();
+        (spoiled_ballot, pre_voting_data)
+    }
+
+    #[test]
+    fn test_spoiled_ballot_round_trip() {
+        let mut csprng = Csprng::new(b"test_spoiled_ballot_round_trip");
+        let (spoiled_ballot, pre_voting_data) = spoiled_ballot(&mut csprng);
+        assert_eq!(spoiled_ballot.ballot.state, BallotState::Challenged);
+
+        let canonical_bytes = spoiled_ballot.to_canonical_bytes().unwrap();
+        let pretty_bytes = spoiled_ballot.to_json_pretty().into_bytes();
+        for bytes in [&canonical_bytes, &pretty_bytes] {
+            let loaded =
+                SpoiledBallot::from_stdioread_validated(&mut bytes.as_slice(), &pre_voting_data)
+                    .unwrap();
+            assert_eq!(loaded.to_canonical_bytes().unwrap(), canonical_bytes);
+        }
+    }
+
+    #[test]
+    fn test_spoiled_ballot_tampered() {
+        let mut csprng = Csprng::new(b"test_spoiled_ballot_tampered");
+        let (mut spoiled_ballot, pre_voting_data) = spoiled_ballot(&mut csprng);
+
+        // Flip the revealed vote for the first option of the first contest.
+        let idx = ContestIndex::from_one_based_index(1).unwrap();
+        let selection = spoiled_ballot.selections.get_mut(&idx).unwrap();
+        let mut votes = selection.get_vote().to_vec();
+        votes[0] = 1 - votes[0];
+        *selection = ContestSelection::new(votes).unwrap();
+
+        assert_eq!(
+            spoiled_ballot.validate(&pre_voting_data),
+            Err(SpoiledBallotError::SelectionMismatch {
+                idx,
+                option_ix: ContestOptionIndex::from_one_based_index(1).unwrap(),
+            })
+        );
+
+        // The tampered spoiled ballot is rejected on load.
+        let bytes = spoiled_ballot.to_canonical_bytes().unwrap();
+        let err = SpoiledBallot::from_stdioread_validated(&mut bytes.as_slice(), &pre_voting_data)
+            .unwrap_err();
+        assert!(err.downcast_ref::<SpoiledBallotError>().is_some());
+
+        // As is a spoiled ballot which is not challenged.
+        let (mut spoiled_ballot, _) = self::spoiled_ballot(&mut csprng);
+        spoiled_ballot.ballot.state = BallotState::Cast;
+        assert_eq!(
+            spoiled_ballot.validate(&pre_voting_data),
+            Err(SpoiledBallotError::NotChallenged)
+        );
+    }
+}
@@ -8,23 +8,31 @@
 //! specification `2.0.0`.
 
 use crate::{
+    ballot::{ContestOptionTally, TallyError},
     election_manifest::ElectionManifest,
     election_parameters::ElectionParameters,
     fixed_parameters::FixedParameters,
     guardian::GuardianIndex,
     guardian_public_key::GuardianPublicKey,
     guardian_share::GuardianSecretKeyShare,
-    hash::{eg_h, HValue},
+    hash::HValue,
     hashes::Hashes,
     hashes_ext::HashesExt,
     joint_election_public_key::{Ciphertext, JointElectionPublicKey},
+    zk::TranscriptBuilder,
 };
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
 use itertools::izip;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use util::{
     algebra::{FieldElement, Group, GroupElement, ScalarField},
-    algebra_utils::{get_single_coefficient_at_zero, group_lagrange_at_zero, DiscreteLog},
+    algebra_utils::{
+        get_single_coefficient_at_zero, group_lagrange_at_zero, DiscreteLog, LagrangeBasisAtZero,
+        LagrangeError,
+    },
     csprng::Csprng,
 };
 
@@ -84,8 +92,8 @@ pub enum ShareCombinationError {
     #[error("Guardian {i} is represented more than once in the decryption shares.")]
     DuplicateGuardian { i: GuardianIndex },
     /// Occurs if the Lagrange interpolation fails.
-    #[error("Could not compute the polynomial interpolation.")]
-    InterpolationFailure,
+    #[error("Could not compute the polynomial interpolation: {err}")]
+    InterpolationFailure { err: LagrangeError },
 }
 
 impl CombinedDecryptionShare {
@@ -138,13 +146,11 @@ impl CombinedDecryptionShare {
             ys.push(share.m_i.clone());
         }
 
-        let m = group_lagrange_at_zero(&xs, &ys, &fixed_parameters.field, &fixed_parameters.group);
+        // This should not fail, as the guardian indices are distinct and non-zero.
+        let m = group_lagrange_at_zero(&xs, &ys, &fixed_parameters.field, &fixed_parameters.group)
+            .map_err(|err| ShareCombinationError::InterpolationFailure { err })?;
 
-        match m {
-            // This should not happen
-            None => Err(ShareCombinationError::InterpolationFailure),
-            Some(m) => Ok(CombinedDecryptionShare(m)),
-        }
+        Ok(CombinedDecryptionShare(m))
     }
 }
 
@@ -194,6 +200,9 @@ pub enum ResponseShareError {
     /// Occurs if the Lagrange coefficient can not be computed.
     #[error("Computation of the Lagrange coefficient failed.")]
     CoefficientFailure,
+    /// Occurs if the challenge can not be computed.
+    #[error(transparent)]
+    ProofTranscript(#[from] ProofTranscriptError),
 }
 
 /// Represents errors occurring while combining the commit and response shares
@@ -219,6 +228,18 @@ pub enum CombineProofError {
     /// Occurs if the Lagrange coefficient can not be computed.
     #[error("Computation of the Lagrange coefficient failed!")]
     CoefficientFailure,
+    /// Occurs if the challenge can not be computed.
+    #[error(transparent)]
+    ProofTranscript(#[from] ProofTranscriptError),
+}
+
+/// Represents errors occurring while computing the challenge of a [`DecryptionProof`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ProofTranscriptError {
+    /// Occurs if the bytes hashed into the challenge are not of the expected length, e.g.,
+    /// because a group element is too large for the group.
+    #[error("The proof transcript is {actual} bytes long, but {expected} bytes were expected.")]
+    UnexpectedProofTranscriptLength { expected: usize, actual: usize },
 }
 
 /// Proof that a given plaintext is the decryption of a given ciphertext
@@ -264,7 +285,12 @@ impl DecryptionProof {
 
     /// This function computes the challenge for the decryption NIZK.
     ///
-    /// The computation corresponds to Equation `71`.
+    /// The computation corresponds to Equation `71`. The hash value is reduced modulo `q`
+    /// (cf. Section 5.4), so the challenge is always a valid field element. Proofs whose
+    /// challenge is not reduced do not validate.
+    ///
+    /// Returns an error if the group elements do not all encode to the byte length of the group
+    /// modulus, rather than hashing a transcript of the wrong length.
     ///
     /// The arguments are
     /// - `fixed_parameters` - the fixed parameters
@@ -282,24 +308,28 @@ impl DecryptionProof {
         a: &GroupElement,
         b: &GroupElement,
         m: &CombinedDecryptionShare,
-    ) -> FieldElement {
+    ) -> Result<FieldElement, ProofTranscriptError> {
         let field = &fixed_parameters.field;
         let group = &fixed_parameters.group;
         // v = 0x30 | b(k,512) | b(c.A,512)| b(c.B,512) | b(a,512) | b(b,512) | b(m,512)
-        let mut v = vec![0x30];
-        v.extend_from_slice(
-            k.joint_election_public_key
-                .to_be_bytes_left_pad(group)
-                .as_slice(),
-        );
-        v.extend_from_slice(c.alpha.to_be_bytes_left_pad(group).as_slice());
-        v.extend_from_slice(c.beta.to_be_bytes_left_pad(group).as_slice());
-        v.extend_from_slice(a.to_be_bytes_left_pad(group).as_slice());
-        v.extend_from_slice(b.to_be_bytes_left_pad(group).as_slice());
-        v.extend_from_slice(m.0.to_be_bytes_left_pad(group).as_slice());
-        let c = eg_h(h_e, &v);
-        //The challenge is not reduced modulo q (cf. Section 5.4)
-        FieldElement::from_bytes_be(c.0.as_slice(), field)
+        let transcript = TranscriptBuilder::new()
+            .push_u8(0x30)
+            .push_group_element(&k.joint_election_public_key, group)
+            .push_group_element(&c.alpha, group)
+            .push_group_element(&c.beta, group)
+            .push_group_element(a, group)
+            .push_group_element(b, group)
+            .push_group_element(&m.0, group);
+
+        let expected = 1 + 6 * group.p_len_bytes();
+        if transcript.len() != expected {
+            return Err(ProofTranscriptError::UnexpectedProofTranscriptLength {
+                expected,
+                actual: transcript.len(),
+            });
+        }
+
+        Ok(transcript.finish(h_e, field))
     }
 
     /// This function computes a guardian's response share for the decryption
@@ -342,17 +372,15 @@ impl DecryptionProof {
                 (a.mul(&share.a_i, group), b.mul(&share.b_i, group))
             });
         // Equation `71`
-        let c = Self::challenge(fixed_parameters, &h_e.h_e, k, ciphertext, &a, &b, m);
+        let c = Self::challenge(fixed_parameters, &h_e.h_e, k, ciphertext, &a, &b, m)?;
         // Equation `72` c_i = (c*w_i)
         let i_scalar = FieldElement::from(proof_commit_state.i.get_one_based_u32(), field);
         let xs: Vec<FieldElement> = proof_commit_shares
             .iter()
             .map(|s| FieldElement::from(s.i.get_one_based_u32(), field))
             .collect();
-        let w_i = match get_single_coefficient_at_zero(&xs, &i_scalar, field) {
-            Some(w_i) => w_i,
-            None => return Err(ResponseShareError::CoefficientFailure),
-        };
+        let w_i = get_single_coefficient_at_zero(&xs, &i_scalar, field)
+            .ok_or(ResponseShareError::CoefficientFailure)?;
         let c_i = c.mul(&w_i, field);
         // Equation `73` v_i = (u_i - c_i*P(i))
         let v_i = proof_commit_state
@@ -440,21 +468,22 @@ impl DecryptionProof {
             &a,
             &b,
             &m,
-        );
+        )?;
 
         let xs: Vec<FieldElement> = proof_commit_shares
             .clone()
             .map(|s| FieldElement::from(s.i.get_one_based_u32(), field))
             .collect();
+        let basis = LagrangeBasisAtZero::new(&xs, field)
+            .map_err(|_| CombineProofError::CoefficientFailure)?;
         let mut c_i_vec = vec![];
         for cs in proof_commit_shares.clone() {
             let i = cs.i.get_one_based_u32();
             let i = FieldElement::from(i, field);
-            let w_i = match get_single_coefficient_at_zero(&xs, &i, &fixed_parameters.field) {
-                Some(w_i) => w_i,
-                None => return Err(CombineProofError::CoefficientFailure),
-            };
-            let c_i = c.mul(&w_i, field);
+            let w_i = basis
+                .coefficient(&i)
+                .ok_or(CombineProofError::CoefficientFailure)?;
+            let c_i = c.mul(w_i, field);
             c_i_vec.push(c_i);
         }
 
@@ -469,13 +498,9 @@ impl DecryptionProof {
             let g_v = group.g_exp(&rs.v_i);
             let i_scalar = FieldElement::from(ds.i.get_one_based_u32(), field);
             let k_prod = guardian_public_keys.iter().fold(Group::one(), |prod, pk| {
-                let inner_p = pk.coefficient_commitments.0.iter().enumerate().fold(
-                    Group::one(),
-                    |prod, (m, k_m)| {
-                        let i_pow_m = i_scalar.pow(m, field);
-                        prod.mul(&k_m.0.exp(&i_pow_m, group), group)
-                    },
-                );
+                let inner_p = pk
+                    .coefficient_commitments
+                    .evaluate_in_exponent(&i_scalar, group, field);
                 prod.mul(&inner_p, group)
             });
             let a_i = g_v.mul(&k_prod.exp(&c_i, group), group);
@@ -535,16 +560,55 @@ impl DecryptionProof {
         let b = a_v.mul(&m_c, group);
 
         //Check (9.A)
-        if !self.response.is_valid(field) {
+        if self.validate_ranges(field).is_err() {
             return false;
         }
-        let c = Self::challenge(fixed_parameters, &h_e.h_e, joint_key, ciphertext, &a, &b, m);
+        let Ok(c) = Self::challenge(fixed_parameters, &h_e.h_e, joint_key, ciphertext, &a, &b, m)
+        else {
+            return false;
+        };
         //Check (9.B)
         if c != self.challenge {
             return false;
         }
         true
     }
+
+    /// Checks that the challenge and the response of the decryption proof are in `Z_q`.
+    pub fn validate_ranges(&self, field: &ScalarField) -> Result<(), DecryptionProofRangeError> {
+        if !self.challenge.is_valid(field) {
+            return Err(DecryptionProofRangeError::ChallengeNotInField);
+        }
+        //Check (9.A)
+        if !self.response.is_valid(field) {
+            return Err(DecryptionProofRangeError::ResponseNotInField);
+        }
+        Ok(())
+    }
+
+    /// Reads a [`DecryptionProof`] from a [`std::io::Read`] and checks that its challenge
+    /// and response are in `Z_q`.
+    pub fn from_stdioread_validated(
+        stdioread: &mut dyn std::io::Read,
+        fixed_parameters: &FixedParameters,
+    ) -> Result<Self> {
+        let self_: Self = serde_json::from_reader(stdioread).context("Reading DecryptionProof")?;
+
+        self_.validate_ranges(&fixed_parameters.field)?;
+
+        Ok(self_)
+    }
+}
+
+/// Represents errors occurring if a [`DecryptionProof`] has a component out of range.
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum DecryptionProofRangeError {
+    /// Occurs if the challenge is not in `Z_q`.
+    #[error("The challenge of the decryption proof is not in Z_q.")]
+    ChallengeNotInField,
+    /// Occurs if the response is not in `Z_q`, as required by Verification check (9.A).
+    #[error("The response of the decryption proof is not in Z_q.")]
+    ResponseNotInField,
 }
 
 /// Represents errors occurring during decryption.
@@ -587,6 +651,24 @@ pub enum ComputeDecryptionError {
     CombineProofShares(#[from] CombineProofError),
     #[error("One or more input parameters were not hashable.")]
     InvalidParameters,
+    #[error("The decryption share of guardian {share_i} is bundled with the commitment share of guardian {proof_commit_i}.")]
+    ShareCommitIndexMismatch {
+        share_i: GuardianIndex,
+        proof_commit_i: GuardianIndex,
+    },
+}
+
+impl DecryptionShareResult {
+    /// Checks that the decryption share and the commitment share are from the same guardian.
+    pub fn validate(&self) -> Result<(), ComputeDecryptionError> {
+        if self.share.i != self.proof_commit.i {
+            return Err(ComputeDecryptionError::ShareCommitIndexMismatch {
+                share_i: self.share.i,
+                proof_commit_i: self.proof_commit.i,
+            });
+        }
+        Ok(())
+    }
 }
 
 impl VerifiableDecryption {
@@ -605,6 +687,30 @@ impl VerifiableDecryption {
         m: &CombinedDecryptionShare,
         proof: &DecryptionProof,
     ) -> Result<Self, DecryptionError> {
+        let plain_text = Self::decrypt_plain_text(fixed_parameters, joint_key, ciphertext, m)?;
+        Ok(VerifiableDecryption {
+            plain_text,
+            proof: proof.clone(),
+        })
+    }
+
+    /// Returns the decrypted plain-text as an integer count, e.g. the tally of a contest option.
+    ///
+    /// This goes through [`ContestOptionTally::try_from_field_element`], so fails if the
+    /// plain-text is not in the field or exceeds [`CONTEST_OPTION_TALLY_MAX`].
+    ///
+    /// [`CONTEST_OPTION_TALLY_MAX`]: crate::ballot::CONTEST_OPTION_TALLY_MAX
+    pub fn recovered_count(&self, field: &ScalarField) -> Result<u64, TallyError> {
+        ContestOptionTally::try_from_field_element(&self.plain_text, field).map(|tally| tally.get())
+    }
+
+    /// Computes the plain-text of a ciphertext from the combined decryption share.
+    fn decrypt_plain_text(
+        fixed_parameters: &FixedParameters,
+        joint_key: &JointElectionPublicKey,
+        ciphertext: &Ciphertext,
+        m: &CombinedDecryptionShare,
+    ) -> Result<FieldElement, DecryptionError> {
         let field = &fixed_parameters.field;
         let group = &fixed_parameters.group;
         let group_msg = match m.0.inv(group) {
@@ -613,14 +719,8 @@ impl VerifiableDecryption {
         };
         let base = &joint_key.joint_election_public_key;
         let dlog = DiscreteLog::from_group(base, group);
-        let plain_text = match dlog.ff_find(&group_msg, field) {
-            None => return Err(DecryptionError::NoDlog),
-            Some(x) => x,
-        };
-        Ok(VerifiableDecryption {
-            plain_text,
-            proof: proof.clone(),
-        })
+        dlog.ff_find(&group_msg, field)
+            .ok_or(DecryptionError::NoDlog)
     }
 
     /// This function computes a verifiable decryption together
@@ -647,6 +747,9 @@ impl VerifiableDecryption {
         Proofs::IntoIter: ExactSizeIterator,
     {
         let decryptions = decryptions.into_iter();
+        for decryption in decryptions.clone() {
+            decryption.validate()?;
+        }
         let m =
             CombinedDecryptionShare::combine(parameters, decryptions.clone().map(|d| &d.share))?;
 
@@ -716,30 +819,136 @@ impl VerifiableDecryption {
     }
 }
 
+/// Represents errors occurring while auditing a decryption with [`audit_decryption`].
+#[derive(Error, Debug)]
+pub enum DecryptionAuditError {
+    /// Occurs if a guardian of a quorum has no decryption share.
+    #[error("No decryption share was given for guardian {i} of quorum {quorum:?}.")]
+    MissingShare {
+        i: GuardianIndex,
+        quorum: Vec<GuardianIndex>,
+    },
+    /// Occurs if the decryption shares of a quorum could not be combined.
+    #[error("Could not combine the decryption shares of quorum {quorum:?}: {err}")]
+    ShareCombination {
+        quorum: Vec<GuardianIndex>,
+        err: ShareCombinationError,
+    },
+    /// Occurs if the ciphertext could not be decrypted with the decryption shares of a quorum.
+    #[error("Could not decrypt with the decryption shares of quorum {quorum:?}: {err}")]
+    Decryption {
+        quorum: Vec<GuardianIndex>,
+        err: DecryptionError,
+    },
+    /// Occurs if a quorum decrypts to a different plain-text than the first quorum.
+    #[error("Quorum {quorum:?} decrypts to a different plain-text than quorum {first_quorum:?}.")]
+    DivergentQuorum {
+        quorum: Vec<GuardianIndex>,
+        first_quorum: Vec<GuardianIndex>,
+    },
+}
+
+/// Decrypts the same ciphertext using the decryption shares of each of the supplied quorums of
+/// guardians, and checks that they all yield the same plain-text. This catches inconsistent or
+/// malicious decryption shares.
+///
+/// The arguments are
+/// - `election_parameters` - the election parameters
+/// - `joint_key` - the joint election public key
+/// - `ciphertext` - the ciphertext
+/// - `shares_by_guardian` - the decryption shares of the ciphertext, by guardian
+/// - `quorums` - the quorums, e.g., from [`GuardianIndex::quorum_combinations`]
+///
+/// Returns an error for the first quorum which does not decrypt to the plain-text of the first
+/// quorum.
+pub fn audit_decryption<Q>(
+    election_parameters: &ElectionParameters,
+    joint_key: &JointElectionPublicKey,
+    ciphertext: &Ciphertext,
+    shares_by_guardian: &BTreeMap<GuardianIndex, DecryptionShare>,
+    quorums: Q,
+) -> Result<(), DecryptionAuditError>
+where
+    Q: IntoIterator<Item = Vec<GuardianIndex>>,
+{
+    let fixed_parameters = &election_parameters.fixed_parameters;
+
+    let mut opt_first: Option<(Vec<GuardianIndex>, FieldElement)> = None;
+    for quorum in quorums {
+        let mut shares = Vec::with_capacity(quorum.len());
+        for &i in &quorum {
+            let Some(share) = shares_by_guardian.get(&i) else {
+                return Err(DecryptionAuditError::MissingShare { i, quorum });
+            };
+            shares.push(share);
+        }
+
+        let m = match CombinedDecryptionShare::combine(election_parameters, shares) {
+            Ok(m) => m,
+            Err(err) => return Err(DecryptionAuditError::ShareCombination { quorum, err }),
+        };
+
+        let plain_text = match VerifiableDecryption::decrypt_plain_text(
+            fixed_parameters,
+            joint_key,
+            ciphertext,
+            &m,
+        ) {
+            Ok(plain_text) => plain_text,
+            Err(err) => return Err(DecryptionAuditError::Decryption { quorum, err }),
+        };
+
+        match &opt_first {
+            None => opt_first = Some((quorum, plain_text)),
+            Some((first_quorum, first_plain_text)) => {
+                if plain_text != *first_plain_text {
+                    return Err(DecryptionAuditError::DivergentQuorum {
+                        quorum,
+                        first_quorum: first_quorum.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
-    use std::iter::zip;
-    use util::{algebra::FieldElement, csprng::Csprng};
+    use std::{collections::BTreeMap, iter::zip};
+    use util::{
+        algebra::{FieldElement, GroupElement},
+        csprng::Csprng,
+    };
 
     use crate::{
-        election_parameters::ElectionParameters,
+        election_parameters::{ElectionParameters, ELECTION_PARAMETERS_SCHEMA_VERSION},
         example_election_manifest,
-        example_election_parameters::example_election_parameters,
+        example_election_parameters::{example_election_parameters, toy_election_parameters},
         fixed_parameters::FixedParameters,
         guardian::GuardianIndex,
         guardian_public_key::GuardianPublicKey,
         guardian_secret_key::GuardianSecretKey,
         guardian_share::{GuardianEncryptedShare, GuardianSecretKeyShare},
+        hash::HValue,
         hashes::Hashes,
         hashes_ext::HashesExt,
-        joint_election_public_key::JointElectionPublicKey,
+        joint_election_public_key::{
+            Ciphertext, JointElectionPublicKey, JOINT_ELECTION_PUBLIC_KEY_SCHEMA_VERSION,
+        },
         standard_parameters::test_parameter_do_not_use_in_production::TOY_PARAMETERS_01,
         varying_parameters::{BallotChaining, VaryingParameters},
         verifiable_decryption::ShareCombinationError,
     };
 
-    use super::{CombinedDecryptionShare, DecryptionProof, DecryptionShare, VerifiableDecryption};
+    use super::{
+        audit_decryption, CombinedDecryptionShare, ComputeDecryptionError, DecryptionAuditError,
+        DecryptionProof, DecryptionProofRangeError, DecryptionShare, DecryptionShareResult,
+        ProofTranscriptError, VerifiableDecryption,
+    };
+    use crate::ballot::{TallyError, CONTEST_OPTION_TALLY_MAX};
 
     fn key_setup(
         csprng: &mut Csprng,
@@ -800,6 +1009,7 @@ mod test {
             ballot_chaining: BallotChaining::Prohibited,
         };
         let election_parameters = ElectionParameters {
+            schema_version: ELECTION_PARAMETERS_SCHEMA_VERSION,
             fixed_parameters,
             varying_parameters,
         };
@@ -880,26 +1090,32 @@ mod test {
         );
     }
 
-    #[test]
-    fn test_decryption_overall() {
-        let mut csprng = Csprng::new(b"test_proof_generation");
-        let election_parameters = example_election_parameters();
+    /// Encrypts `message` under a joint key of freshly generated guardian keys, then decrypts it
+    /// with a proof of correct decryption.
+    fn decrypt_with_proof(
+        csprng: &mut Csprng,
+        election_parameters: &ElectionParameters,
+        message: usize,
+    ) -> (
+        VerifiableDecryption,
+        HashesExt,
+        JointElectionPublicKey,
+        Ciphertext,
+    ) {
         let fixed_parameters = &election_parameters.fixed_parameters;
-
         let field = &fixed_parameters.field;
 
-        let (joint_key, public_keys, key_shares) = key_setup(&mut csprng, &election_parameters);
+        let (joint_key, public_keys, key_shares) = key_setup(csprng, election_parameters);
 
         let hashes = Hashes::compute(
-            &election_parameters,
+            election_parameters,
             &example_election_manifest::example_election_manifest(),
         )
         .unwrap();
 
-        let h_e = HashesExt::compute(&election_parameters, &hashes, &joint_key);
+        let h_e = HashesExt::compute(election_parameters, &hashes, &joint_key);
 
-        let message: usize = 42;
-        let nonce = field.random_field_elem(&mut csprng);
+        let nonce = field.random_field_elem(csprng);
         let ciphertext = joint_key.encrypt_with(fixed_parameters, &nonce, message);
 
         let dec_shares: Vec<_> = key_shares
@@ -907,13 +1123,13 @@ mod test {
             .map(|ks| DecryptionShare::from(fixed_parameters, ks, &ciphertext))
             .collect();
         let combined_dec_share =
-            CombinedDecryptionShare::combine(&election_parameters, &dec_shares).unwrap();
+            CombinedDecryptionShare::combine(election_parameters, &dec_shares).unwrap();
 
         let mut com_shares = vec![];
         let mut com_states = vec![];
         for ks in key_shares.iter() {
             let (share, state) = DecryptionProof::generate_commit_share(
-                &mut csprng,
+                csprng,
                 fixed_parameters,
                 &ciphertext,
                 &ks.i,
@@ -940,7 +1156,7 @@ mod test {
             .collect();
 
         let proof = DecryptionProof::combine_proof(
-            &election_parameters,
+            election_parameters,
             &h_e,
             &ciphertext,
             &dec_shares,
@@ -959,6 +1175,20 @@ mod test {
         )
         .unwrap();
 
+        (decryption, h_e, joint_key, ciphertext)
+    }
+
+    #[test]
+    fn test_decryption_overall() {
+        let mut csprng = Csprng::new(b"test_proof_generation");
+        let election_parameters = example_election_parameters();
+        let fixed_parameters = &election_parameters.fixed_parameters;
+        let field = &fixed_parameters.field;
+
+        let message: usize = 42;
+        let (decryption, h_e, joint_key, ciphertext) =
+            decrypt_with_proof(&mut csprng, &election_parameters, message);
+
         assert_eq!(
             decryption.plain_text,
             FieldElement::from(message, field),
@@ -966,4 +1196,267 @@ mod test {
         );
         assert!(decryption.verify(fixed_parameters, &h_e, &joint_key, &ciphertext))
     }
+
+    #[test]
+    fn test_decryption_proof_challenge_transcript_length() {
+        use crate::standard_parameters::STANDARD_PARAMETERS;
+
+        let mut csprng = Csprng::new(b"test_decryption_proof_challenge_transcript_length");
+        let fixed_parameters = &*TOY_PARAMETERS_01;
+        let group = &fixed_parameters.group;
+
+        let joint_key = JointElectionPublicKey {
+            schema_version: JOINT_ELECTION_PUBLIC_KEY_SCHEMA_VERSION,
+            joint_election_public_key: group.random_group_elem(&mut csprng),
+        };
+        let ciphertext = Ciphertext {
+            alpha: group.random_group_elem(&mut csprng),
+            beta: group.random_group_elem(&mut csprng),
+        };
+        let m = CombinedDecryptionShare(group.random_group_elem(&mut csprng));
+        let b = group.random_group_elem(&mut csprng);
+        let challenge = |a: &GroupElement| {
+            DecryptionProof::challenge(
+                fixed_parameters,
+                &HValue::default(),
+                &joint_key,
+                &ciphertext,
+                a,
+                &b,
+                &m,
+            )
+        };
+
+        assert!(challenge(&group.random_group_elem(&mut csprng)).is_ok());
+
+        // An element of the much larger standard group doesn't fit in the toy group's encoding.
+        let p_len_bytes = group.p_len_bytes();
+        assert_eq!(
+            challenge(&STANDARD_PARAMETERS.group.generator()).unwrap_err(),
+            ProofTranscriptError::UnexpectedProofTranscriptLength {
+                expected: 1 + 6 * p_len_bytes,
+                actual: 1 + 5 * p_len_bytes + STANDARD_PARAMETERS.group.p_len_bytes(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_audit_decryption() {
+        let mut csprng = Csprng::new(b"test_audit_decryption");
+        let election_parameters = toy_election_parameters(4, 2);
+        let n = election_parameters.varying_parameters.n;
+        let k = election_parameters.varying_parameters.k;
+        let fixed_parameters = &election_parameters.fixed_parameters;
+        let field = &fixed_parameters.field;
+        let group = &fixed_parameters.group;
+
+        let (joint_key, _, key_shares) = key_setup(&mut csprng, &election_parameters);
+
+        let nonce = field.random_field_elem(&mut csprng);
+        let ciphertext = joint_key.encrypt_with(fixed_parameters, &nonce, 7);
+
+        let mut shares_by_guardian: BTreeMap<_, _> = key_shares
+            .iter()
+            .map(|ks| {
+                (
+                    ks.i,
+                    DecryptionShare::from(fixed_parameters, ks, &ciphertext),
+                )
+            })
+            .collect();
+
+        audit_decryption(
+            &election_parameters,
+            &joint_key,
+            &ciphertext,
+            &shares_by_guardian,
+            GuardianIndex::quorum_combinations(n, k),
+        )
+        .unwrap();
+
+        // Guardian 3 submits a bad share. The quorums are enumerated in lexicographic order,
+        // so quorum [1, 3] is the first to diverge from quorum [1, 2].
+        let share_3 = shares_by_guardian
+            .get_mut(&GuardianIndex::from_one_based_index(3).unwrap())
+            .unwrap();
+        share_3.m_i = share_3.m_i.mul(&group.generator(), group);
+
+        let err = audit_decryption(
+            &election_parameters,
+            &joint_key,
+            &ciphertext,
+            &shares_by_guardian,
+            GuardianIndex::quorum_combinations(n, k),
+        )
+        .unwrap_err();
+        let quorum_ixs = |quorum: &[GuardianIndex]| {
+            quorum
+                .iter()
+                .map(|i| i.get_one_based_u32())
+                .collect::<Vec<_>>()
+        };
+        assert!(
+            matches!(
+                &err,
+                DecryptionAuditError::DivergentQuorum {
+                    quorum,
+                    first_quorum,
+                } if quorum_ixs(quorum) == [1, 3] && quorum_ixs(first_quorum) == [1, 2]
+            ),
+            "{err}"
+        );
+
+        // Quorums not involving guardian 3 still agree.
+        audit_decryption(
+            &election_parameters,
+            &joint_key,
+            &ciphertext,
+            &shares_by_guardian,
+            GuardianIndex::quorum_combinations(n, k)
+                .filter(|quorum| quorum.iter().all(|i| i.get_one_based_u32() != 3)),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_decryption_proof_challenge_reduced() {
+        // With the toy parameters, `q` is much smaller than the hash values, so the challenge
+        // is reduced modulo `q` in practically every case.
+        let mut csprng = Csprng::new(b"test_decryption_proof_challenge_reduced");
+        let election_parameters = toy_election_parameters(3, 3);
+        let fixed_parameters = &election_parameters.fixed_parameters;
+        let field = &fixed_parameters.field;
+
+        let message: usize = 5;
+        let (mut decryption, h_e, joint_key, ciphertext) =
+            decrypt_with_proof(&mut csprng, &election_parameters, message);
+
+        assert_eq!(decryption.plain_text, FieldElement::from(message, field));
+        assert!(decryption.proof.challenge.is_valid(field));
+        assert!(decryption.verify(fixed_parameters, &h_e, &joint_key, &ciphertext));
+
+        // The same challenge, not reduced modulo `q`, does not validate. A `FieldElement` is
+        // always reduced, so the unreduced challenge is deserialized.
+        let unreduced = decryption.proof.challenge.value() + field.order();
+        let unreduced = util::base16::to_string_uppercase_hex_bits(&unreduced, 256).unwrap();
+        decryption.proof.challenge =
+            serde_json::from_value(serde_json::Value::String(unreduced)).unwrap();
+        assert!(!decryption.proof.challenge.is_valid(field));
+        assert!(!decryption.verify(fixed_parameters, &h_e, &joint_key, &ciphertext));
+    }
+
+    #[test]
+    fn test_decryption_share_result_validate() {
+        let mut csprng = Csprng::new(b"test_decryption_share_result_validate");
+        let fixed_parameters: FixedParameters = (*TOY_PARAMETERS_01).clone();
+        let field = &fixed_parameters.field;
+        let group = &fixed_parameters.group;
+
+        let ciphertext = Ciphertext {
+            alpha: group.g_exp(&field.random_field_elem(&mut csprng)),
+            beta: group.g_exp(&field.random_field_elem(&mut csprng)),
+        };
+        let i_1 = GuardianIndex::from_one_based_index(1).unwrap();
+        let i_2 = GuardianIndex::from_one_based_index(2).unwrap();
+        let share = DecryptionShare {
+            i: i_1,
+            m_i: group.g_exp(&field.random_field_elem(&mut csprng)),
+        };
+        let mut proof_commit = |i| {
+            DecryptionProof::generate_commit_share(&mut csprng, &fixed_parameters, &ciphertext, &i)
+                .0
+        };
+
+        let result = DecryptionShareResult {
+            share: share.clone(),
+            proof_commit: proof_commit(i_1),
+        };
+        result.validate().unwrap();
+
+        let result = DecryptionShareResult {
+            share,
+            proof_commit: proof_commit(i_2),
+        };
+        let err = result.validate().unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ComputeDecryptionError::ShareCommitIndexMismatch {
+                    share_i,
+                    proof_commit_i
+                } if share_i == i_1 && proof_commit_i == i_2
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_decryption_proof_from_stdioread_validated() {
+        let fixed_parameters = &*TOY_PARAMETERS_01;
+        let field = &fixed_parameters.field;
+        let q_len_hex = 2 * 32;
+        let to_json = |challenge: &str, response: &str| {
+            serde_json::json!({ "challenge": challenge, "response": response }).to_string()
+        };
+
+        let one = format!("{:0q_len_hex$X}", 1);
+        let q = format!("{:0q_len_hex$X}", field.order());
+        let q_minus_one = format!("{:0q_len_hex$X}", field.order() - 1u8);
+
+        let proof = DecryptionProof::from_stdioread_validated(
+            &mut to_json(&one, &q_minus_one).as_bytes(),
+            fixed_parameters,
+        )
+        .unwrap();
+        assert_eq!(proof.challenge, FieldElement::from(1u8, field));
+
+        // A response equal to q is rejected on load.
+        let err = DecryptionProof::from_stdioread_validated(
+            &mut to_json(&one, &q).as_bytes(),
+            fixed_parameters,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DecryptionProofRangeError>(),
+            Some(&DecryptionProofRangeError::ResponseNotInField)
+        );
+
+        // As is a challenge equal to q.
+        let err = DecryptionProof::from_stdioread_validated(
+            &mut to_json(&q, &one).as_bytes(),
+            fixed_parameters,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<DecryptionProofRangeError>(),
+            Some(&DecryptionProofRangeError::ChallengeNotInField)
+        );
+    }
+
+    #[test]
+    fn test_recovered_count() {
+        let verifiable_decryption = |plain_text| VerifiableDecryption {
+            plain_text,
+            proof: DecryptionProof {
+                challenge: FieldElement::from(0u8, &TOY_PARAMETERS_01.field),
+                response: FieldElement::from(0u8, &TOY_PARAMETERS_01.field),
+            },
+        };
+
+        let field = &TOY_PARAMETERS_01.field;
+        let decryption = verifiable_decryption(FieldElement::from(42u8, field));
+        assert_eq!(decryption.recovered_count(field), Ok(42));
+
+        // A count of `q - 1` in the standard field is implausibly large.
+        let field = &crate::standard_parameters::STANDARD_PARAMETERS.field;
+        let q_minus_one = field.order() - 1u8;
+        let decryption = verifiable_decryption(FieldElement::from(q_minus_one.clone(), field));
+        assert_eq!(
+            decryption.recovered_count(field),
+            Err(TallyError::TallyOutOfRange {
+                value: q_minus_one.to_string(),
+                max: CONTEST_OPTION_TALLY_MAX,
+            })
+        );
+    }
 }
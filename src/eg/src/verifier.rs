@@ -0,0 +1,766 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the [`Verifier`], which checks the published artifacts of an election
+//! against its [`PreVotingData`]. The checks follow the verification steps of Section `6`
+//! of the ElectionGuard specification `2.0.0`.
+
+use std::collections::BTreeMap;
+
+use anyhow::{ensure, Context, Result};
+use serde::Serialize;
+use thiserror::Error;
+use util::csprng::Csprng;
+
+use crate::{
+    ballot::{BallotEncrypted, BallotState, BallotTallyBuilder, EncryptedTally},
+    election_manifest::{ContestIndex, ContestOptionIndex},
+    election_record::PreVotingData,
+    guardian_public_key::GuardianPublicKey,
+    hashes::Hashes,
+    hashes_ext::HashesExt,
+    joint_election_public_key::JointElectionPublicKey,
+    verifiable_decryption::{DecryptionProofRangeError, VerifiableDecryption},
+};
+
+/// A mismatch between a published [`EncryptedTally`] and the cast ballots, as found by
+/// [`Verifier::verify_tally`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TallyVerificationError {
+    /// A cast ballot is incompatible with the election manifest
+    #[error("Ballot {index} is cast, but is incompatible with the election manifest.")]
+    IncompatibleBallot { index: usize },
+
+    /// A contest of the cast ballots is missing from the published tally
+    #[error("The published tally has no entry for contest {contest_ix}.")]
+    ContestMissing { contest_ix: ContestIndex },
+
+    /// The published tally has an entry for a contest on none of the cast ballots
+    #[error(
+        "The published tally has an entry for contest {contest_ix}, which is on no cast ballot."
+    )]
+    UnexpectedContest { contest_ix: ContestIndex },
+
+    /// The published tally of a contest has the wrong number of ciphertexts
+    #[error(
+        "The published tally for contest {contest_ix} has {actual} ciphertexts, but {expected} were expected."
+    )]
+    CiphertextCountMismatch {
+        contest_ix: ContestIndex,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A ciphertext of the published tally is not the aggregation of the cast ballots
+    #[error("The published tally for contest {contest_ix}, option {option_ix} is not the aggregation of the cast ballots.")]
+    CiphertextMismatch {
+        contest_ix: ContestIndex,
+        option_ix: ContestOptionIndex,
+    },
+}
+
+/// A published decryption of an [`EncryptedTally`] which fails verification, as found by
+/// [`Verifier::verify_decryption`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum DecryptionVerificationError {
+    /// A contest of the encrypted tally has no decryption
+    #[error("Contest {contest_ix} of the encrypted tally has no decryption.")]
+    ContestMissing { contest_ix: ContestIndex },
+
+    /// A decryption is published for a contest which is not in the encrypted tally
+    #[error(
+        "A decryption is published for contest {contest_ix}, which is not in the encrypted tally."
+    )]
+    UnexpectedContest { contest_ix: ContestIndex },
+
+    /// The number of decryptions of a contest does not match its encrypted tally
+    #[error(
+        "Contest {contest_ix} has {actual} decryptions, but its encrypted tally has {expected} ciphertexts."
+    )]
+    DecryptionCountMismatch {
+        contest_ix: ContestIndex,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// The proof of correct decryption of a ciphertext does not verify
+    #[error(
+        "The proof of correct decryption for contest {contest_ix}, option {option_ix} is invalid."
+    )]
+    InvalidProof {
+        contest_ix: ContestIndex,
+        option_ix: ContestOptionIndex,
+    },
+
+    /// The proof of correct decryption of a ciphertext has a component out of range
+    #[error(
+        "The proof of correct decryption for contest {contest_ix}, option {option_ix} is malformed."
+    )]
+    ProofOutOfRange {
+        contest_ix: ContestIndex,
+        option_ix: ContestOptionIndex,
+        #[source]
+        source: DecryptionProofRangeError,
+    },
+}
+
+/// The outcome of one step of a [`VerificationChecklist`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerificationStep {
+    /// The number of the verification step in the specification.
+    pub number: u8,
+
+    /// Description of the verification step.
+    pub description: &'static str,
+
+    /// Whether the step passed.
+    pub passed: bool,
+
+    /// The reason the step failed, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opt_error: Option<String>,
+}
+
+/// The outcome of every step of the verification of an election, as produced by
+/// [`Verifier::full_report`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VerificationChecklist {
+    /// The steps performed, in order of their number.
+    pub steps: Vec<VerificationStep>,
+}
+
+impl VerificationChecklist {
+    /// Whether every step passed.
+    pub fn all_passed(&self) -> bool {
+        self.steps.iter().all(|step| step.passed)
+    }
+
+    /// The steps which failed.
+    pub fn failed_steps(&self) -> impl Iterator<Item = &VerificationStep> {
+        self.steps.iter().filter(|step| !step.passed)
+    }
+
+    fn record<E: std::fmt::Display>(
+        &mut self,
+        number: u8,
+        description: &'static str,
+        result: Result<(), E>,
+    ) {
+        self.steps.push(VerificationStep {
+            number,
+            description,
+            passed: result.is_ok(),
+            opt_error: result.err().map(|err| format!("{err:#}")),
+        });
+    }
+}
+
+/// Verifies the published artifacts of an election.
+pub struct Verifier<'a> {
+    pre_voting_data: &'a PreVotingData,
+}
+
+impl<'a> Verifier<'a> {
+    /// Creates a [`Verifier`] for the election described by the given [`PreVotingData`].
+    pub fn new(pre_voting_data: &'a PreVotingData) -> Self {
+        Self { pre_voting_data }
+    }
+
+    /// The [`PreVotingData`] of the election.
+    pub fn pre_voting_data(&self) -> &PreVotingData {
+        self.pre_voting_data
+    }
+
+    /// Verifies that a published [`EncryptedTally`] is the homomorphic aggregation of the cast
+    /// ballots among `ballots` (Verification 8). Ballots in any other state are not tallied.
+    ///
+    /// The tally is recomputed and compared ciphertext by ciphertext, in order of contest and
+    /// option. Returns the first mismatch found. Ballot indices in the returned error are
+    /// zero-based positions in `ballots`.
+    pub fn verify_tally(
+        &self,
+        ballots: &[BallotEncrypted],
+        published_tally: &EncryptedTally,
+    ) -> Result<(), TallyVerificationError> {
+        let mut builder = BallotTallyBuilder::new(
+            self.pre_voting_data.manifest(),
+            self.pre_voting_data.parameters(),
+        );
+        for (index, ballot) in ballots.iter().enumerate() {
+            if ballot.state == BallotState::Cast && !builder.update(ballot.as_scaled_unit()) {
+                return Err(TallyVerificationError::IncompatibleBallot { index });
+            }
+        }
+        let tally = builder.finalize();
+
+        for (&contest_ix, expected) in &tally {
+            let Some(published) = published_tally.0.get(&contest_ix) else {
+                return Err(TallyVerificationError::ContestMissing { contest_ix });
+            };
+            if published.len() != expected.len() {
+                return Err(TallyVerificationError::CiphertextCountMismatch {
+                    contest_ix,
+                    expected: expected.len(),
+                    actual: published.len(),
+                });
+            }
+            let mismatch = ContestOptionIndex::iter_range_inclusive(
+                ContestOptionIndex::MIN,
+                ContestOptionIndex::MAX,
+            )
+            .zip(expected.iter().zip(published))
+            .find(|(_, (expected, published))| expected != published);
+            if let Some((option_ix, _)) = mismatch {
+                return Err(TallyVerificationError::CiphertextMismatch {
+                    contest_ix,
+                    option_ix,
+                });
+            }
+        }
+
+        if let Some(&contest_ix) = published_tally
+            .0
+            .keys()
+            .find(|contest_ix| !tally.contains_key(contest_ix))
+        {
+            return Err(TallyVerificationError::UnexpectedContest { contest_ix });
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that the published decryptions are correct decryptions of the
+    /// [`EncryptedTally`] (Verification 9). For every option of every contest in the tally,
+    /// the proof of correct decryption is checked to be in range, and against the tally
+    /// ciphertext and the joint election public key.
+    ///
+    /// Returns the first failure found, in order of contest and option.
+    pub fn verify_decryption(
+        &self,
+        encrypted_tally: &EncryptedTally,
+        verifiable_decryptions: &BTreeMap<ContestIndex, Vec<VerifiableDecryption>>,
+    ) -> Result<(), DecryptionVerificationError> {
+        let pre_voting_data = self.pre_voting_data;
+        let fixed_parameters = &pre_voting_data.parameters().fixed_parameters;
+
+        for (&contest_ix, ciphertexts) in &encrypted_tally.0 {
+            let Some(decryptions) = verifiable_decryptions.get(&contest_ix) else {
+                return Err(DecryptionVerificationError::ContestMissing { contest_ix });
+            };
+            if decryptions.len() != ciphertexts.len() {
+                return Err(DecryptionVerificationError::DecryptionCountMismatch {
+                    contest_ix,
+                    expected: ciphertexts.len(),
+                    actual: decryptions.len(),
+                });
+            }
+            for (option_ix, (ciphertext, decryption)) in ContestOptionIndex::iter_range_inclusive(
+                ContestOptionIndex::MIN,
+                ContestOptionIndex::MAX,
+            )
+            .zip(ciphertexts.iter().zip(decryptions))
+            {
+                // The published proofs are rejected as malformed before checking them.
+                decryption
+                    .proof
+                    .validate_ranges(&fixed_parameters.field)
+                    .map_err(|source| DecryptionVerificationError::ProofOutOfRange {
+                        contest_ix,
+                        option_ix,
+                        source,
+                    })?;
+
+                if !decryption.verify(
+                    fixed_parameters,
+                    &pre_voting_data.hashes_ext,
+                    pre_voting_data.joint_public_key(),
+                    ciphertext,
+                ) {
+                    return Err(DecryptionVerificationError::InvalidProof {
+                        contest_ix,
+                        option_ix,
+                    });
+                }
+            }
+        }
+
+        if let Some(&contest_ix) = verifiable_decryptions
+            .keys()
+            .find(|contest_ix| !encrypted_tally.0.contains_key(contest_ix))
+        {
+            return Err(DecryptionVerificationError::UnexpectedContest { contest_ix });
+        }
+
+        Ok(())
+    }
+
+    /// Runs every verification step on the published artifacts of an election and reports the
+    /// outcome of each, numbered as in the specification. Unlike the individual checks, this
+    /// continues past failures, so that all of them are reported.
+    ///
+    /// The steps are
+    /// - 1 - the election parameters are valid, see [`ElectionParameters::validate`]
+    /// - 2 - the guardian public keys are valid, including their proofs of knowledge
+    /// - 3 - the joint election public key is valid and formed from the guardian public keys
+    /// - 4 - the hashes, up to the extended base hash, match those computed from the parameters,
+    ///   manifest and joint election public key
+    /// - 5 - every ballot passes [`BallotEncrypted::verify`]
+    /// - 8 - the encrypted tally is the aggregation of the cast ballots, see [`Self::verify_tally`]
+    /// - 9 - the decryptions of the tally are correct, see [`Self::verify_decryption`]
+    ///
+    /// [`BallotEncrypted::verify`] checks the proofs of Verification 5 (correctness of selection
+    /// encryptions) together with those of Verification 6 (adherence to vote limits), so a
+    /// failure of either is reported as step 5. The other steps of the specification, such as
+    /// Verification 7 (validation of confirmation codes), are not run.
+    ///
+    /// [`ElectionParameters::validate`]: crate::election_parameters::ElectionParameters::validate
+    pub fn full_report(
+        &self,
+        csprng: &mut Csprng,
+        guardian_public_keys: &[GuardianPublicKey],
+        ballots: &[BallotEncrypted],
+        encrypted_tally: &EncryptedTally,
+        verifiable_decryptions: &BTreeMap<ContestIndex, Vec<VerifiableDecryption>>,
+    ) -> VerificationChecklist {
+        let parameters = self.pre_voting_data.parameters();
+
+        let mut checklist = VerificationChecklist::default();
+        checklist.record(1, "Parameter validation", parameters.validate(csprng));
+        checklist.record(
+            2,
+            "Guardian public-key validation",
+            self.verify_guardian_public_keys(guardian_public_keys),
+        );
+        checklist.record(
+            3,
+            "Election public-key validation",
+            self.verify_joint_public_key(guardian_public_keys),
+        );
+        checklist.record(4, "Extended base hash validation", self.verify_hashes());
+        checklist.record(5, "Ballot validity", self.verify_ballots(ballots));
+        checklist.record(
+            8,
+            "Correctness of ballot aggregation",
+            self.verify_tally(ballots, encrypted_tally),
+        );
+        checklist.record(
+            9,
+            "Correctness of tally decryptions",
+            self.verify_decryption(encrypted_tally, verifiable_decryptions),
+        );
+        checklist
+    }
+
+    fn verify_guardian_public_keys(
+        &self,
+        guardian_public_keys: &[GuardianPublicKey],
+    ) -> Result<()> {
+        let parameters = self.pre_voting_data.parameters();
+        let n = parameters.varying_parameters.n.get_one_based_usize();
+        ensure!(
+            guardian_public_keys.len() == n,
+            "Expected {n} guardian public keys, but got {}",
+            guardian_public_keys.len()
+        );
+        for guardian_public_key in guardian_public_keys {
+            guardian_public_key
+                .validate(parameters)
+                .with_context(|| format!("Guardian public key {}", guardian_public_key.i))?;
+        }
+        Ok(())
+    }
+
+    fn verify_joint_public_key(&self, guardian_public_keys: &[GuardianPublicKey]) -> Result<()> {
+        let parameters = self.pre_voting_data.parameters();
+        let joint_public_key = self.pre_voting_data.joint_public_key();
+        joint_public_key.validate(parameters)?;
+        ensure!(
+            JointElectionPublicKey::compute(parameters, guardian_public_keys)? == *joint_public_key,
+            "Joint election public key does not match the guardian public keys"
+        );
+        Ok(())
+    }
+
+    fn verify_hashes(&self) -> Result<()> {
+        let pre_voting_data = self.pre_voting_data;
+        let parameters = pre_voting_data.parameters();
+        let hashes = Hashes::compute(parameters, pre_voting_data.manifest())?;
+        ensure!(
+            *pre_voting_data.hashes() == hashes,
+            "Hashes H_P, H_M, H_B do not match the election parameters and manifest"
+        );
+        let hashes_ext =
+            HashesExt::compute(parameters, &hashes, pre_voting_data.joint_public_key());
+        ensure!(
+            *pre_voting_data.extended_base_hash() == hashes_ext.h_e,
+            "Extended base hash H_E does not match the joint election public key"
+        );
+        Ok(())
+    }
+
+    fn verify_ballots(&self, ballots: &[BallotEncrypted]) -> Result<()> {
+        for (index, ballot) in ballots.iter().enumerate() {
+            ensure!(
+                ballot.verify(self.pre_voting_data),
+                "Ballot {index} (confirmation code {}) is invalid",
+                ballot.confirmation_code
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use util::algebra::FieldElement;
+
+    use super::*;
+    use crate::{
+        ballot::tally_ballots,
+        ballot_style::BallotStyleIndex,
+        contest_selection::ContestSelection,
+        device::Device,
+        example_election_manifest::example_election_manifest,
+        example_election_parameters::toy_election_parameters,
+        joint_election_public_key::Ciphertext,
+        key_ceremony::KeyCeremony,
+        verifiable_decryption::{
+            CombinedDecryptionShare, DecryptionProof, DecryptionShare, DecryptionShareResult,
+        },
+    };
+
+    /// An election with toy parameters, its key ceremony, and some encrypted ballots.
+    struct TestElection {
+        key_ceremony: KeyCeremony,
+        device: Device,
+        ballots: Vec<BallotEncrypted>,
+    }
+
+    impl TestElection {
+        fn new(csprng: &mut Csprng) -> Self {
+            let election_parameters = toy_election_parameters(3, 2);
+            let key_ceremony = KeyCeremony::run(csprng, &election_parameters, None).unwrap();
+            let pre_voting_data = PreVotingData::compute(
+                example_election_manifest(),
+                election_parameters,
+                &key_ceremony.guardian_public_keys,
+            )
+            .unwrap();
+            let device = Device::new("Some encryption device", pre_voting_data);
+
+            // Ballots of both ballot styles, one of which is not cast.
+            let ballots = [
+                (1, BallotState::Cast),
+                (2, BallotState::Cast),
+                (2, BallotState::Challenged),
+            ]
+            .into_iter()
+            .map(|(ballot_style_ix, state)| {
+                let ballot_style_index =
+                    BallotStyleIndex::from_one_based_index(ballot_style_ix).unwrap();
+                let manifest = device.header.manifest();
+                let selections: BTreeMap<_, _> = manifest
+                    .ballot_styles
+                    .get(ballot_style_index)
+                    .unwrap()
+                    .contests
+                    .iter()
+                    .map(|&contest_ix| {
+                        let contest = manifest.contests.get(contest_ix).unwrap();
+                        let selection = ContestSelection::new_pick_random(
+                            csprng,
+                            contest.selection_limit,
+                            contest.options.len(),
+                        );
+                        (contest_ix, selection)
+                    })
+                    .collect();
+                let mut ballot = BallotEncrypted::new_from_selections(
+                    ballot_style_index,
+                    &device,
+                    "2023-05-02",
+                    csprng,
+                    &[0, 1, 2, 3, 4],
+                    &selections,
+                )
+                .unwrap();
+                ballot.state = state;
+                ballot
+            })
+            .collect();
+
+            Self {
+                key_ceremony,
+                device,
+                ballots,
+            }
+        }
+
+        /// The encrypted tally of the cast ballots.
+        fn encrypted_tally(&self) -> EncryptedTally {
+            let header = &self.device.header;
+            let cast_ballots = self
+                .ballots
+                .iter()
+                .filter(|ballot| ballot.state == BallotState::Cast)
+                .map(BallotEncrypted::as_scaled_unit);
+            tally_ballots(cast_ballots, header.manifest(), header.parameters())
+                .unwrap()
+                .into()
+        }
+
+        /// Decrypts a ciphertext with the secret key shares of all the guardians.
+        fn decrypt(&self, csprng: &mut Csprng, ciphertext: &Ciphertext) -> VerifiableDecryption {
+            let header = &self.device.header;
+            let fixed_parameters = &header.parameters().fixed_parameters;
+            let key_shares = &self.key_ceremony.guardian_secret_key_shares;
+
+            // Each guardian posts its decryption share together with a commitment.
+            let (share_results, com_states): (Vec<_>, Vec<_>) = key_shares
+                .iter()
+                .map(|ks| {
+                    let share = DecryptionShare::from(fixed_parameters, ks, ciphertext);
+                    let (proof_commit, state) = DecryptionProof::generate_commit_share(
+                        csprng,
+                        fixed_parameters,
+                        ciphertext,
+                        &ks.i,
+                    );
+                    (
+                        DecryptionShareResult {
+                            share,
+                            proof_commit,
+                        },
+                        state,
+                    )
+                })
+                .unzip();
+
+            // Each guardian answers the joint challenge.
+            let combined_dec_share = CombinedDecryptionShare::combine(
+                header.parameters(),
+                share_results.iter().map(|r| &r.share),
+            )
+            .unwrap();
+            let com_shares: Vec<_> = share_results
+                .iter()
+                .map(|r| r.proof_commit.clone())
+                .collect();
+            let rsp_shares: Vec<_> = com_states
+                .iter()
+                .zip(key_shares)
+                .map(|(state, key_share)| {
+                    DecryptionProof::generate_response_share(
+                        fixed_parameters,
+                        &header.hashes_ext,
+                        header.joint_public_key(),
+                        ciphertext,
+                        &combined_dec_share,
+                        &com_shares,
+                        state,
+                        key_share,
+                    )
+                    .unwrap()
+                })
+                .collect();
+
+            VerifiableDecryption::compute(
+                header.manifest(),
+                header.parameters(),
+                &self.key_ceremony.guardian_public_keys,
+                ciphertext,
+                &share_results,
+                &rsp_shares,
+            )
+            .unwrap()
+        }
+
+        /// Decrypts every ciphertext of an encrypted tally.
+        fn decrypt_tally(
+            &self,
+            csprng: &mut Csprng,
+            encrypted_tally: &EncryptedTally,
+        ) -> BTreeMap<ContestIndex, Vec<VerifiableDecryption>> {
+            encrypted_tally
+                .0
+                .iter()
+                .map(|(&contest_ix, ciphertexts)| {
+                    let decryptions = ciphertexts
+                        .iter()
+                        .map(|ciphertext| self.decrypt(csprng, ciphertext))
+                        .collect();
+                    (contest_ix, decryptions)
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_verify_tally() {
+        let mut csprng = Csprng::new(b"test_verify_tally");
+        let election = TestElection::new(&mut csprng);
+        let verifier = Verifier::new(&election.device.header);
+
+        let mut tally = election.encrypted_tally();
+        assert_eq!(verifier.verify_tally(&election.ballots, &tally), Ok(()));
+
+        // Ballot style 1 has contest 10, ballot style 2 has contest 11.
+        let contest_ix = ContestIndex::from_one_based_index(10).unwrap();
+        assert!(tally.0.contains_key(&contest_ix));
+
+        // Altering a single ciphertext.
+        let option_ix = ContestOptionIndex::from_one_based_index(2).unwrap();
+        let ct = &mut tally.0.get_mut(&contest_ix).unwrap()[1];
+        let group = &verifier
+            .pre_voting_data()
+            .parameters()
+            .fixed_parameters
+            .group;
+        ct.alpha = ct.alpha.mul(&group.generator(), group);
+        assert_eq!(
+            verifier.verify_tally(&election.ballots, &tally),
+            Err(TallyVerificationError::CiphertextMismatch {
+                contest_ix,
+                option_ix
+            })
+        );
+
+        // Dropping a contest.
+        tally.0.remove(&contest_ix);
+        assert_eq!(
+            verifier.verify_tally(&election.ballots, &tally),
+            Err(TallyVerificationError::ContestMissing { contest_ix })
+        );
+
+        // Counting the challenged ballot changes the tally.
+        let tally = election.encrypted_tally();
+        let mut ballots = election.ballots;
+        ballots[2].state = BallotState::Cast;
+        assert_eq!(
+            verifier.verify_tally(&ballots, &tally),
+            Err(TallyVerificationError::CiphertextMismatch {
+                contest_ix: ContestIndex::from_one_based_index(1).unwrap(),
+                option_ix: ContestOptionIndex::from_one_based_index(1).unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_decryption() {
+        let mut csprng = Csprng::new(b"test_verify_decryption");
+        let election = TestElection::new(&mut csprng);
+        let verifier = Verifier::new(&election.device.header);
+        let field = &verifier
+            .pre_voting_data()
+            .parameters()
+            .fixed_parameters
+            .field;
+
+        let encrypted_tally = election.encrypted_tally();
+        let mut decryptions = election.decrypt_tally(&mut csprng, &encrypted_tally);
+        assert_eq!(
+            verifier.verify_decryption(&encrypted_tally, &decryptions),
+            Ok(())
+        );
+
+        // Forging the proof of a decryption.
+        let contest_ix = ContestIndex::from_one_based_index(3).unwrap();
+        let option_ix = ContestOptionIndex::from_one_based_index(2).unwrap();
+        let proof = &mut decryptions.get_mut(&contest_ix).unwrap()[1].proof;
+        proof.response = proof.response.add(&FieldElement::from(1u8, field), field);
+        assert_eq!(
+            verifier.verify_decryption(&encrypted_tally, &decryptions),
+            Err(DecryptionVerificationError::InvalidProof {
+                contest_ix,
+                option_ix
+            })
+        );
+
+        // Publishing a proof whose response is `q`, which would be reduced to zero when used.
+        let proof = &mut decryptions.get_mut(&contest_ix).unwrap()[1].proof;
+        let mut proof_json = serde_json::to_value(&*proof).unwrap();
+        proof_json["response"] = serde_json::to_value(field).unwrap()["q"].clone();
+        *proof = serde_json::from_value(proof_json).unwrap();
+        assert_eq!(
+            verifier.verify_decryption(&encrypted_tally, &decryptions),
+            Err(DecryptionVerificationError::ProofOutOfRange {
+                contest_ix,
+                option_ix,
+                source: DecryptionProofRangeError::ResponseNotInField,
+            })
+        );
+
+        // Dropping the decryptions of a contest.
+        decryptions.remove(&contest_ix);
+        assert_eq!(
+            verifier.verify_decryption(&encrypted_tally, &decryptions),
+            Err(DecryptionVerificationError::ContestMissing { contest_ix })
+        );
+    }
+
+    #[test]
+    fn test_full_report() {
+        let mut csprng = Csprng::new(b"test_full_report");
+        let election = TestElection::new(&mut csprng);
+        let verifier = Verifier::new(&election.device.header);
+        let guardian_public_keys = &election.key_ceremony.guardian_public_keys;
+
+        let mut encrypted_tally = election.encrypted_tally();
+        let decryptions = election.decrypt_tally(&mut csprng, &encrypted_tally);
+
+        let checklist = verifier.full_report(
+            &mut csprng,
+            guardian_public_keys,
+            &election.ballots,
+            &encrypted_tally,
+            &decryptions,
+        );
+        assert!(checklist.all_passed(), "{checklist:#?}");
+        assert_eq!(
+            checklist
+                .steps
+                .iter()
+                .map(|step| step.number)
+                .collect::<Vec<_>>(),
+            [1, 2, 3, 4, 5, 8, 9]
+        );
+
+        // Altering the encrypted tally fails both the aggregation and the decryption, but
+        // the other steps are still performed.
+        let ct = &mut encrypted_tally
+            .0
+            .get_mut(&ContestIndex::from_one_based_index(1).unwrap())
+            .unwrap()[0];
+        let group = &verifier
+            .pre_voting_data()
+            .parameters()
+            .fixed_parameters
+            .group;
+        ct.beta = ct.beta.mul(&group.generator(), group);
+
+        let checklist = verifier.full_report(
+            &mut csprng,
+            guardian_public_keys,
+            &election.ballots,
+            &encrypted_tally,
+            &decryptions,
+        );
+        assert_eq!(checklist.steps.len(), 7);
+        assert_eq!(
+            checklist
+                .failed_steps()
+                .map(|step| step.number)
+                .collect::<Vec<_>>(),
+            [8, 9]
+        );
+        assert!(checklist.failed_steps().all(|step| step
+            .opt_error
+            .as_ref()
+            .is_some_and(|err| err.contains("contest 1"))));
+    }
+}
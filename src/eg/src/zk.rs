@@ -10,13 +10,14 @@
 
 use serde::{Deserialize, Serialize};
 use util::{
-    algebra::{FieldElement, GroupElement, ScalarField},
+    algebra::{FieldElement, Group, GroupElement, ScalarField},
     csprng::Csprng,
 };
 
 use crate::{
+    ballot_encryption_precompute::BallotEncryptionPrecompute,
     election_record::PreVotingData,
-    hash::eg_h,
+    hash::{eg_h, HValue},
     index::Index,
     joint_election_public_key::{Ciphertext, Nonce},
     vec1::HasIndexTypeMarker,
@@ -39,13 +40,22 @@ pub struct ProofRange(Vec<ProofRangeSingle>);
 
 impl HasIndexTypeMarker for ProofRange {}
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Serialize)]
 pub enum ProofRangeError {
     /// Bla bla
     #[error(
         "It must be the case that 0 ≤ small_l ≤ big_l (here small_l={small_l} and big_l={big_l})."
     )]
     RangeNotSatisfied { small_l: usize, big_l: usize },
+
+    /// The encrypted number is required to be exactly one.
+    #[error("The encrypted number must be exactly 1 (here small_l={small_l}).")]
+    NotExactlyOne { small_l: usize },
+
+    /// The joint election public key has no inverse, so one can't be subtracted from an
+    /// encrypted number.
+    #[error("The joint election public key has no inverse.")]
+    JointPublicKeyNotInvertible,
 }
 
 impl ProofRange {
@@ -102,12 +112,28 @@ impl ProofRange {
         nonce: &Nonce,
         small_l: usize,
         big_l: usize,
+    ) -> Result<Self, ProofRangeError> {
+        Self::new_with_opt_precompute(pvd, csprng, ct, nonce, small_l, big_l, None)
+    }
+
+    /// Same as [`ProofRange::new`], but computes the commitments using the fixed-base tables of
+    /// the [`BallotEncryptionPrecompute`], if any. The proof is the same either way.
+    pub(crate) fn new_with_opt_precompute(
+        pvd: &PreVotingData,
+        csprng: &mut Csprng,
+        ct: &Ciphertext,
+        nonce: &Nonce,
+        small_l: usize,
+        big_l: usize,
+        opt_precompute: Option<&BallotEncryptionPrecompute>,
     ) -> Result<Self, ProofRangeError> {
         if small_l > big_l {
             return Err(ProofRangeError::RangeNotSatisfied { small_l, big_l });
         }
-        let field = &pvd.parameters.fixed_parameters.field;
-        let group = &pvd.parameters.fixed_parameters.group;
+        let fixed_parameters = &pvd.parameters.fixed_parameters;
+        let field = &fixed_parameters.field;
+        let group = &fixed_parameters.group;
+        let k = &pvd.public_key.joint_election_public_key;
 
         // Compute commit message and simulated challenges
         let u = (0..big_l + 1)
@@ -117,7 +143,10 @@ impl ProofRange {
             .map(|_| field.random_field_elem(csprng))
             .collect::<Vec<FieldElement>>();
         let a = (0..big_l + 1)
-            .map(|j| group.g_exp(&u[j]))
+            .map(|j| match opt_precompute {
+                Some(precompute) => precompute.g_exp(&u[j], fixed_parameters),
+                None => group.g_exp(&u[j]),
+            })
             .collect::<Vec<GroupElement>>();
         let l_scalar = FieldElement::from(small_l, field);
         let mut t = u.clone();
@@ -129,7 +158,10 @@ impl ProofRange {
             }
         }
         let b = (0..big_l + 1)
-            .map(|j| pvd.public_key.joint_election_public_key.exp(&t[j], group))
+            .map(|j| match opt_precompute {
+                Some(precompute) => precompute.k_exp(&t[j], fixed_parameters),
+                None => k.exp(&t[j], group),
+            })
             .collect::<Vec<GroupElement>>();
 
         // Compute real challenge c_{small_l}
@@ -219,6 +251,63 @@ impl ProofRange {
     }
 }
 
+/// Builds the byte sequence hashed to compute the challenge of a proof.
+///
+/// A transcript starts with a domain separation byte, followed by the big-endian encodings of
+/// the proof's inputs in order. Group elements are left-padded to the byte length of the group
+/// modulus (cf. Section 5.1.1).
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptBuilder(Vec<u8>);
+
+impl TranscriptBuilder {
+    /// Creates a new, empty transcript.
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Appends a byte, e.g., the domain separation byte.
+    pub fn push_u8(mut self, b: u8) -> Self {
+        self.0.push(b);
+        self
+    }
+
+    /// Appends an [`Index`] as 4 big-endian bytes.
+    pub fn push_index<T>(mut self, ix: Index<T>) -> Self {
+        self.0
+            .extend_from_slice(&ix.get_one_based_u32().to_be_bytes());
+        self
+    }
+
+    /// Appends a group element, left-padded to the byte length of the group modulus.
+    pub fn push_group_element(mut self, elem: &GroupElement, group: &Group) -> Self {
+        self.0
+            .extend_from_slice(elem.to_be_bytes_left_pad(group).as_slice());
+        self
+    }
+
+    /// Returns the length of the transcript, in bytes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the transcript is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the bytes of the transcript.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Hashes the transcript with the extended base hash `h_e`, returning the challenge reduced
+    /// modulo `q` (cf. Section 5.4).
+    pub fn finish(self, h_e: &HValue, field: &ScalarField) -> FieldElement {
+        let c = eg_h(h_e, &self.0);
+        FieldElement::from_bytes_be(c.0.as_slice(), field)
+    }
+}
+
 /*
 #[derive(Debug, Clone)]
 pub struct ProofGuardian {
@@ -386,3 +475,50 @@ impl ProofGuardian {
     }
 }
 */
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::{
+        election_manifest::ContestIndex,
+        standard_parameters::test_parameter_do_not_use_in_production::TOY_PARAMETERS_01,
+    };
+
+    #[test]
+    fn test_transcript_builder() {
+        let mut csprng = Csprng::new(b"test_transcript_builder");
+        let fixed_parameters = &*TOY_PARAMETERS_01;
+        let field = &fixed_parameters.field;
+        let group = &fixed_parameters.group;
+        let h_e = HValue::from([0x5A; 32]);
+
+        let elems: Vec<_> = (0..6)
+            .map(|_| group.random_group_elem(&mut csprng))
+            .collect();
+        let contest_ix = ContestIndex::from_one_based_index(3).unwrap();
+
+        // The bytes as previously built by `DecryptionProof::challenge`, plus an index.
+        let mut v = vec![0x30];
+        for elem in &elems {
+            v.extend_from_slice(elem.to_be_bytes_left_pad(group).as_slice());
+        }
+        v.extend_from_slice(&[0, 0, 0, 3]);
+
+        let transcript = elems
+            .iter()
+            .fold(TranscriptBuilder::new().push_u8(0x30), |t, elem| {
+                t.push_group_element(elem, group)
+            })
+            .push_index(contest_ix);
+
+        assert_eq!(transcript.as_bytes(), v.as_slice());
+        assert_eq!(transcript.len(), 1 + 6 * group.p_len_bytes() + 4);
+
+        let c = eg_h(&h_e, &v);
+        assert_eq!(
+            transcript.finish(&h_e, field),
+            FieldElement::from_bytes_be(c.0.as_slice(), field)
+        );
+    }
+}
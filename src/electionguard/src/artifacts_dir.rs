@@ -6,6 +6,7 @@
 #![deny(clippy::manual_assert)]
 
 use std::fs::{File, OpenOptions};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Context, Result};
@@ -16,6 +17,7 @@ use eg::hash::HValue;
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) enum ArtifactFile {
     PseudorandomSeedDefeatsAllSecrecy,
+    StandardParametersVerification,
     ElectionManifestPretty,
     ElectionManifestCanonical,
     ElectionParameters,
@@ -98,6 +100,9 @@ impl From<ArtifactFile> for PathBuf {
             //     i.to_string_hex_no_prefix_suffix()
             // )),
             // JointElectionPublicKey => PathBuf::from("joint_election_public_key.json"),
+            StandardParametersVerification => {
+                election_public_dir().join("standard_parameters_verification.json")
+            }
             ElectionManifestPretty => election_public_dir().join("election_manifest_pretty.json"),
             ElectionManifestCanonical => {
                 election_public_dir().join("election_manifest_canonical.bin")
@@ -190,36 +195,33 @@ impl ArtifactsDir {
         Ok(stdioread_and_path)
     }
 
-    /// Opens the specified file for writing, or if "-" then write to stdout.
+    /// Writes the bytes to the specified file, or if "-" then to stdout.
     /// Next it tries any specified artifact file.
-    pub fn out_file_stdiowrite(
+    /// Files are written atomically, so an interrupted write never leaves a partial file behind.
+    /// Returns the path written.
+    pub fn out_file_write_atomic(
         &self,
         opt_path: &Option<PathBuf>,
         opt_artifact_file: Option<ArtifactFile>,
-    ) -> Result<(Box<dyn std::io::Write>, PathBuf)> {
-        let mut open_options_write = OpenOptions::new();
-        open_options_write.write(true).create(true).truncate(true);
-
-        let stdiowrite_and_path: (Box<dyn std::io::Write>, PathBuf) =
-            if let Some(ref path) = opt_path {
-                let stdiowrite: Box<dyn std::io::Write> = if *path == PathBuf::from("-") {
-                    Box::new(std::io::stdout())
-                } else {
-                    let file = open_options_write.open(path).with_context(|| {
-                        format!("Couldn't open file for writing: {}", path.display())
-                    })?;
-                    Box::new(file)
-                };
-
-                (stdiowrite, path.clone())
-            } else if let Some(artifact_file) = opt_artifact_file {
-                let (file, path) = self.open(artifact_file, &open_options_write)?;
-                let bx_write: Box<dyn std::io::Write> = Box::new(file);
-                (bx_write, path)
-            } else {
-                bail!("Specify at least one of opt_path or opt_artifact_file");
-            };
+        bytes: &[u8],
+    ) -> Result<PathBuf> {
+        let path = if let Some(ref path) = opt_path {
+            if path.as_os_str() == "-" {
+                std::io::stdout()
+                    .write_all(bytes)
+                    .context("Couldn't write to stdout")?;
+                return Ok(path.clone());
+            }
+            path.clone()
+        } else if let Some(artifact_file) = opt_artifact_file {
+            self.path(artifact_file)
+        } else {
+            bail!("Specify at least one of opt_path or opt_artifact_file");
+        };
+
+        util::file::write_atomic(&path, bytes)
+            .with_context(|| format!("Couldn't write file: {}", path.display()))?;
 
-        Ok(stdiowrite_and_path)
+        Ok(path)
     }
 }
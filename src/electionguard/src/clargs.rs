@@ -21,6 +21,47 @@ pub(crate) struct Clargs {
     #[arg(long)]
     pub insecure_deterministic: bool,
 
+    /// Explicitly select the operating system's random number generator as the source of
+    /// randomness. This is also the default when `--insecure-deterministic` is not given.
+    #[arg(long, conflicts_with = "insecure_deterministic")]
+    pub use_os_csprng: bool,
+
+    /// File from which to read the seed data in insecure deterministic mode, instead of the
+    /// `artifacts/pseudorandom_seed_defeats_all_secrecy.bin` file. Every subcommand seeds its
+    /// csprng from it, so separate invocations with the same seed file produce the same artifacts.
+    #[arg(long, requires = "insecure_deterministic")]
+    pub seed_file: Option<PathBuf>,
+
+    /// File from which to load the fixed parameters, instead of using the standard parameters.
+    /// For experimentation. Unless built with the `eg_allow_nonstandard_params` feature,
+    /// parameters other than the standard parameters are rejected.
+    #[arg(long)]
+    pub fixed_parameters: Option<PathBuf>,
+
     #[command(subcommand)]
     pub subcommand: Subcommands,
 }
+
+/// The source of the seed data for the csprng.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum CsprngSource {
+    /// True random bytes from the operating system.
+    Os,
+
+    /// Seed data read from a file. Completely insecure, for testing only.
+    InsecureDeterministic,
+}
+
+impl Clargs {
+    /// Returns the source of the seed data for the csprng selected by the command line.
+    ///
+    /// The arguments `--use-os-csprng` and `--insecure-deterministic` conflict, but should both
+    /// be set anyway, the operating system's random number generator wins.
+    pub fn csprng_source(&self) -> CsprngSource {
+        if self.insecure_deterministic && !self.use_os_csprng {
+            CsprngSource::InsecureDeterministic
+        } else {
+            CsprngSource::Os
+        }
+    }
+}
@@ -5,16 +5,18 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
-use std::path::PathBuf;
+use std::{io::Read, path::PathBuf};
 
 use anyhow::{ensure, Context, Result};
 use rand_core::{OsRng, RngCore};
 
 use eg::{
     election_manifest::ElectionManifest, election_parameters::ElectionParameters,
-    example_election_manifest::example_election_manifest, guardian::GuardianIndex,
+    election_record::PreVotingData, example_election_manifest::example_election_manifest,
+    fixed_parameters::FixedParameters, guardian::GuardianIndex,
     guardian_public_key::GuardianPublicKey, guardian_secret_key::GuardianSecretKey, hashes::Hashes,
     hashes_ext::HashesExt, joint_election_public_key::JointElectionPublicKey,
+    standard_parameters::STANDARD_PARAMETERS,
 };
 use util::csprng::Csprng;
 
@@ -59,6 +61,37 @@ impl ElectionManifestSource {
     }
 }
 
+/// Loads the fixed parameters from the file given by the `--fixed-parameters` command line
+/// argument, if any, or returns the standard parameters.
+///
+/// The loaded parameters are validated, and rejected if nonstandard unless the
+/// `eg_allow_nonstandard_params` feature is enabled.
+pub(crate) fn load_fixed_parameters(
+    opt_fixed_parameters_path: &Option<PathBuf>,
+    artifacts_dir: &ArtifactsDir,
+) -> Result<FixedParameters> {
+    let Some(path) = opt_fixed_parameters_path else {
+        return Ok(STANDARD_PARAMETERS.clone());
+    };
+
+    let (mut stdioread, path) = artifacts_dir.in_file_stdioread(&Some(path.clone()), None)?;
+
+    let mut json = String::new();
+    stdioread
+        .read_to_string(&mut json)
+        .with_context(|| format!("Reading fixed parameters from: {}", path.display()))?;
+
+    // The csprng is only used for primality testing.
+    let mut csprng = Csprng::new(&osrng_seed_data_for_csprng());
+
+    let fixed_parameters = FixedParameters::try_validate_from_json(&json, &mut csprng)
+        .with_context(|| format!("Loading fixed parameters from: {}", path.display()))?;
+
+    eprintln!("Fixed parameters loaded from: {}", path.display());
+
+    Ok(fixed_parameters)
+}
+
 pub(crate) fn load_election_parameters(
     artifacts_dir: &ArtifactsDir,
     csprng: &mut Csprng,
@@ -201,6 +234,127 @@ pub(crate) fn load_hashes_ext(artifacts_dir: &ArtifactsDir) -> Result<HashesExt>
     Ok(hashes)
 }
 
+/// A prerequisite of ballot encryption, i.e., a component of the [`PreVotingData`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EncryptionPrerequisite {
+    ElectionParameters,
+    ElectionManifest,
+    Hashes,
+    JointElectionPublicKey,
+    ExtendedBaseHash,
+}
+
+impl EncryptionPrerequisite {
+    /// All the prerequisites, in the order they are produced.
+    pub const ALL: [EncryptionPrerequisite; 5] = [
+        EncryptionPrerequisite::ElectionParameters,
+        EncryptionPrerequisite::ElectionManifest,
+        EncryptionPrerequisite::Hashes,
+        EncryptionPrerequisite::JointElectionPublicKey,
+        EncryptionPrerequisite::ExtendedBaseHash,
+    ];
+
+    /// The artifact file from which the prerequisite is loaded.
+    pub fn artifact_file(self) -> ArtifactFile {
+        use EncryptionPrerequisite::*;
+        match self {
+            ElectionParameters => ArtifactFile::ElectionParameters,
+            ElectionManifest => ArtifactFile::ElectionManifestCanonical,
+            Hashes => ArtifactFile::Hashes,
+            JointElectionPublicKey => ArtifactFile::JointElectionPublicKey,
+            ExtendedBaseHash => ArtifactFile::HashesExt,
+        }
+    }
+}
+
+impl std::fmt::Display for EncryptionPrerequisite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use EncryptionPrerequisite::*;
+        let s = match self {
+            ElectionParameters => "Election parameters",
+            ElectionManifest => "Election manifest",
+            Hashes => "Hashes H_P, H_M, and H_B",
+            JointElectionPublicKey => "Joint election public key",
+            ExtendedBaseHash => "Extended base hash H_E",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Which of the prerequisites of ballot encryption are available in the artifacts dir.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct EncryptionReadiness {
+    /// The prerequisites available, in the order they are produced.
+    pub satisfied: Vec<EncryptionPrerequisite>,
+
+    /// The prerequisites not yet available, in the order they are produced.
+    pub missing: Vec<EncryptionPrerequisite>,
+}
+
+impl EncryptionReadiness {
+    /// Returns whether all the prerequisites of ballot encryption are available.
+    pub fn is_ready(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Reports which of the prerequisites of ballot encryption are available in the artifacts dir.
+/// The prerequisites are only checked for existence, not loaded or validated.
+pub(crate) fn ballot_encryption_readiness(artifacts_dir: &ArtifactsDir) -> EncryptionReadiness {
+    let (satisfied, missing) = EncryptionPrerequisite::ALL
+        .into_iter()
+        .partition(|prerequisite| artifacts_dir.exists(prerequisite.artifact_file()));
+    EncryptionReadiness { satisfied, missing }
+}
+
+/// Loads the election parameters, manifest, hashes, extended hashes, and joint election public key
+/// from the artifacts dir and assembles them into the [`PreVotingData`].
+///
+/// Fails with a message naming every component that is not yet available.
+pub(crate) fn load_pre_voting_data(
+    artifacts_dir: &ArtifactsDir,
+    csprng: &mut Csprng,
+) -> Result<PreVotingData> {
+    let readiness = ballot_encryption_readiness(artifacts_dir);
+    ensure!(
+        readiness.is_ready(),
+        "Not available for the pre-voting data: {}",
+        readiness
+            .missing
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    //? TODO: Do we need a command line arg to specify the election parameters source?
+    let election_parameters = load_election_parameters(artifacts_dir, csprng)
+        .context("Election parameters are not available for the pre-voting data")?;
+
+    //? TODO: Do we need a command line arg to specify the election manifest source?
+    let election_manifest = ElectionManifestSource::ArtifactFileElectionManifestCanonical
+        .load_election_manifest(artifacts_dir)
+        .context("Election manifest is not available for the pre-voting data")?;
+
+    let hashes = load_hashes(artifacts_dir)
+        .context("Hashes H_P, H_M, and H_B are not available for the pre-voting data")?;
+
+    let hashes_ext = load_hashes_ext(artifacts_dir)
+        .context("Extended base hash H_E is not available for the pre-voting data")?;
+
+    let joint_election_public_key =
+        load_joint_election_public_key(artifacts_dir, &election_parameters)
+            .context("Joint election public key is not available for the pre-voting data")?;
+
+    Ok(PreVotingData::new(
+        election_manifest,
+        election_parameters,
+        hashes,
+        hashes_ext,
+        joint_election_public_key,
+    ))
+}
+
 /// Read the recommended amount of seed data from the OS RNG.
 ///
 /// `OsRng` is implemented by the `getrandom` crate, which describes itself as an "Interface to
@@ -232,3 +386,177 @@ pub(crate) fn load_all_guardian_public_keys(
 
     Ok(guardian_public_keys)
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use eg::{
+        device::Device,
+        example_pre_voting_data::example_pre_voting_data,
+        serializable::{SerializableCanonical, SerializablePretty},
+    };
+
+    #[test]
+    fn test_load_pre_voting_data() {
+        let mut csprng = Csprng::new(b"test_load_pre_voting_data");
+
+        let expected = example_pre_voting_data(&mut csprng).unwrap();
+
+        let dir_path = std::env::temp_dir().join(format!(
+            "electionguard_test_load_pre_voting_data_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir_path.join("public")).unwrap();
+        let artifacts_dir = ArtifactsDir::new(&dir_path).unwrap();
+
+        let write = |artifact_file, f: &dyn Fn(&mut dyn std::io::Write) -> Result<()>| {
+            let mut bytes = Vec::new();
+            f(&mut bytes).unwrap();
+            artifacts_dir
+                .out_file_write_atomic(&None, Some(artifact_file), &bytes)
+                .unwrap();
+        };
+
+        write(ArtifactFile::ElectionParameters, &|w| {
+            expected.parameters.to_stdiowrite_pretty(w)
+        });
+        write(ArtifactFile::ElectionManifestCanonical, &|w| {
+            expected.manifest.to_stdiowrite_canonical(w)
+        });
+        write(ArtifactFile::Hashes, &|w| {
+            expected.hashes.to_stdiowrite_pretty(w)
+        });
+        write(ArtifactFile::JointElectionPublicKey, &|w| {
+            expected.public_key.to_stdiowrite_pretty(w)
+        });
+
+        // H_E has not been written yet.
+        let err = load_pre_voting_data(&artifacts_dir, &mut csprng).unwrap_err();
+        assert!(err.to_string().contains("H_E"), "{err}");
+        assert!(!err.to_string().contains("Joint"), "{err}");
+
+        write(ArtifactFile::HashesExt, &|w| {
+            expected.hashes_ext.to_stdiowrite_pretty(w)
+        });
+
+        let pre_voting_data = load_pre_voting_data(&artifacts_dir, &mut csprng).unwrap();
+
+        std::fs::remove_dir_all(&dir_path).unwrap();
+
+        assert_eq!(
+            pre_voting_data.to_canonical_bytes().unwrap(),
+            expected.to_canonical_bytes().unwrap()
+        );
+
+        let device = Device::new("test device", pre_voting_data);
+        assert_eq!(device.header.hashes_ext.h_e, expected.hashes_ext.h_e);
+    }
+
+    #[test]
+    fn test_load_fixed_parameters() {
+        let dir_path = std::env::temp_dir().join(format!(
+            "electionguard_test_load_fixed_parameters_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir_path).unwrap();
+        let artifacts_dir = ArtifactsDir::new(&dir_path).unwrap();
+
+        // Without a file, the standard parameters.
+        let fixed_parameters = load_fixed_parameters(&None, &artifacts_dir).unwrap();
+        assert_eq!(fixed_parameters, *STANDARD_PARAMETERS);
+
+        // The standard parameters, from a file.
+        let standard_path = dir_path.join("standard_parameters.json");
+        let mut file = std::fs::File::create(&standard_path).unwrap();
+        STANDARD_PARAMETERS.to_stdiowrite_pretty(&mut file).unwrap();
+        drop(file);
+        let fixed_parameters = load_fixed_parameters(&Some(standard_path), &artifacts_dir).unwrap();
+        assert_eq!(fixed_parameters, *STANDARD_PARAMETERS);
+
+        // Toy parameters, with a `q` of 7 bits and a `p` of 16 bits.
+        let toy_path = dir_path.join("toy_parameters.json");
+        std::fs::write(
+            &toy_path,
+            format!(
+                r#"{{
+                    "generation_parameters": {{
+                        "q_bits_total": 7,
+                        "p_bits_total": 16,
+                        "p_bits_msb_fixed_1": 0,
+                        "p_middle_bits_source": null,
+                        "p_bits_lsb_fixed_1": 0
+                    }},
+                    "field": {{ "q": "{q:0>64}" }},
+                    "group": {{ "p": "{p:0>1024}", "g": "{g:0>1024}", "q": "{q:0>64}" }}
+                }}"#,
+                q = "7F",
+                p = "E72F",
+                g = "7F68",
+            ),
+        )
+        .unwrap();
+        let result = load_fixed_parameters(&Some(toy_path), &artifacts_dir);
+
+        std::fs::remove_dir_all(&dir_path).unwrap();
+
+        #[cfg(not(feature = "eg_allow_nonstandard_params"))]
+        {
+            let err = result.unwrap_err();
+            assert!(format!("{err:#}").contains("insecure"), "{err:#}");
+        }
+        #[cfg(feature = "eg_allow_nonstandard_params")]
+        assert_eq!(result.unwrap().field.order(), &127_u8.into());
+    }
+
+    #[test]
+    fn test_ballot_encryption_readiness() {
+        use EncryptionPrerequisite::*;
+
+        let dir_path = std::env::temp_dir().join(format!(
+            "electionguard_test_ballot_encryption_readiness_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(dir_path.join("public")).unwrap();
+        let artifacts_dir = ArtifactsDir::new(&dir_path).unwrap();
+
+        let readiness = ballot_encryption_readiness(&artifacts_dir);
+        assert!(!readiness.is_ready());
+        assert!(readiness.satisfied.is_empty());
+        assert_eq!(readiness.missing, EncryptionPrerequisite::ALL);
+
+        let produce = |prerequisite: EncryptionPrerequisite| {
+            std::fs::write(artifacts_dir.path(prerequisite.artifact_file()), "{}").unwrap();
+        };
+        for prerequisite in [ElectionParameters, ElectionManifest, Hashes] {
+            produce(prerequisite);
+        }
+
+        // Not ready before the joint election public key exists.
+        let readiness = ballot_encryption_readiness(&artifacts_dir);
+        assert!(!readiness.is_ready());
+        assert_eq!(
+            readiness.satisfied,
+            [ElectionParameters, ElectionManifest, Hashes]
+        );
+        assert_eq!(
+            readiness.missing,
+            [JointElectionPublicKey, ExtendedBaseHash]
+        );
+        let err = load_pre_voting_data(&artifacts_dir, &mut Csprng::new(b"")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Not available for the pre-voting data: Joint election public key, Extended base hash H_E"
+        );
+
+        // Ready afterward.
+        produce(JointElectionPublicKey);
+        produce(ExtendedBaseHash);
+        let readiness = ballot_encryption_readiness(&artifacts_dir);
+
+        std::fs::remove_dir_all(&dir_path).unwrap();
+
+        assert!(readiness.is_ready());
+        assert_eq!(readiness.satisfied, EncryptionPrerequisite::ALL);
+    }
+}
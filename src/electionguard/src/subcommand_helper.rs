@@ -5,15 +5,15 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
-use std::{fs::OpenOptions, io::Read};
+use std::io::Read;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 
 use util::csprng::Csprng;
 
 use crate::{
     artifacts_dir::{ArtifactFile, ArtifactsDir},
-    clargs::Clargs,
+    clargs::{Clargs, CsprngSource},
     common_utils::osrng_seed_data_for_csprng,
 };
 
@@ -47,6 +47,30 @@ impl SubcommandHelper {
     /// But only once, ever, for this subcommand.
     /// We don't allow the Csprng to be initialized multiple times.
     pub fn get_csprng(&mut self, customization_data: &[u8]) -> Result<Csprng> {
+        let seed_data = self.get_seed_data()?;
+        Ok(Csprng::new(&csprng_seed(&seed_data, customization_data)))
+    }
+
+    /// Like [`get_csprng`](Self::get_csprng), but returns a csprng seed for each element of
+    /// `customizations`, all derived from the same entropy source or seed file.
+    /// Seeding a [`Csprng`] with one of these gives the same csprng that `get_csprng` would
+    /// have returned for that customization data. Unlike a [`Csprng`], a seed can be sent
+    /// to another thread.
+    pub fn get_csprng_seeds<I, C>(&mut self, customizations: I) -> Result<Vec<Vec<u8>>>
+    where
+        I: IntoIterator<Item = C>,
+        C: AsRef<[u8]>,
+    {
+        let seed_data = self.get_seed_data()?;
+        Ok(customizations
+            .into_iter()
+            .map(|customization_data| csprng_seed(&seed_data, customization_data.as_ref()))
+            .collect())
+    }
+
+    /// Reads the seed data from the entropy source or the seed file.
+    /// But only once, ever, for this subcommand.
+    fn get_seed_data(&mut self) -> Result<Vec<u8>> {
         if !self.uses_csprng {
             bail!("This subcommand is not supposed to use the Csprng");
         }
@@ -58,32 +82,109 @@ impl SubcommandHelper {
         self.csprng_initialized = true;
 
         let mut seed_data = Vec::new();
-        if self.clargs.insecure_deterministic {
-            let (mut file, path) = self.artifacts_dir.open(
-                ArtifactFile::PseudorandomSeedDefeatsAllSecrecy,
-                OpenOptions::new().read(true),
-            )?;
-
-            file.read_to_end(&mut seed_data)?;
-
-            eprintln!("!!! WARNING: Using INSECURE deterministic mode. !!!",);
-            eprintln!(
-                "{} bytes of seed data read from: {}",
-                seed_data.len(),
-                path.display()
-            );
-        } else {
-            // Read true random bytes from the OS.
-            seed_data.extend_from_slice(&osrng_seed_data_for_csprng());
-        };
-
-        let mut seed = Vec::new();
-        seed.extend_from_slice(&(seed_data.len() as u64).to_be_bytes());
-        seed.extend_from_slice(seed_data.as_slice());
-
-        seed.extend_from_slice(&(customization_data.len() as u64).to_be_bytes());
-        seed.extend_from_slice(customization_data);
-
-        Ok(Csprng::new(&seed))
+        match self.clargs.csprng_source() {
+            CsprngSource::InsecureDeterministic => {
+                let (mut stdioread, path) = self.artifacts_dir.in_file_stdioread(
+                    &self.clargs.seed_file,
+                    Some(ArtifactFile::PseudorandomSeedDefeatsAllSecrecy),
+                )?;
+
+                stdioread
+                    .read_to_end(&mut seed_data)
+                    .with_context(|| format!("Reading seed data from: {}", path.display()))?;
+
+                eprintln!("!!! WARNING: Using INSECURE deterministic mode. !!!",);
+                eprintln!(
+                    "{} bytes of seed data read from: {}",
+                    seed_data.len(),
+                    path.display()
+                );
+            }
+            CsprngSource::Os => {
+                // Read true random bytes from the OS.
+                seed_data.extend_from_slice(&osrng_seed_data_for_csprng());
+            }
+        }
+
+        Ok(seed_data)
+    }
+}
+
+fn csprng_seed(seed_data: &[u8], customization_data: &[u8]) -> Vec<u8> {
+    let mut seed = Vec::new();
+    seed.extend_from_slice(&(seed_data.len() as u64).to_be_bytes());
+    seed.extend_from_slice(seed_data);
+
+    seed.extend_from_slice(&(customization_data.len() as u64).to_be_bytes());
+    seed.extend_from_slice(customization_data);
+
+    seed
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use clap::Parser;
+
+    fn subcommand_helper(args: &[&str]) -> Result<SubcommandHelper> {
+        let mut all_args = vec!["electionguard", "--artifacts-dir", "."];
+        all_args.extend_from_slice(args);
+        all_args.push("write-random-seed");
+        let clargs = Clargs::try_parse_from(all_args)?;
+        let artifacts_dir = ArtifactsDir::new(".")?;
+        SubcommandHelper::new(clargs, artifacts_dir, true)
+    }
+
+    #[test]
+    fn test_csprng_source() {
+        // OS mode, whether explicitly requested or by default.
+        for args in [&["--use-os-csprng"][..], &[]] {
+            let mut subcommand_helper_a = subcommand_helper(args).unwrap();
+            let mut subcommand_helper_b = subcommand_helper(args).unwrap();
+            assert_eq!(subcommand_helper_a.clargs.csprng_source(), CsprngSource::Os);
+
+            let seed_a = subcommand_helper_a.get_seed_data().unwrap();
+            let seed_b = subcommand_helper_b.get_seed_data().unwrap();
+            assert_ne!(seed_a, seed_b);
+        }
+
+        // Insecure deterministic mode.
+        let seed_path = std::env::temp_dir().join(format!(
+            "electionguard_test_csprng_source_{}.bin",
+            std::process::id()
+        ));
+        std::fs::write(&seed_path, b"test_csprng_source").unwrap();
+        let seed_path_str = seed_path.to_str().unwrap();
+        let args = ["--insecure-deterministic", "--seed-file", seed_path_str];
+        let mut subcommand_helper_a = subcommand_helper(&args).unwrap();
+        let mut subcommand_helper_b = subcommand_helper(&args).unwrap();
+        assert_eq!(
+            subcommand_helper_a.clargs.csprng_source(),
+            CsprngSource::InsecureDeterministic
+        );
+
+        let seed_a = subcommand_helper_a.get_seed_data().unwrap();
+        let seed_b = subcommand_helper_b.get_seed_data().unwrap();
+        std::fs::remove_file(&seed_path).unwrap();
+        assert_eq!(seed_a, b"test_csprng_source");
+        assert_eq!(seed_a, seed_b);
+
+        // Requesting both modes is an error.
+        let err = Clargs::try_parse_from([
+            "electionguard",
+            "--artifacts-dir",
+            ".",
+            "--insecure-deterministic",
+            "--use-os-csprng",
+            "write-random-seed",
+        ])
+        .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+
+        // Should both be set anyway, the OS wins.
+        let mut clargs = subcommand_helper(&["--use-os-csprng"]).unwrap().clargs;
+        clargs.insecure_deterministic = true;
+        assert_eq!(clargs.csprng_source(), CsprngSource::Os);
     }
 }
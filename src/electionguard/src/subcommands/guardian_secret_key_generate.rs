@@ -7,12 +7,13 @@
 
 use std::path::PathBuf;
 
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, ensure, Context, Result};
 
 use eg::{
-    guardian::GuardianIndex, guardian_secret_key::GuardianSecretKey,
-    serializable::SerializablePretty,
+    election_parameters::ElectionParameters, guardian::GuardianIndex,
+    guardian_secret_key::GuardianSecretKey, serializable::SerializableSecret,
 };
+use util::csprng::Csprng;
 
 use crate::{
     artifacts_dir::ArtifactFile, common_utils::load_election_parameters,
@@ -22,8 +23,18 @@ use crate::{
 #[derive(clap::Args, Debug)]
 pub(crate) struct GuardianSecretKeyGenerate {
     /// Guardian number, 1 <= i <= [`VaryingParameters::n`].
-    #[arg(long)]
-    i: GuardianIndex,
+    #[arg(long, required_unless_present = "i_last", conflicts_with = "i_last")]
+    i: Option<GuardianIndex>,
+
+    /// Generate keys for a range of guardians, starting with this guardian number.
+    /// The guardians are generated in parallel if built with `eg_parallel_key_generation`.
+    #[arg(long, requires = "i_last", conflicts_with_all = ["i", "name", "secret_key_out_file"])]
+    i_first: Option<GuardianIndex>,
+
+    /// Generate keys for a range of guardians, ending with this guardian number (inclusive).
+    /// Each key is written to the default file in its guardian's dir.
+    #[arg(long, conflicts_with_all = ["name", "secret_key_out_file"])]
+    i_last: Option<GuardianIndex>,
 
     /// Guardian's name or other short description.
     #[arg(long)]
@@ -42,46 +53,337 @@ impl Subcommand for GuardianSecretKeyGenerate {
     }
 
     fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
-        let mut csprng = subcommand_helper
-            .get_csprng(format!("GuardianSecretKeyGenerate({})", self.i).as_bytes())?;
+        let (is, opt_name, secret_key_out_file) = match (self.i, self.i_last) {
+            (Some(i), _) => (vec![i], self.name.clone(), self.secret_key_out_file.clone()),
+            (None, Some(i_last)) => {
+                let i_first = self.i_first.unwrap_or(GuardianIndex::MIN);
+                ensure!(
+                    i_first <= i_last,
+                    "Guardian number {i_first} must be less than or equal to {i_last}"
+                );
+                (
+                    GuardianIndex::iter_range_inclusive(i_first, i_last).collect(),
+                    None,
+                    None,
+                )
+            }
+            (None, None) => bail!("Specify either `--i` or `--i-last`"),
+        };
+
+        let csprng_seeds = subcommand_helper
+            .get_csprng_seeds(is.iter().map(|i| format!("GuardianSecretKeyGenerate({i})")))?;
+
+        let artifacts_dir = &subcommand_helper.artifacts_dir;
 
         //? TODO: Do we need a command line arg to specify the election parameters source?
-        let election_parameters =
-            load_election_parameters(&subcommand_helper.artifacts_dir, &mut csprng)?;
+        let secret_keys =
+            generate_secret_keys(is.iter().copied().zip(csprng_seeds), opt_name, |csprng| {
+                load_election_parameters(artifacts_dir, csprng)
+            })?;
+
+        for secret_key in secret_keys {
+            let mut bytes = Vec::new();
+            secret_key
+                .to_stdiowrite_pretty_secret(&mut bytes, true)
+                .with_context(|| format!("Serializing secret key for guardian {}", secret_key.i))?;
 
-        let varying_parameters = &election_parameters.varying_parameters;
+            let path = artifacts_dir.out_file_write_atomic(
+                &secret_key_out_file,
+                Some(ArtifactFile::GuardianSecretKey(secret_key.i)),
+                &bytes,
+            )?;
 
-        #[allow(clippy::nonminimal_bool)]
-        if !(self.i <= varying_parameters.n) {
-            bail!(
-                "Guardian number {} must be less than or equal to n = {} from election parameters",
-                self.i,
-                varying_parameters.n
+            eprintln!(
+                "Wrote secret key for guardian {} to: {}",
+                secret_key.i,
+                path.display()
             );
         }
 
-        let secret_key = GuardianSecretKey::generate(
-            &mut csprng,
-            &election_parameters,
-            self.i,
-            self.name.clone(),
+        Ok(())
+    }
+}
+
+/// Generates the secret key for guardian `i`.
+///
+/// The election parameters are loaded (and validated) using the same `csprng` that is then
+/// used for key generation.
+fn generate_secret_key<F>(
+    mut csprng: Csprng,
+    i: GuardianIndex,
+    opt_name: Option<String>,
+    load_election_parameters: &F,
+) -> Result<GuardianSecretKey>
+where
+    F: Fn(&mut Csprng) -> Result<ElectionParameters>,
+{
+    let election_parameters = load_election_parameters(&mut csprng)?;
+
+    let varying_parameters = &election_parameters.varying_parameters;
+
+    #[allow(clippy::nonminimal_bool)]
+    if !(i <= varying_parameters.n) {
+        bail!(
+            "Guardian number {} must be less than or equal to n = {} from election parameters",
+            i,
+            varying_parameters.n
         );
+    }
 
-        let (mut stdiowrite, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
-            &self.secret_key_out_file,
-            Some(ArtifactFile::GuardianSecretKey(self.i)),
-        )?;
+    Ok(GuardianSecretKey::generate(
+        &mut csprng,
+        &election_parameters,
+        i,
+        opt_name,
+    ))
+}
 
-        let description = format!("secret key for guardian {} to: {}", self.i, path.display());
+/// Generates the secret keys for several guardians. With the `eg_parallel_key_generation`
+/// feature, they are generated concurrently on the `rayon` thread pool.
+///
+/// Since each guardian has its own `Csprng` seed, the result is the same as if the keys were
+/// generated one at a time. The keys are returned in the same order as `is_and_csprng_seeds`.
+fn generate_secret_keys<I, F>(
+    is_and_csprng_seeds: I,
+    opt_name: Option<String>,
+    load_election_parameters: F,
+) -> Result<Vec<GuardianSecretKey>>
+where
+    I: IntoIterator<Item = (GuardianIndex, Vec<u8>)>,
+    F: Fn(&mut Csprng) -> Result<ElectionParameters> + Sync,
+{
+    let generate = |(i, csprng_seed): (GuardianIndex, Vec<u8>)| {
+        let csprng = Csprng::new(&csprng_seed);
+        generate_secret_key(csprng, i, opt_name.clone(), &load_election_parameters)
+            .with_context(|| format!("Generating secret key for guardian {i}"))
+    };
 
-        secret_key
-            .to_stdiowrite_pretty(stdiowrite.as_mut())
-            .with_context(|| format!("Writing {description}"))?;
+    #[cfg(feature = "eg_parallel_key_generation")]
+    {
+        use rayon::prelude::*;
 
-        drop(stdiowrite);
+        let is_and_csprng_seeds: Vec<_> = is_and_csprng_seeds.into_iter().collect();
+        is_and_csprng_seeds.into_par_iter().map(generate).collect()
+    }
 
-        eprintln!("Wrote {description}");
+    #[cfg(not(feature = "eg_parallel_key_generation"))]
+    is_and_csprng_seeds.into_iter().map(generate).collect()
+}
 
-        Ok(())
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::path::Path;
+
+    use clap::Parser;
+    use eg::{
+        example_election_parameters::example_election_parameters, serializable::SerializablePretty,
+    };
+
+    use super::*;
+    use crate::{artifacts_dir::ArtifactsDir, clargs::Clargs};
+
+    /// Runs the subcommand as `main` would, in insecure deterministic mode seeded from
+    /// `seed_file`.
+    fn run(artifacts_path: &Path, seed_file: &Path, subcommand_args: &[&str]) -> Result<()> {
+        let artifacts_path_str = artifacts_path.to_str().unwrap();
+        let seed_file_str = seed_file.to_str().unwrap();
+        let mut args = vec![
+            "electionguard",
+            "--artifacts-dir",
+            artifacts_path_str,
+            "--insecure-deterministic",
+            "--seed-file",
+            seed_file_str,
+        ];
+        args.extend_from_slice(subcommand_args);
+        let mut clargs = Clargs::try_parse_from(args)?;
+
+        let artifacts_dir = ArtifactsDir::new(artifacts_path)?;
+        let mut subcommand = std::mem::take(&mut clargs.subcommand);
+        let subcommand: &mut dyn Subcommand = (&mut subcommand).into();
+        let uses_csprng = subcommand.uses_csprng();
+        let mut subcommand_helper = SubcommandHelper::new(clargs, artifacts_dir, uses_csprng)?;
+        subcommand.do_it(&mut subcommand_helper)
+    }
+
+    /// Creates an artifacts dir holding the example election parameters, and the dirs for the
+    /// keys of guardians `1..=n`.
+    fn artifacts_dir_with_election_parameters(artifacts_path: &Path) -> ArtifactsDir {
+        let election_parameters = example_election_parameters();
+        let artifacts_dir = ArtifactsDir::new(artifacts_path).unwrap();
+        let artifact_files = std::iter::once(ArtifactFile::ElectionParameters).chain(
+            election_parameters
+                .varying_parameters
+                .each_guardian_i()
+                .flat_map(|i| {
+                    [
+                        ArtifactFile::GuardianSecretKey(i),
+                        ArtifactFile::GuardianPublicKey(i),
+                    ]
+                }),
+        );
+        for artifact_file in artifact_files {
+            let path = artifacts_dir.path(artifact_file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        }
+
+        let mut bytes = Vec::new();
+        election_parameters
+            .to_stdiowrite_pretty(&mut bytes)
+            .unwrap();
+        artifacts_dir
+            .out_file_write_atomic(&None, Some(ArtifactFile::ElectionParameters), &bytes)
+            .unwrap();
+        artifacts_dir
+    }
+
+    /// A fresh temporary dir for the test, holding a seed file.
+    fn test_dir_with_seed_file(test_name: &str) -> (PathBuf, PathBuf) {
+        let dir_path =
+            std::env::temp_dir().join(format!("electionguard_{test_name}_{}", std::process::id()));
+        let seed_file = dir_path.join("seed.bin");
+        std::fs::create_dir_all(&dir_path).unwrap();
+        std::fs::write(&seed_file, test_name.as_bytes()).unwrap();
+        (dir_path, seed_file)
+    }
+
+    #[test]
+    fn test_generate_range_matches_sequential() {
+        let (dir_path, seed_file) =
+            test_dir_with_seed_file("test_generate_range_matches_sequential");
+        let is: Vec<_> = example_election_parameters()
+            .varying_parameters
+            .each_guardian_i()
+            .collect();
+
+        // All the guardians in one invocation.
+        let range_artifacts_path = dir_path.join("range");
+        let range_artifacts_dir = artifacts_dir_with_election_parameters(&range_artifacts_path);
+        run(
+            &range_artifacts_path,
+            &seed_file,
+            &[
+                "guardian-secret-key-generate",
+                "--i-first",
+                "1",
+                "--i-last",
+                "5",
+            ],
+        )
+        .unwrap();
+
+        // One invocation per guardian.
+        let sequential_artifacts_path = dir_path.join("sequential");
+        let sequential_artifacts_dir =
+            artifacts_dir_with_election_parameters(&sequential_artifacts_path);
+        for i in &is {
+            run(
+                &sequential_artifacts_path,
+                &seed_file,
+                &["guardian-secret-key-generate", "--i", &i.to_string()],
+            )
+            .unwrap();
+        }
+
+        // Guardian numbers greater than `n` are rejected.
+        let err = run(
+            &range_artifacts_path,
+            &seed_file,
+            &[
+                "guardian-secret-key-generate",
+                "--i-first",
+                "5",
+                "--i-last",
+                "6",
+            ],
+        )
+        .unwrap_err();
+
+        let read_secret_keys = |artifacts_dir: &ArtifactsDir| {
+            is.iter()
+                .map(|&i| std::fs::read(artifacts_dir.path(ArtifactFile::GuardianSecretKey(i))))
+                .collect::<std::io::Result<Vec<_>>>()
+        };
+        let range_secret_keys = read_secret_keys(&range_artifacts_dir);
+        let sequential_secret_keys = read_secret_keys(&sequential_artifacts_dir);
+
+        std::fs::remove_dir_all(&dir_path).unwrap();
+
+        assert_eq!(is.len(), 5);
+        assert_eq!(range_secret_keys.unwrap(), sequential_secret_keys.unwrap());
+        assert!(
+            format!("{err:#}").contains("must be less than or equal to n = 5"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_range_rejects_single_key_args() {
+        // A range of keys can't share a name or an out file.
+        for args in [
+            ["--i-last", "3", "--name", "Guardian"],
+            ["--i-last", "3", "--secret-key-out-file", "-"],
+        ] {
+            let err = Clargs::try_parse_from(
+                ["electionguard", "guardian-secret-key-generate"]
+                    .into_iter()
+                    .chain(args),
+            )
+            .unwrap_err();
+            assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+        }
+    }
+
+    #[test]
+    fn test_seed_file_reproducible_across_invocations() {
+        let (dir_path, seed_file) =
+            test_dir_with_seed_file("test_seed_file_reproducible_across_invocations");
+
+        let i = GuardianIndex::from_one_based_index(1).unwrap();
+        let keys: Vec<_> = ["a", "b"]
+            .into_iter()
+            .map(|artifacts_subdir| {
+                let artifacts_path = dir_path.join(artifacts_subdir);
+                let artifacts_dir = artifacts_dir_with_election_parameters(&artifacts_path);
+
+                run(
+                    &artifacts_path,
+                    &seed_file,
+                    &["guardian-secret-key-generate", "--i", "1"],
+                )
+                .unwrap();
+                run(
+                    &artifacts_path,
+                    &seed_file,
+                    &["guardian-secret-key-write-public-key", "--i", "1"],
+                )
+                .unwrap();
+
+                [
+                    ArtifactFile::GuardianSecretKey(i),
+                    ArtifactFile::GuardianPublicKey(i),
+                ]
+                .map(|artifact_file| std::fs::read(artifacts_dir.path(artifact_file)).unwrap())
+            })
+            .collect();
+
+        std::fs::remove_dir_all(&dir_path).unwrap();
+
+        // Separate invocations with the same seed file give the same keys.
+        assert_eq!(keys[0], keys[1]);
+
+        // The seed file requires insecure deterministic mode.
+        assert!(Clargs::try_parse_from([
+            "electionguard",
+            "--artifacts-dir",
+            ".",
+            "--seed-file",
+            "seed.bin",
+            "guardian-secret-key-generate",
+            "--i",
+            "1",
+        ])
+        .is_err());
     }
 }
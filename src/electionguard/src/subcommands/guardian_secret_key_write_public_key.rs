@@ -64,19 +64,17 @@ impl Subcommand for GuardianSecretKeyWritePublicKey {
 
         let public_key = guardian_secret_key.make_public_key();
 
-        let (mut stdiowrite, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+        let mut bytes = Vec::new();
+        public_key
+            .to_stdiowrite_pretty(&mut bytes)
+            .with_context(|| format!("Serializing public key for guardian {i}"))?;
+
+        let path = subcommand_helper.artifacts_dir.out_file_write_atomic(
             &self.public_key_out,
             Some(ArtifactFile::GuardianPublicKey(i)),
+            &bytes,
         )?;
 
-        public_key
-            .to_stdiowrite_pretty(stdiowrite.as_mut())
-            .with_context(|| {
-                format!("Writing public key for guardian {i} to: {}", path.display())
-            })?;
-
-        drop(stdiowrite);
-
         eprintln!("Wrote public key for guardian {i} to: {}", path.display());
 
         Ok(())
@@ -0,0 +1,225 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use eg::{
+    election_manifest::{ElectionManifest, ElectionManifestLimits},
+    manifest_lint::{lint_election_manifest, ManifestLintFinding, ManifestLintSeverity},
+};
+
+use crate::{
+    artifacts_dir::ArtifactFile, subcommand_helper::SubcommandHelper, subcommands::Subcommand,
+};
+
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct LintManifest {
+    /// Use the pretty JSON election manifest file in the artifacts dir.
+    #[arg(long)]
+    pub in_pretty: bool,
+
+    /// Input election manifest file. Default is the canonical JSON file in the artifacts dir.
+    #[arg(long)]
+    pub in_file: Option<PathBuf>,
+
+    /// Report ballot styles without contests as warnings rather than errors.
+    #[arg(long)]
+    pub allow_empty_ballot_styles: bool,
+}
+
+impl LintManifest {
+    /// Loads the election manifest, without validating it, and returns the findings about it.
+    fn findings(&self, subcommand_helper: &SubcommandHelper) -> Result<Vec<ManifestLintFinding>> {
+        if self.in_pretty && self.in_file.is_some() {
+            bail!("Specify at most one of `--in-pretty` or `--in-file`");
+        }
+
+        let opt_artifact_file = if self.in_pretty {
+            ArtifactFile::ElectionManifestPretty
+        } else {
+            ArtifactFile::ElectionManifestCanonical
+        };
+
+        let (mut stdioread, actual_path) = subcommand_helper
+            .artifacts_dir
+            .in_file_stdioread(&self.in_file, Some(opt_artifact_file))?;
+
+        let election_manifest =
+            ElectionManifest::from_stdioread(&mut stdioread).with_context(|| {
+                format!("Reading election manifest from: {}", actual_path.display())
+            })?;
+
+        eprintln!("Election manifest loaded from: {}", actual_path.display());
+
+        let limits = ElectionManifestLimits {
+            allow_empty_ballot_styles: self.allow_empty_ballot_styles,
+            ..Default::default()
+        };
+
+        Ok(lint_election_manifest(&election_manifest, &limits))
+    }
+}
+
+impl Subcommand for LintManifest {
+    fn uses_csprng(&self) -> bool {
+        false
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let findings = self.findings(subcommand_helper)?;
+
+        for finding in &findings {
+            println!("{finding}");
+        }
+
+        let cnt_errors = findings
+            .iter()
+            .filter(|finding| finding.severity == ManifestLintSeverity::Error)
+            .count();
+        let cnt_warnings = findings.len() - cnt_errors;
+
+        eprintln!("Found {cnt_errors} errors and {cnt_warnings} warnings.");
+
+        if cnt_errors != 0 {
+            bail!("The election manifest has {cnt_errors} errors");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use clap::Parser;
+
+    use super::*;
+    use crate::{artifacts_dir::ArtifactsDir, clargs::Clargs};
+
+    /// Runs the subcommand as `main` would, on the specified manifest file.
+    fn lint_manifest(
+        in_file: &std::path::Path,
+        extra_args: &[&str],
+    ) -> (Result<Vec<ManifestLintFinding>>, Result<()>) {
+        let mut args = vec![
+            "electionguard",
+            "--artifacts-dir",
+            ".",
+            "lint-manifest",
+            "--in-file",
+            in_file.to_str().unwrap(),
+        ];
+        args.extend_from_slice(extra_args);
+        let mut clargs = Clargs::try_parse_from(args).unwrap();
+
+        let mut subcommand = std::mem::take(&mut clargs.subcommand);
+        let crate::subcommands::Subcommands::LintManifest(lint_manifest) = &mut subcommand else {
+            unreachable!();
+        };
+        let mut subcommand_helper =
+            SubcommandHelper::new(clargs, ArtifactsDir::new(".").unwrap(), false).unwrap();
+
+        let findings = lint_manifest.findings(&subcommand_helper);
+        let result = lint_manifest.do_it(&mut subcommand_helper);
+        (findings, result)
+    }
+
+    #[test]
+    fn test_lint_manifest() {
+        use ManifestLintSeverity::*;
+
+        // A manifest with a duplicate contest label, a clamped selection limit, a contest on no
+        // ballot style, and an empty ballot style.
+        let manifest_json = r#"{
+            "label": "Seeded election",
+            "contests": [
+                { "label": "Mayor", "selection_limit": 1,
+                  "options": [ { "label": "Alice" }, { "label": "Bob" } ] },
+                { "label": "Council", "selection_limit": 3,
+                  "options": [ { "label": "Carol" }, { "label": "Dave" } ] },
+                { "label": "THIRD_CONTEST", "selection_limit": 1,
+                  "options": [ { "label": "Erin" } ] }
+            ],
+            "ballot_styles": [
+                { "label": "North", "contests": [ 1, 2NORTH_DUPLICATE ] },
+                { "label": "South", "contests": [] }
+            ]
+        }"#;
+        let path = std::env::temp_dir().join(format!(
+            "electionguard_test_lint_manifest_{}.json",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            manifest_json
+                .replace("THIRD_CONTEST", "Mayor")
+                .replace("NORTH_DUPLICATE", ""),
+        )
+        .unwrap();
+
+        let (findings, result) = lint_manifest(&path, &[]);
+        let findings = findings.unwrap();
+        let findings: Vec<_> = findings
+            .iter()
+            .map(|finding| (finding.severity, finding.message.as_str()))
+            .collect();
+        assert_eq!(
+            findings,
+            [
+                (
+                    Warning,
+                    "Contest 2 has selection limit 3, but only 2 options, so at most 2 can be selected."
+                ),
+                (
+                    Warning,
+                    "Contest 3 appears on no ballot style, so it can never be voted on."
+                ),
+                (Error, "Ballot style 2 has no contests."),
+                (
+                    Error,
+                    "The label of contest 3 duplicates that of contest 1: \"Mayor\""
+                ),
+            ]
+        );
+        assert!(result.is_err());
+
+        // With only warnings, the subcommand succeeds.
+        std::fs::write(
+            &path,
+            manifest_json
+                .replace("THIRD_CONTEST", "Sheriff")
+                .replace("NORTH_DUPLICATE", ""),
+        )
+        .unwrap();
+
+        let (findings, result) = lint_manifest(&path, &["--allow-empty-ballot-styles"]);
+        let findings = findings.unwrap();
+        assert_eq!(findings.len(), 3);
+        assert!(findings.iter().all(|finding| finding.severity == Warning));
+        assert!(result.is_ok());
+
+        // A ballot style listing a contest twice can't be read at all.
+        std::fs::write(
+            &path,
+            manifest_json
+                .replace("THIRD_CONTEST", "Sheriff")
+                .replace("NORTH_DUPLICATE", ", 2"),
+        )
+        .unwrap();
+
+        let (findings, result) = lint_manifest(&path, &[]);
+        let err = findings.unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Ballot style 1 lists contest 2 more than once."),
+            "{err:#}"
+        );
+        assert!(result.is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
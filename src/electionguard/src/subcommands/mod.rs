@@ -8,6 +8,7 @@
 mod guardian_secret_key_generate;
 //? TODO mod guardian_secret_key_write_encrypted_share;
 mod guardian_secret_key_write_public_key;
+mod lint_manifest;
 mod none;
 mod preencrypted_ballot_generate;
 mod preencrypted_ballot_record;
@@ -52,6 +53,10 @@ pub(crate) enum Subcommands {
     /// Write the election manifest to a file.
     WriteManifest(crate::subcommands::write_manifest::WriteManifest),
 
+    /// Lint the election manifest, printing every finding with its severity.
+    /// Fails only if a finding is an error.
+    LintManifest(crate::subcommands::lint_manifest::LintManifest),
+
     /// Write the election parameters to a file.
     WriteParameters(crate::subcommands::write_parameters::WriteParameters),
 
@@ -114,6 +119,7 @@ impl<'a> From<&'a mut Subcommands> for &'a mut dyn Subcommand {
             WriteRandomSeed(a) => a,
             VerifyStandardParameters(a) => a,
             WriteManifest(a) => a,
+            LintManifest(a) => a,
             WriteParameters(a) => a,
             WriteHashes(a) => a,
             GuardianSecretKeyGenerate(a) => a,
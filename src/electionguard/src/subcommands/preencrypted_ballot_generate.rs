@@ -9,21 +9,13 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{bail, Context, Result};
 
-use eg::{
-    ballot_style::BallotStyleIndex, device::Device, election_record::PreVotingData,
-    serializable::SerializablePretty,
-};
+use eg::{ballot_style::BallotStyleIndex, device::Device, serializable::SerializablePretty};
 use preencrypted::ballot_encrypting_tool::BallotEncryptingTool;
 use util::file::create_path;
 
 use crate::{
-    artifacts_dir::ArtifactFile,
-    common_utils::{
-        load_election_parameters, load_hashes, load_hashes_ext, load_joint_election_public_key,
-        ElectionManifestSource,
-    },
-    subcommand_helper::SubcommandHelper,
-    subcommands::Subcommand,
+    artifacts_dir::ArtifactFile, common_utils::load_pre_voting_data,
+    subcommand_helper::SubcommandHelper, subcommands::Subcommand,
 };
 
 /// Generate a pre-encrypted ballot.
@@ -50,15 +42,7 @@ impl Subcommand for PreEncryptedBallotGenerate {
     fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
         let mut csprng = subcommand_helper.get_csprng("PreEncryptedBallotGenerate".as_bytes())?;
 
-        //? TODO: Do we need a command line arg to specify the election parameters source?
-        let election_parameters =
-            load_election_parameters(&subcommand_helper.artifacts_dir, &mut csprng)?;
-
-        //? TODO: Do we need a command line arg to specify the election manifest source?
-        let election_manifest_source =
-            ElectionManifestSource::ArtifactFileElectionManifestCanonical;
-        let election_manifest =
-            election_manifest_source.load_election_manifest(&subcommand_helper.artifacts_dir)?;
+        let pv_data = load_pre_voting_data(&subcommand_helper.artifacts_dir, &mut csprng)?;
 
         if self.ballot_style_index == 0 {
             bail!("Ballot style is required to generate pre-encrypted ballots.");
@@ -68,28 +52,16 @@ impl Subcommand for PreEncryptedBallotGenerate {
         let ballot_style_index =
             BallotStyleIndex::from_one_based_index(self.ballot_style_index).unwrap();
 
-        let hashes = load_hashes(&subcommand_helper.artifacts_dir)?;
-        let hashes_ext = load_hashes_ext(&subcommand_helper.artifacts_dir)?;
-        let jepk =
-            load_joint_election_public_key(&subcommand_helper.artifacts_dir, &election_parameters)?;
-
-        let pv_data = PreVotingData::new(
-            election_manifest,
-            election_parameters,
-            hashes,
-            hashes_ext,
-            jepk,
-        );
-
-        let (mut bx_write, path) = subcommand_helper
-            .artifacts_dir
-            .out_file_stdiowrite(&None, Some(ArtifactFile::ElectionPreVotingData))?;
-
+        let mut bytes = Vec::new();
         pv_data
-            .to_stdiowrite_pretty(bx_write.as_mut())
-            .with_context(|| format!("Writing record header to: {}", path.display()))?;
+            .to_stdiowrite_pretty(&mut bytes)
+            .context("Serializing record header")?;
 
-        drop(bx_write);
+        subcommand_helper.artifacts_dir.out_file_write_atomic(
+            &None,
+            Some(ArtifactFile::ElectionPreVotingData),
+            &bytes,
+        )?;
 
         let device = Device::new("Ballot Encrypting Tool", pv_data.clone());
 
@@ -115,54 +87,47 @@ impl Subcommand for PreEncryptedBallotGenerate {
         for b_idx in 0..self.num_ballots {
             confirmation_codes.push(ballots[b_idx].confirmation_code);
 
-            let (mut bx_write, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+            let mut bytes = Vec::new();
+            ballots[b_idx]
+                .to_stdiowrite_pretty(&mut bytes)
+                .context("Serializing pre-encrypted ballot")?;
+
+            let path = subcommand_helper.artifacts_dir.out_file_write_atomic(
                 &None,
                 Some(ArtifactFile::PreEncryptedBallot(
                     label as u128,
                     ballots[b_idx].confirmation_code,
                 )),
+                &bytes,
             )?;
 
-            ballots[b_idx]
-                .to_stdiowrite_pretty(bx_write.as_mut())
-                .with_context(|| format!("Writing pre-encrypted ballot to: {}", path.display()))?;
-
             eprintln!("Wrote pre-encrypted ballot to: {}", path.display());
 
-            drop(bx_write);
+            let mut bytes = Vec::new();
+            primary_nonces[b_idx]
+                .to_stdiowrite_pretty(&mut bytes)
+                .context("Serializing pre-encrypted ballot nonce")?;
 
-            let (mut bx_write, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+            subcommand_helper.artifacts_dir.out_file_write_atomic(
                 &None,
                 Some(ArtifactFile::PreEncryptedBallotNonce(
                     label as u128,
                     ballots[b_idx].confirmation_code,
                 )),
+                &bytes,
             )?;
-
-            primary_nonces[b_idx]
-                .to_stdiowrite_pretty(bx_write.as_mut())
-                .with_context(|| {
-                    format!("Writing pre-encrypted ballot nonce to: {}", path.display())
-                })?;
-
-            drop(bx_write);
         }
 
-        let (mut bx_write, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+        let mut bytes = Vec::new();
+        tool.metadata_to_stdiowrite(&confirmation_codes, &mut bytes)
+            .context("Serializing pre-encrypted ballot metadata")?;
+
+        subcommand_helper.artifacts_dir.out_file_write_atomic(
             &None,
             Some(ArtifactFile::PreEncryptedBallotMetadata(label as u128)),
+            &bytes,
         )?;
 
-        tool.metadata_to_stdiowrite(&confirmation_codes, bx_write.as_mut())
-            .with_context(|| {
-                format!(
-                    "Writing pre-encrypted ballot metadata to: {}",
-                    path.display()
-                )
-            })?;
-
-        drop(bx_write);
-
         Ok(())
 
         // TODO: Encrypt them
@@ -8,8 +8,7 @@
 use anyhow::{bail, Context, Result};
 
 use eg::{
-    ballot_style::BallotStyleIndex, device::Device, election_record::PreVotingData, hash::HValue,
-    serializable::SerializablePretty,
+    ballot_style::BallotStyleIndex, device::Device, hash::HValue, serializable::SerializablePretty,
 };
 use preencrypted::{
     ballot::{BallotPreEncrypted, VoterSelection},
@@ -18,13 +17,8 @@ use preencrypted::{
 use util::file::create_path;
 
 use crate::{
-    artifacts_dir::ArtifactFile,
-    common_utils::{
-        load_election_parameters, load_hashes, load_hashes_ext, load_joint_election_public_key,
-        ElectionManifestSource,
-    },
-    subcommand_helper::SubcommandHelper,
-    subcommands::Subcommand,
+    artifacts_dir::ArtifactFile, common_utils::load_pre_voting_data,
+    subcommand_helper::SubcommandHelper, subcommands::Subcommand,
 };
 
 /// Record voter selections on a pre-encrypted ballot.
@@ -55,15 +49,7 @@ impl Subcommand for PreEncryptedBallotRecord {
     fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
         let mut csprng = subcommand_helper.get_csprng("PreEncryptedBallotGenerate".as_bytes())?;
 
-        //? TODO: Do we need a command line arg to specify the election parameters source?
-        let election_parameters =
-            load_election_parameters(&subcommand_helper.artifacts_dir, &mut csprng)?;
-
-        //? TODO: Do we need a command line arg to specify the election manifest source?
-        let election_manifest_source =
-            ElectionManifestSource::ArtifactFileElectionManifestCanonical;
-        let election_manifest =
-            election_manifest_source.load_election_manifest(&subcommand_helper.artifacts_dir)?;
+        let record_header = load_pre_voting_data(&subcommand_helper.artifacts_dir, &mut csprng)?;
 
         if self.ballot_style_index == 0 {
             bail!("Ballot style is required to record pre-encrypted ballots.");
@@ -73,18 +59,6 @@ impl Subcommand for PreEncryptedBallotRecord {
         let ballot_style_index =
             BallotStyleIndex::from_one_based_index(self.ballot_style_index).unwrap();
 
-        let hashes = load_hashes(&subcommand_helper.artifacts_dir)?;
-        let hashes_ext = load_hashes_ext(&subcommand_helper.artifacts_dir)?;
-        let jepk =
-            load_joint_election_public_key(&subcommand_helper.artifacts_dir, &election_parameters)?;
-
-        let record_header = PreVotingData::new(
-            election_manifest,
-            election_parameters.clone(),
-            hashes,
-            hashes_ext,
-            jepk,
-        );
         let device = Device::new("Ballot Recording Tool", record_header.clone());
         let tool = BallotRecordingTool::new(record_header.clone(), ballot_style_index);
 
@@ -147,18 +121,19 @@ impl Subcommand for PreEncryptedBallotRecord {
                 let encrypted_ballot =
                     regenerated_ballot.finalize(&device, &mut csprng, &voter_ballot)?;
 
-                let (mut bx_write, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+                let mut bytes = Vec::new();
+                encrypted_ballot
+                    .to_stdiowrite_pretty(&mut bytes)
+                    .context("Serializing encrypted ballot")?;
+
+                subcommand_helper.artifacts_dir.out_file_write_atomic(
                     &None,
                     Some(ArtifactFile::EncryptedBallot(
                         self.ballots_in,
                         encrypted_ballot.confirmation_code,
                     )),
+                    &bytes,
                 )?;
-
-                encrypted_ballot
-                    .to_stdiowrite_pretty(bx_write.as_mut())
-                    .with_context(|| format!("Writing encrypted ballot to: {}", path.display()))?;
-                drop(bx_write);
             } else {
                 eprintln!(
                     "Regenerated ballot with nonce {} does not match ballot {}",
@@ -5,17 +5,35 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
-use anyhow::{Context, Result};
+use std::path::PathBuf;
 
-use eg::standard_parameters::STANDARD_PARAMETERS;
+use anyhow::{ensure, Context, Result};
 
-use crate::{subcommand_helper::SubcommandHelper, subcommands::Subcommand};
+use eg::{fixed_parameters::FixedParametersVerification, serializable::SerializablePretty};
+
+use crate::{
+    artifacts_dir::ArtifactFile, common_utils::load_fixed_parameters,
+    subcommand_helper::SubcommandHelper, subcommands::Subcommand,
+};
 
 /// Verify the standard parameters.
 #[derive(clap::Args, Debug)]
 pub(crate) struct VerifyStandardParameters {
     #[arg(long, default_value_t = 1)]
     passes: usize,
+
+    /// File to which to write the record of the checks made and their results.
+    /// Default is the standard parameters verification file in the artifacts dir.
+    /// If "-", write to stdout.
+    #[arg(long)]
+    out_file: Option<PathBuf>,
+
+    /// Instead of verifying the parameters again, rely on the verification file (the out file,
+    /// or by default the standard parameters verification file in the artifacts dir) if it
+    /// records a complete, passed verification of the same parameters. The file is not signed,
+    /// so use this only if it was written by a trusted run.
+    #[arg(long)]
+    reuse: bool,
 }
 
 impl Subcommand for VerifyStandardParameters {
@@ -26,18 +44,81 @@ impl Subcommand for VerifyStandardParameters {
     fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
         let mut csprng = subcommand_helper.get_csprng(b"VerifyStandardParameters")?;
 
-        eprint!("Initializing standard parameters...");
-        let fixed_parameters = &*STANDARD_PARAMETERS;
+        eprint!("Initializing parameters...");
+        let fixed_parameters = &load_fixed_parameters(
+            &subcommand_helper.clargs.fixed_parameters,
+            &subcommand_helper.artifacts_dir,
+        )?;
         eprintln!("Done.");
 
+        // On request, a passed verification of the same parameters, as identified by `H_P`,
+        // is reused.
+        if self.reuse {
+            let path = match &self.out_file {
+                Some(path) => path.clone(),
+                None => subcommand_helper
+                    .artifacts_dir
+                    .path(ArtifactFile::StandardParametersVerification),
+            };
+            ensure!(
+                path.as_os_str() != "-",
+                "Can't reuse a verification written to stdout"
+            );
+
+            let reuse = path.is_file() && {
+                let (mut stdioread, _) = subcommand_helper
+                    .artifacts_dir
+                    .in_file_stdioread(&Some(path.clone()), None)?;
+                FixedParametersVerification::from_stdioread(stdioread.as_mut())
+                    .is_ok_and(|previous| previous.is_passed_verification_of(fixed_parameters))
+            };
+            if reuse {
+                eprintln!(
+                    "Reusing the passed verification of the same parameters in: {}",
+                    path.display()
+                );
+                return Ok(());
+            }
+            eprintln!(
+                "{} is not a passed verification of these parameters.",
+                path.display()
+            );
+        }
+
+        // The record of the last pass is written, unless an earlier one failed.
         eprintln!("Verifying standard parameters...");
-        for pass in 0..self.passes {
+        eprintln!("    Starting pass 0/{}...", self.passes);
+        let mut verification = FixedParametersVerification::compute(fixed_parameters, &mut csprng);
+        for pass in 1..self.passes {
+            if !verification.all_passed() {
+                break;
+            }
             eprintln!("    Starting pass {pass}/{}...", self.passes);
-            fixed_parameters
-                .validate(&mut csprng)
-                .context("Parameter verification failed")?;
+            verification = FixedParametersVerification::compute(fixed_parameters, &mut csprng);
         }
 
+        let mut bytes = Vec::new();
+        verification
+            .to_stdiowrite_pretty(&mut bytes)
+            .context("Serializing standard parameters verification")?;
+
+        let path = subcommand_helper.artifacts_dir.out_file_write_atomic(
+            &self.out_file,
+            Some(ArtifactFile::StandardParametersVerification),
+            &bytes,
+        )?;
+
+        eprintln!(
+            "Wrote standard parameters verification to: {}",
+            path.display()
+        );
+
+        ensure!(
+            verification.all_passed(),
+            "Parameter verification failed, see: {}",
+            path.display()
+        );
+
         eprintln!("Done.");
 
         Ok(())
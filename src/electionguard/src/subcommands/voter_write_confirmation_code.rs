@@ -32,18 +32,18 @@ impl Subcommand for VoterWriteConfirmationCode {
     fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
         // match VoterConfirmationQRCode::new(&self.code) {
         //     Some(qr_code) => {
-        //         let (mut bx_write, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+        //         let mut bytes = Vec::new();
+        //         qr_code
+        //             .to_stdiowrite(&mut bytes)
+        //             .context("Serializing voter confirmation QR code")?;
+
+        //         let path = subcommand_helper.artifacts_dir.out_file_write_atomic(
         //             &self.out_file,
         //             Some(ArtifactFile::VoterConfirmationCode(
         //                 HValue::from_str(&self.code).unwrap(),
         //             )),
+        //             &bytes,
         //         )?;
-
-        //         qr_code.to_stdiowrite(bx_write.as_mut()).with_context(|| {
-        //             format!("Writing voter confirmation QR code to: {}", path.display())
-        //         })?;
-
-        //         drop(bx_write);
         //     }
         //     None => {}
         // }
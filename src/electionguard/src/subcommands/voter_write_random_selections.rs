@@ -14,17 +14,11 @@ use std::{
 use anyhow::{Context, Result};
 use eg::{
     ballot::BallotEncrypted, ballot_style::BallotStyleIndex, contest_selection::ContestSelection,
-    device::Device, election_manifest::ContestIndex, election_record::PreVotingData,
-    serializable::SerializablePretty,
+    device::Device, election_manifest::ContestIndex, serializable::SerializablePretty,
 };
 
 use crate::{
-    artifacts_dir::ArtifactFile,
-    common_utils::{
-        load_election_parameters, load_hashes, load_hashes_ext, load_joint_election_public_key,
-        ElectionManifestSource,
-    },
-    subcommands::Subcommand,
+    artifacts_dir::ArtifactFile, common_utils::load_pre_voting_data, subcommands::Subcommand,
 };
 
 #[derive(clap::Args, Debug, Default)]
@@ -46,32 +40,11 @@ impl Subcommand for VoterWriteRandomSelection {
     ) -> Result<()> {
         let mut csprng = subcommand_helper.get_csprng(b"VoterWriteRandomSelection")?;
 
-        //? TODO: Do we need a command line arg to specify the election parameters source?
-        let election_parameters =
-            load_election_parameters(&subcommand_helper.artifacts_dir, &mut csprng)?;
-
-        //? TODO: Do we need a command line arg to specify the election manifest source?
-        let election_manifest_source =
-            ElectionManifestSource::ArtifactFileElectionManifestCanonical;
-        let election_manifest =
-            election_manifest_source.load_election_manifest(&subcommand_helper.artifacts_dir)?;
-
-        let hashes = load_hashes(&subcommand_helper.artifacts_dir)?;
-        let hashes_ext = load_hashes_ext(&subcommand_helper.artifacts_dir)?;
-        let jepk =
-            load_joint_election_public_key(&subcommand_helper.artifacts_dir, &election_parameters)?;
-
-        let record_header = PreVotingData::new(
-            election_manifest.clone(),
-            election_parameters,
-            hashes,
-            hashes_ext,
-            jepk,
-        );
+        let record_header = load_pre_voting_data(&subcommand_helper.artifacts_dir, &mut csprng)?;
         let device = Device::new("Ballot Recording Tool", record_header.clone());
 
         let mut contest_selections = BTreeMap::new();
-        for (i, c) in (1u32..).zip(election_manifest.contests) {
+        for (i, c) in (1u32..).zip(record_header.manifest.contests.iter()) {
             let selection =
                 ContestSelection::new_pick_random(&mut csprng, c.selection_limit, c.options.len());
             contest_selections.insert(ContestIndex::from_one_based_index_unchecked(i), selection);
@@ -90,20 +63,20 @@ impl Subcommand for VoterWriteRandomSelection {
         // distinct from `ballot.date`
         let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
-        let (mut stdiowrite, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+        let mut bytes = Vec::new();
+        ballot
+            .to_stdiowrite_pretty(&mut bytes)
+            .context("Serializing ballot")?;
+
+        let path = subcommand_helper.artifacts_dir.out_file_write_atomic(
             &self.out_file,
             Some(ArtifactFile::EncryptedBallot(
                 timestamp as u128,
                 ballot.confirmation_code,
             )),
+            &bytes,
         )?;
 
-        ballot
-            .to_stdiowrite_pretty(stdiowrite.as_mut())
-            .with_context(|| format!("Writing ballot to: {}", path.display()))?;
-
-        drop(stdiowrite);
-
         eprintln!("Wrote ballot to: {}", path.display());
 
         Ok(())
@@ -51,15 +51,16 @@ impl Subcommand for WriteHashes {
 
         let hashes = Hashes::compute(&election_parameters, &election_manifest)?;
 
-        let (mut stdiowrite, path) = subcommand_helper
-            .artifacts_dir
-            .out_file_stdiowrite(&self.out_file, Some(ArtifactFile::Hashes))?;
-
+        let mut bytes = Vec::new();
         hashes
-            .to_stdiowrite_pretty(stdiowrite.as_mut())
-            .with_context(|| format!("Writing hashes to: {}", path.display()))?;
-
-        drop(stdiowrite);
+            .to_stdiowrite_pretty(&mut bytes)
+            .context("Serializing hashes")?;
+
+        let path = subcommand_helper.artifacts_dir.out_file_write_atomic(
+            &self.out_file,
+            Some(ArtifactFile::Hashes),
+            &bytes,
+        )?;
 
         eprintln!("Wrote hashes to: {}", path.display());
 
@@ -49,15 +49,16 @@ impl Subcommand for WriteHashesExt {
         let hashes_ext =
             HashesExt::compute(&election_parameters, &hashes, &joint_election_public_key);
 
-        let (mut stdiowrite, path) = subcommand_helper
-            .artifacts_dir
-            .out_file_stdiowrite(&self.out_file, Some(ArtifactFile::HashesExt))?;
-
+        let mut bytes = Vec::new();
         hashes_ext
-            .to_stdiowrite_pretty(stdiowrite.as_mut())
-            .with_context(|| format!("Writing hashes ext to: {}", path.display()))?;
-
-        drop(stdiowrite);
+            .to_stdiowrite_pretty(&mut bytes)
+            .context("Serializing hashes ext")?;
+
+        let path = subcommand_helper.artifacts_dir.out_file_write_atomic(
+            &self.out_file,
+            Some(ArtifactFile::HashesExt),
+            &bytes,
+        )?;
 
         eprintln!("Wrote hashes ext to: {}", path.display());
 
@@ -48,15 +48,16 @@ impl Subcommand for WriteJointElectionPublicKey {
         let joint_election_public_key =
             JointElectionPublicKey::compute(&election_parameters, guardian_public_keys.as_slice())?;
 
-        let (mut stdiowrite, path) = subcommand_helper
-            .artifacts_dir
-            .out_file_stdiowrite(&self.out_file, Some(ArtifactFile::JointElectionPublicKey))?;
-
+        let mut bytes = Vec::new();
         joint_election_public_key
-            .to_stdiowrite_pretty(stdiowrite.as_mut())
-            .with_context(|| format!("Writing joint election public key to: {}", path.display()))?;
-
-        drop(stdiowrite);
+            .to_stdiowrite_pretty(&mut bytes)
+            .context("Serializing joint election public key")?;
+
+        let path = subcommand_helper.artifacts_dir.out_file_write_atomic(
+            &self.out_file,
+            Some(ArtifactFile::JointElectionPublicKey),
+            &bytes,
+        )?;
 
         eprintln!("Wrote joint election public key to: {}", path.display());
 
@@ -87,21 +87,19 @@ impl Subcommand for WriteManifest {
             Pretty => (ArtifactFile::ElectionManifestPretty, "pretty JSON"),
         };
 
-        let (mut stdiowrite, path) = subcommand_helper
-            .artifacts_dir
-            .out_file_stdiowrite(&self.out_file, Some(artifact_file))?;
-
-        let write_result = match self.out_format {
-            Canonical => election_manifest.to_stdiowrite_canonical(&mut stdiowrite),
-            Pretty => election_manifest.to_stdiowrite_pretty(&mut stdiowrite),
+        let mut bytes = Vec::new();
+        let serialize_result = match self.out_format {
+            Canonical => election_manifest.to_stdiowrite_canonical(&mut bytes),
+            Pretty => election_manifest.to_stdiowrite_pretty(&mut bytes),
         };
 
-        write_result.with_context(|| {
-            format!(
-                "Writing election manifest {description} to: {}",
-                path.display()
-            )
-        })?;
+        serialize_result.with_context(|| format!("Serializing election manifest {description}"))?;
+
+        let path = subcommand_helper.artifacts_dir.out_file_write_atomic(
+            &self.out_file,
+            Some(artifact_file),
+            &bytes,
+        )?;
 
         eprintln!(
             "Wrote election manifest {description} to: {}",
@@ -10,13 +10,15 @@ use std::path::PathBuf;
 use anyhow::{Context, Result};
 
 use eg::{
-    election_parameters::ElectionParameters, guardian::GuardianIndex,
-    serializable::SerializablePretty, standard_parameters::STANDARD_PARAMETERS,
+    election_parameters::{ElectionParameters, ELECTION_PARAMETERS_SCHEMA_VERSION},
+    guardian::GuardianIndex,
+    serializable::SerializablePretty,
     varying_parameters::VaryingParameters,
 };
 
 use crate::{
-    artifacts_dir::ArtifactFile, subcommand_helper::SubcommandHelper, subcommands::Subcommand,
+    artifacts_dir::ArtifactFile, common_utils::load_fixed_parameters,
+    subcommand_helper::SubcommandHelper, subcommands::Subcommand,
 };
 
 #[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
@@ -72,9 +74,10 @@ impl Subcommand for WriteParameters {
     }
 
     fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
-        // eprint!("Initializing standard parameters...");
-        let fixed_parameters = STANDARD_PARAMETERS.clone();
-        // eprintln!("Done.");
+        let fixed_parameters = load_fixed_parameters(
+            &subcommand_helper.clargs.fixed_parameters,
+            &subcommand_helper.artifacts_dir,
+        )?;
 
         let varying_parameters = VaryingParameters {
             n: self.n,
@@ -85,19 +88,21 @@ impl Subcommand for WriteParameters {
         };
 
         let election_parameters = ElectionParameters {
+            schema_version: ELECTION_PARAMETERS_SCHEMA_VERSION,
             fixed_parameters,
             varying_parameters,
         };
 
-        let (mut stdiowrite, path) = subcommand_helper
-            .artifacts_dir
-            .out_file_stdiowrite(&self.out_file, Some(ArtifactFile::ElectionParameters))?;
-
+        let mut bytes = Vec::new();
         election_parameters
-            .to_stdiowrite_pretty(stdiowrite.as_mut())
-            .with_context(|| format!("Writing election parameters to: {}", path.display()))?;
-
-        drop(stdiowrite);
+            .to_stdiowrite_pretty(&mut bytes)
+            .context("Serializing election parameters")?;
+
+        let path = subcommand_helper.artifacts_dir.out_file_write_atomic(
+            &self.out_file,
+            Some(ArtifactFile::ElectionParameters),
+            &bytes,
+        )?;
 
         eprintln!("Wrote election parameters to: {}", path.display());
 
@@ -7,7 +7,12 @@
 
 use std::{collections::BTreeMap, fs, path::PathBuf};
 
-use crate::{confirmation_code::confirmation_code, contest::ContestPreEncrypted};
+use crate::{
+    ballot_encrypting_tool::BallotEncryptingTool,
+    confirmation_code::confirmation_code,
+    contest::ContestPreEncrypted,
+    contest_selection::{ContestSelectionPreEncrypted, ContestSelectionPreEncryptedIndex},
+};
 use anyhow::{anyhow, Context, Result};
 use eg::{
     ballot::{BallotEncrypted, BallotEncryptedError, BallotState},
@@ -17,13 +22,50 @@ use eg::{
     election_manifest::{ContestIndex, ElectionManifest},
     election_record::PreVotingData,
     hash::HValue,
+    joint_election_public_key::Ciphertext,
     serializable::SerializablePretty,
     vec1::Vec1,
 };
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use util::{csprng::Csprng, logging::Logging};
 // use voter::ballot::BallotSelections;
 
+/// The reason the selection hashes of a [`BallotPreEncrypted`] do not correspond to its contest
+/// options, as found by [`BallotPreEncrypted::verify_selection_hashes`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BallotPreEncryptedError {
+    /// A contest of the ballot is not in the election manifest
+    #[error("Contest {contest_ix} of the pre-encrypted ballot is not in the election manifest.")]
+    ContestNotInManifest { contest_ix: ContestIndex },
+
+    /// A contest of the ballot does not have one selection per option and null selection
+    #[error(
+        "Contest {contest_ix} has {actual} pre-encrypted selections, but {expected} are required."
+    )]
+    SelectionCountMismatch {
+        contest_ix: ContestIndex,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A selection does not have one ciphertext per option and null selection
+    #[error("Selection {selection_ix} of contest {contest_ix} has {actual} ciphertexts, but {expected} are required.")]
+    CiphertextCountMismatch {
+        contest_ix: ContestIndex,
+        selection_ix: ContestSelectionPreEncryptedIndex,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// The selection hash of a selection is not that of its ciphertexts
+    #[error("The selection hash of selection {selection_ix} of contest {contest_ix} does not match its ciphertexts.")]
+    SelectionHashMismatch {
+        contest_ix: ContestIndex,
+        selection_ix: ContestSelectionPreEncryptedIndex,
+    },
+}
+
 /// A pre-encrypted ballot.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BallotPreEncrypted {
@@ -184,24 +226,32 @@ impl BallotPreEncrypted {
                 .contests
                 .get(correct_content_index)
                 .unwrap();
-            contests
-                .insert(
-                    correct_content_index,
-                    contest
-                        .finalize(
-                            device,
-                            csprng,
-                            voter_ballot.selections.get(c_idx).unwrap().get_vote(),
-                            c.selection_limit,
-                            c.options.len(),
-                        )
-                        .map_err(|err| BallotEncryptedError::ProofError { err })?,
-                )
-                .unwrap();
+            contests.insert(
+                correct_content_index,
+                contest
+                    .finalize(
+                        device,
+                        csprng,
+                        voter_ballot.selections.get(c_idx).unwrap().get_vote(),
+                        c,
+                    )
+                    .map_err(|err| BallotEncryptedError::ProofError { err })?,
+            );
         }
 
+        let ballot_style_hash = device
+            .header
+            .manifest
+            .ballot_styles
+            .get(self.ballot_style_index)
+            .ok_or(BallotEncryptedError::BallotStyleNotInManifest {
+                ballot_style_ix: self.ballot_style_index,
+            })?
+            .content_hash();
+
         Ok(BallotEncrypted::new(
             self.ballot_style_index,
+            ballot_style_hash,
             &contests,
             BallotState::Cast,
             self.confirmation_code,
@@ -210,6 +260,74 @@ impl BallotPreEncrypted {
         ))
     }
 
+    /// Verifies that each contest of the ballot has one pre-encrypted selection per option of
+    /// the contest in the manifest, plus one null selection per allowed selection, and that the
+    /// selection hash `ψ` of each selection is that of its ciphertexts (Equation 93/94).
+    ///
+    /// Returns the first mismatch, by contest and selection. This applies to a ballot as produced
+    /// by the [`BallotEncryptingTool`] as well as to one read back from its serialization.
+    pub fn verify_selection_hashes(
+        &self,
+        header: &PreVotingData,
+    ) -> Result<(), BallotPreEncryptedError> {
+        for contest in self.contests.iter() {
+            let contest_ix = contest.contest_index;
+            let manifest_contest = header
+                .manifest
+                .contests
+                .get(contest_ix)
+                .ok_or(BallotPreEncryptedError::ContestNotInManifest { contest_ix })?;
+
+            let expected = manifest_contest.options.len() + manifest_contest.selection_limit;
+            if contest.selections.len() != expected {
+                return Err(BallotPreEncryptedError::SelectionCountMismatch {
+                    contest_ix,
+                    expected,
+                    actual: contest.selections.len(),
+                });
+            }
+
+            for (selection_ix, selection) in
+                contest.selections.indices().zip(contest.selections.iter())
+            {
+                Self::verify_selection_hash(header, contest_ix, selection_ix, selection, expected)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn verify_selection_hash(
+        header: &PreVotingData,
+        contest_ix: ContestIndex,
+        selection_ix: ContestSelectionPreEncryptedIndex,
+        selection: &ContestSelectionPreEncrypted,
+        expected: usize,
+    ) -> Result<(), BallotPreEncryptedError> {
+        if selection.selections.len() != expected {
+            return Err(BallotPreEncryptedError::CiphertextCountMismatch {
+                contest_ix,
+                selection_ix,
+                expected,
+                actual: selection.selections.len(),
+            });
+        }
+
+        let ciphertexts: Vec<Ciphertext> = selection
+            .selections
+            .iter()
+            .map(|(ct, _)| ct.clone())
+            .collect();
+        if BallotEncryptingTool::selection_hash(header, &ciphertexts) != selection.selection_hash {
+            return Err(BallotPreEncryptedError::SelectionHashMismatch {
+                contest_ix,
+                selection_ix,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Reads `BallotPreEncrypted` from a `std::io::Read`.
     pub fn from_reader(io_read: &mut dyn std::io::Read) -> Result<BallotPreEncrypted> {
         serde_json::from_reader(io_read)
@@ -226,3 +344,140 @@ impl BallotPreEncrypted {
 }
 
 impl SerializablePretty for VoterSelection {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use eg::example_pre_voting_data::example_pre_voting_data;
+
+    #[test]
+    fn test_verify_selection_hashes() {
+        let mut csprng = Csprng::new(b"test_verify_selection_hashes");
+        let header = example_pre_voting_data(&mut csprng).unwrap();
+
+        let ballot_style_index = BallotStyleIndex::from_one_based_index(1).unwrap();
+        let (mut ballot, _) =
+            BallotPreEncrypted::new(&header, ballot_style_index, &mut csprng, true);
+        assert_eq!(ballot.verify_selection_hashes(&header), Ok(()));
+
+        // Corrupt the selection hash of the second selection of the first contest.
+        let contest = ballot
+            .contests
+            .get_mut(ContestIndex::from_one_based_index(1).unwrap())
+            .unwrap();
+        let contest_ix = contest.contest_index;
+        let selection_ix = ContestSelectionPreEncryptedIndex::from_one_based_index(2).unwrap();
+        contest
+            .selections
+            .get_mut(selection_ix)
+            .unwrap()
+            .selection_hash = HValue::default();
+        assert_eq!(
+            ballot.verify_selection_hashes(&header),
+            Err(BallotPreEncryptedError::SelectionHashMismatch {
+                contest_ix,
+                selection_ix,
+            })
+        );
+
+        // A ballot read back from JSON, which omits the nonces, is verified the same way.
+        let json = ballot.to_json_pretty();
+        let mut ballot = BallotPreEncrypted::from_stdioread(&mut json.as_bytes()).unwrap();
+        assert_eq!(
+            ballot.verify_selection_hashes(&header),
+            Err(BallotPreEncryptedError::SelectionHashMismatch {
+                contest_ix,
+                selection_ix,
+            })
+        );
+
+        let selection = ballot
+            .contests
+            .get_mut(ContestIndex::from_one_based_index(1).unwrap())
+            .unwrap()
+            .selections
+            .get_mut(selection_ix)
+            .unwrap();
+        let ciphertexts: Vec<Ciphertext> = selection
+            .selections
+            .iter()
+            .map(|(ciphertext, nonce)| {
+                assert!(nonce.is_none());
+                ciphertext.clone()
+            })
+            .collect();
+        selection.selection_hash = BallotEncryptingTool::selection_hash(&header, &ciphertexts);
+        assert_eq!(ballot.verify_selection_hashes(&header), Ok(()));
+    }
+
+    #[test]
+    fn test_finalize_exactly_one() {
+        use eg::{
+            example_election_manifest::example_election_manifest,
+            example_election_parameters::example_election_parameters,
+            example_pre_voting_data::example_pre_voting_data_with, zk::ProofRangeError,
+        };
+
+        // Contest 1 of ballot style 1 requires exactly one selection.
+        let contest_ix_1 = ContestIndex::from_one_based_index(1).unwrap();
+        let mut election_manifest = example_election_manifest();
+        election_manifest
+            .contests
+            .get_mut(contest_ix_1)
+            .unwrap()
+            .exactly_one = true;
+        election_manifest.validate().unwrap();
+
+        let mut csprng = Csprng::new(b"test_finalize_exactly_one");
+        let (_, header) = example_pre_voting_data_with(
+            &mut csprng,
+            example_election_parameters(),
+            election_manifest,
+        )
+        .unwrap();
+        let device = Device::new("Some encryption device", header);
+
+        let ballot_style_index = BallotStyleIndex::from_one_based_index(1).unwrap();
+        let (ballot, _) =
+            BallotPreEncrypted::new(&device.header, ballot_style_index, &mut csprng, true);
+
+        let mut voter_ballot = VoterSelection::new_pick_random(
+            &device.header.manifest,
+            ballot_style_index,
+            &mut csprng,
+        );
+        let cnt_options = device
+            .header
+            .manifest
+            .contests
+            .get(contest_ix_1)
+            .unwrap()
+            .options
+            .len();
+        let mut finalize = |vote: Vec<u8>| {
+            *voter_ballot.selections.get_mut(contest_ix_1).unwrap() =
+                ContestSelection::new(vote).unwrap();
+            ballot.finalize(&device, &mut csprng, &voter_ballot)
+        };
+
+        // A single selection is proven to be exactly one.
+        let mut vote = vec![0; cnt_options];
+        vote[0] = 1;
+        let ballot_encrypted = finalize(vote).unwrap();
+        let contest_encrypted = ballot_encrypted.contests().get(&contest_ix_1).unwrap();
+        assert!(contest_encrypted.verify_exactly_one(&device.header));
+
+        // No selection can't be proven, even though a null selection makes up the count.
+        let err = finalize(vec![0; cnt_options]).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                BallotEncryptedError::ProofError {
+                    err: ProofRangeError::NotExactlyOne { small_l: 0 }
+                }
+            ),
+            "{err}"
+        );
+    }
+}
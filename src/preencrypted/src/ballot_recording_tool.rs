@@ -8,10 +8,37 @@
 use std::str::FromStr;
 
 use anyhow::Result;
+use thiserror::Error;
 
-use eg::{ballot_style::BallotStyleIndex, election_record::PreVotingData, hash::HValue};
+use eg::{
+    ballot_style::BallotStyleIndex, election_manifest::ContestIndex,
+    election_record::PreVotingData, hash::HValue,
+};
 
-use crate::ballot::BallotPreEncrypted;
+use crate::{ballot::BallotPreEncrypted, contest_selection::ContestSelectionPreEncryptedIndex};
+
+/// Represents errors occurring when recording the short codes marked by a voter.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RecordingError {
+    /// The contest is not on the pre-encrypted ballot
+    #[error("Contest {contest_ix} is not on the pre-encrypted ballot.")]
+    ContestNotOnBallot { contest_ix: ContestIndex },
+
+    /// No selection of the contest has the short code
+    #[error("The short code {code:?} matches no selection of contest {contest_ix}.")]
+    UnknownCode {
+        contest_ix: ContestIndex,
+        code: String,
+    },
+
+    /// Several selections of the contest have the short code
+    #[error("The short code {code:?} matches selections {candidates:?} of contest {contest_ix}.")]
+    AmbiguousCode {
+        contest_ix: ContestIndex,
+        code: String,
+        candidates: Vec<ContestSelectionPreEncryptedIndex>,
+    },
+}
 
 pub struct BallotRecordingTool {
     /// The election record header.
@@ -55,6 +82,42 @@ impl BallotRecordingTool {
         (Some(regenerated_ballot), true)
     }
 
+    /// Looks up the selection of a contest of a pre-encrypted ballot having the short code marked
+    /// by the voter. Short codes are compared ignoring ASCII case.
+    ///
+    /// A short code matching no selection, or several, is reported rather than resolved.
+    pub fn lookup_short_code(
+        ballot: &BallotPreEncrypted,
+        contest_ix: ContestIndex,
+        code: &str,
+    ) -> Result<ContestSelectionPreEncryptedIndex, RecordingError> {
+        let contest = ballot
+            .contests
+            .iter()
+            .find(|contest| contest.contest_index == contest_ix)
+            .ok_or(RecordingError::ContestNotOnBallot { contest_ix })?;
+
+        let candidates: Vec<_> = contest
+            .selections
+            .iter()
+            .filter(|selection| selection.shortcode.eq_ignore_ascii_case(code))
+            .map(|selection| selection.index)
+            .collect();
+
+        match candidates.as_slice() {
+            [] => Err(RecordingError::UnknownCode {
+                contest_ix,
+                code: code.to_string(),
+            }),
+            [selection_ix] => Ok(*selection_ix),
+            _ => Err(RecordingError::AmbiguousCode {
+                contest_ix,
+                code: code.to_string(),
+                candidates,
+            }),
+        }
+    }
+
     /// Reads a list of confirmation codes from a file.
     pub fn metadata_from_stdioread(
         &self,
@@ -75,3 +138,88 @@ impl BallotRecordingTool {
             .collect())
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use eg::vec1::Vec1;
+
+    use super::*;
+    use crate::{contest::ContestPreEncrypted, contest_selection::ContestSelectionPreEncrypted};
+
+    /// A pre-encrypted ballot with one contest, whose selections have the given short codes.
+    fn ballot_with_shortcodes(shortcodes: &[&str]) -> BallotPreEncrypted {
+        let mut selections = Vec1::new();
+        for (ix, &shortcode) in shortcodes.iter().enumerate() {
+            selections
+                .try_push(ContestSelectionPreEncrypted {
+                    index: ContestSelectionPreEncryptedIndex::from_one_based_index(ix as u32 + 1)
+                        .unwrap(),
+                    selections: Vec::new(),
+                    selection_hash: HValue::default(),
+                    shortcode: shortcode.to_string(),
+                })
+                .unwrap();
+        }
+
+        let mut contests = Vec1::new();
+        contests
+            .try_push(ContestPreEncrypted {
+                contest_index: ContestIndex::from_one_based_index(2).unwrap(),
+                selections,
+                contest_hash: HValue::default(),
+            })
+            .unwrap();
+
+        BallotPreEncrypted {
+            ballot_style_index: BallotStyleIndex::from_one_based_index(1).unwrap(),
+            contests,
+            confirmation_code: HValue::default(),
+        }
+    }
+
+    #[test]
+    fn test_lookup_short_code() {
+        let selection_ix =
+            |ix| ContestSelectionPreEncryptedIndex::from_one_based_index(ix).unwrap();
+        let contest_ix = ContestIndex::from_one_based_index(2).unwrap();
+
+        // An unambiguous code resolves to its selection, whatever its case.
+        let ballot = ballot_with_shortcodes(&["3a", "c4", "07"]);
+        assert_eq!(
+            BallotRecordingTool::lookup_short_code(&ballot, contest_ix, "c4"),
+            Ok(selection_ix(2))
+        );
+        assert_eq!(
+            BallotRecordingTool::lookup_short_code(&ballot, contest_ix, "3A"),
+            Ok(selection_ix(1))
+        );
+
+        assert_eq!(
+            BallotRecordingTool::lookup_short_code(&ballot, contest_ix, "ff"),
+            Err(RecordingError::UnknownCode {
+                contest_ix,
+                code: "ff".to_string()
+            })
+        );
+
+        let other_contest_ix = ContestIndex::from_one_based_index(1).unwrap();
+        assert_eq!(
+            BallotRecordingTool::lookup_short_code(&ballot, other_contest_ix, "c4"),
+            Err(RecordingError::ContestNotOnBallot {
+                contest_ix: other_contest_ix
+            })
+        );
+
+        // A ballot whose short codes collide.
+        let ballot = ballot_with_shortcodes(&["3a", "c4", "3a"]);
+        assert_eq!(
+            BallotRecordingTool::lookup_short_code(&ballot, contest_ix, "3a"),
+            Err(RecordingError::AmbiguousCode {
+                contest_ix,
+                code: "3a".to_string(),
+                candidates: vec![selection_ix(1), selection_ix(3)],
+            })
+        );
+    }
+}
@@ -5,12 +5,18 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
+use std::collections::BTreeMap;
+
 use eg::{
+    election_manifest::ContestIndex,
     hash::{eg_h, HValue},
     vec1::Vec1,
 };
 
-use crate::contest::ContestPreEncrypted;
+use crate::{
+    ballot::BallotPreEncrypted, contest::ContestPreEncrypted,
+    contest_selection::ContestSelectionPreEncryptedIndex,
+};
 
 /// Confirmation code for a pre-encrypted ballot (Equation 96)
 ///
@@ -31,3 +37,135 @@ pub fn confirmation_code(
     v.extend_from_slice(b_aux);
     eg_h(h_e, &v)
 }
+
+/// Selections of a contest of a pre-encrypted ballot which share a short code, so that a voter
+/// marking the short code is ambiguous. Found by [`find_short_code_collisions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortCodeCollision {
+    /// The position of the ballot in the batch.
+    pub ballot: usize,
+
+    /// The index of the contest in the election manifest.
+    pub contest_index: ContestIndex,
+
+    /// The shared short code.
+    pub shortcode: String,
+
+    /// The selections sharing the short code, in order.
+    pub selections: Vec<ContestSelectionPreEncryptedIndex>,
+}
+
+/// Finds the short codes which are shared by several selections of a contest, across a batch
+/// of pre-encrypted ballots. The ballots reported should be regenerated.
+pub fn find_short_code_collisions(ballots: &[BallotPreEncrypted]) -> Vec<ShortCodeCollision> {
+    let mut collisions = Vec::new();
+    for (ballot, ballot_pre_encrypted) in ballots.iter().enumerate() {
+        for contest in ballot_pre_encrypted.contests.iter() {
+            let mut selections_by_shortcode: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+            for selection in contest.selections.iter() {
+                selections_by_shortcode
+                    .entry(&selection.shortcode)
+                    .or_default()
+                    .push(selection.index);
+            }
+            collisions.extend(
+                selections_by_shortcode
+                    .into_iter()
+                    .filter(|(_, selections)| 1 < selections.len())
+                    .map(|(shortcode, selections)| ShortCodeCollision {
+                        ballot,
+                        contest_index: contest.contest_index,
+                        shortcode: shortcode.to_string(),
+                        selections,
+                    }),
+            );
+        }
+    }
+    collisions
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use eg::{
+        ballot_style::{BallotStyle, BallotStyleIndex},
+        election_manifest::{
+            Contest, ContestOption, ElectionManifest, ELECTION_MANIFEST_SCHEMA_VERSION,
+        },
+        example_election_parameters::example_election_parameters,
+        example_pre_voting_data::example_pre_voting_data_with,
+    };
+    use util::csprng::Csprng;
+
+    #[test]
+    fn test_find_short_code_collisions() {
+        let mut csprng = Csprng::new(b"test_find_short_code_collisions");
+
+        // With 25 selections sharing 256 one-byte short codes, collisions are likely.
+        let contest_index = ContestIndex::from_one_based_index(1).unwrap();
+        let election_manifest = ElectionManifest {
+            schema_version: ELECTION_MANIFEST_SCHEMA_VERSION,
+            label: "Short code election".to_string(),
+            contests: [Contest {
+                label: "Contest with many options".to_string(),
+                selection_limit: 1,
+                exactly_one: false,
+                options: (1..=24)
+                    .map(|j| ContestOption::new(&format!("Option {j}")))
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap(),
+            }]
+            .try_into()
+            .unwrap(),
+            ballot_styles: [BallotStyle {
+                label: "Ballot style".to_string(),
+                contests: BTreeSet::from([contest_index]),
+            }]
+            .try_into()
+            .unwrap(),
+        };
+        let (_, header) = example_pre_voting_data_with(
+            &mut csprng,
+            example_election_parameters(),
+            election_manifest,
+        )
+        .unwrap();
+
+        let ballot_style_index = BallotStyleIndex::from_one_based_index(1).unwrap();
+        let mut ballots: Vec<_> = (0..2)
+            .map(|_| BallotPreEncrypted::new(&header, ballot_style_index, &mut csprng, false).0)
+            .collect();
+
+        // The fixed seed gives the first ballot a collision.
+        let collisions = find_short_code_collisions(&ballots);
+        assert!(!collisions.is_empty());
+        assert!(collisions.iter().any(|collision| collision.ballot == 0));
+        for collision in &collisions {
+            assert_eq!(collision.contest_index, contest_index);
+            assert!(1 < collision.selections.len());
+            let contest = ballots[collision.ballot]
+                .contests
+                .get(contest_index)
+                .unwrap();
+            for &j in &collision.selections {
+                let selection = contest.selections.get(j).unwrap();
+                assert_eq!(selection.shortcode, collision.shortcode);
+            }
+        }
+
+        // Distinct short codes don't collide.
+        for ballot in &mut ballots {
+            let contest = ballot.contests.get_mut(contest_index).unwrap();
+            let selection_indices: Vec<_> = contest.selections.indices().collect();
+            for j in selection_indices {
+                contest.selections.get_mut(j).unwrap().shortcode =
+                    format!("{:02x}", j.get_one_based_u32());
+            }
+        }
+        assert!(find_short_code_collisions(&ballots).is_empty());
+    }
+}
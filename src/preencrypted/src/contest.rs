@@ -209,13 +209,13 @@ impl ContestPreEncrypted {
         device: &Device,
         csprng: &mut Csprng,
         voter_selections: &[u8],
-        selection_limit: usize,
-        num_options: usize,
+        contest: &Contest,
     ) -> Result<ContestEncrypted, ProofRangeError> {
+        let num_options = contest.options.len();
         let selection = self.combine_voter_selections(
             &device.header.parameters.fixed_parameters,
             voter_selections,
-            selection_limit,
+            contest.selection_limit,
         );
 
         let mut proof_ballot_correctness = Vec1::new();
@@ -237,13 +237,22 @@ impl ContestPreEncrypted {
         let mut num_selections = 0;
         voter_selections.iter().for_each(|v| num_selections += v);
 
-        let proof_selection_limit = ContestEncrypted::proof_selection_limit(
-            &device.header,
-            csprng,
-            &selection,
-            num_selections as usize,
-            selection_limit,
-        )?;
+        let proof_selection_limit = if contest.exactly_one {
+            ContestEncrypted::proof_exactly_one(
+                &device.header,
+                csprng,
+                &selection,
+                num_selections as usize,
+            )?
+        } else {
+            ContestEncrypted::proof_selection_limit(
+                &device.header,
+                csprng,
+                &selection,
+                num_selections as usize,
+                contest.selection_limit,
+            )?
+        };
         let selection = selection.iter().map(|(ct, _)| ct.clone()).collect();
 
         // TODO: Change crypto hash
@@ -16,8 +16,15 @@ use crate::contest_selection::ContestSelectionPreEncrypted;
 
 /// Contest hash for pre-encrypted ballots (Equation 95)
 ///
-/// ψ_i = H(H_E;40,λ_i,K,α_1,β_1,α_2,β_2 ...,α_m,β_m),
+/// χ_l = H(H_E;41,Λ_l,K,ψ_(σ(1)),ψ_(σ(2)),...,ψ_(σ(m+L))),
 ///
+/// where the selection hashes `ψ` are sorted.
+///
+/// This differs from the contest hash of encrypted ballots, [`eg::contest_hash::contest_hash`]
+/// (Equation 58), by design, so the two never agree for the same contest: the domain separator
+/// is `0x41` rather than `0x23`, and the hash covers the selection hashes of all the
+/// pre-encrypted selections rather than the ciphertexts of one selection vector. Sorting the
+/// selection hashes keeps the contest hash from revealing which selection is for which option.
 pub fn contest_hash(
     header: &PreVotingData,
     contest_index: ContestIndex,
@@ -52,3 +59,50 @@ pub fn contest_hash(
 
     eg_h(&header.hashes_ext.h_e, &v)
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use eg::{
+        example_pre_voting_data::example_pre_voting_data, joint_election_public_key::Ciphertext,
+    };
+    use util::csprng::Csprng;
+
+    use crate::contest::ContestPreEncrypted;
+
+    #[test]
+    fn test_contest_hash_differs_from_eg() {
+        let mut csprng = Csprng::new(b"test_contest_hash_differs_from_eg");
+        let header = example_pre_voting_data(&mut csprng).unwrap();
+
+        // Contest 1 has two options and a selection limit of 1.
+        let contest_index = ContestIndex::from_one_based_index(1).unwrap();
+        let contest = header.manifest.contests.get(contest_index).unwrap();
+        let contest_pre_encrypted =
+            ContestPreEncrypted::new(&header, &[3u8; 32], false, contest, contest_index);
+        let selections = &contest_pre_encrypted.selections;
+        let chi = contest_hash(&header, contest_index, selections);
+        assert_eq!(contest_pre_encrypted.contest_hash, chi);
+
+        // The `eg` contest hash of the selection vector for the first option differs.
+        let first_selection: Vec<Ciphertext> = selections
+            .iter()
+            .next()
+            .unwrap()
+            .selections
+            .iter()
+            .map(|(ct, _)| ct.clone())
+            .collect();
+        let chi_eg = eg::contest_hash::contest_hash(&header, contest_index, &first_selection);
+        assert_ne!(chi, chi_eg);
+
+        // The pre-encrypted contest hash doesn't depend on the order of the selections.
+        let mut selections = contest_pre_encrypted.selections;
+        let mut reversed = Vec1::new();
+        while let Some(selection) = selections.pop() {
+            reversed.try_push(selection).unwrap();
+        }
+        assert_eq!(contest_hash(&header, contest_index, &reversed), chi);
+    }
+}
@@ -30,18 +30,48 @@ pub struct ContestSelectionPreEncrypted {
     /// The index of this pre-encrypted contest selection in the pre-encrypted contest.
     pub index: ContestSelectionPreEncryptedIndex,
 
-    /// Vector of ciphertexts used to represent the selection.
-    #[serde(skip)]
+    /// Vector of ciphertexts used to represent the selection, with their nonces if stored.
+    /// Only the ciphertexts are serialized, as the nonces would reveal the selection.
+    #[serde(rename = "ciphertexts", with = "ciphertexts_without_nonces")]
     pub selections: Vec<(Ciphertext, Option<Nonce>)>,
 
     /// Selection hash.
-    #[serde(skip)]
     pub selection_hash: HValue,
 
     /// Shortcode for this selection.
     pub shortcode: String,
 }
 
+/// Serializes the ciphertexts of a [`ContestSelectionPreEncrypted`] without their nonces.
+/// Deserialized ciphertexts have no nonce.
+mod ciphertexts_without_nonces {
+    use eg::joint_election_public_key::{Ciphertext, Nonce};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(
+        selections: &[(Ciphertext, Option<Nonce>)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(selections.iter().map(|(ciphertext, _)| ciphertext))
+    }
+
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<Vec<(Ciphertext, Option<Nonce>)>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let ciphertexts = Vec::<Ciphertext>::deserialize(deserializer)?;
+        Ok(ciphertexts
+            .into_iter()
+            .map(|ciphertext| (ciphertext, None))
+            .collect())
+    }
+}
+
 impl PartialEq for ContestSelectionPreEncrypted {
     fn eq(&self, other: &Self) -> bool {
         self.index == other.index && self.shortcode == other.shortcode
@@ -0,0 +1,119 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! Stable identifiers for the errors of pre-encrypted ballots, see [`eg::error_code`].
+
+use eg::error_code::{downcast_error_code, error_code_of_with, ErrorCode, ErrorCodeDowncast};
+
+use crate::{ballot::BallotPreEncryptedError, ballot_recording_tool::RecordingError};
+
+impl ErrorCode for BallotPreEncryptedError {
+    fn error_code(&self) -> &'static str {
+        use BallotPreEncryptedError::*;
+        match self {
+            ContestNotInManifest { .. } => "PREENCRYPTED_CONTEST_NOT_IN_MANIFEST",
+            SelectionCountMismatch { .. } => "PREENCRYPTED_SELECTION_COUNT_MISMATCH",
+            CiphertextCountMismatch { .. } => "PREENCRYPTED_CIPHERTEXT_COUNT_MISMATCH",
+            SelectionHashMismatch { .. } => "PREENCRYPTED_SELECTION_HASH_MISMATCH",
+        }
+    }
+}
+
+impl ErrorCode for RecordingError {
+    fn error_code(&self) -> &'static str {
+        use RecordingError::*;
+        match self {
+            ContestNotOnBallot { .. } => "RECORDING_CONTEST_NOT_ON_BALLOT",
+            UnknownCode { .. } => "RECORDING_UNKNOWN_CODE",
+            AmbiguousCode { .. } => "RECORDING_AMBIGUOUS_CODE",
+        }
+    }
+}
+
+/// Every error type of this crate which has an [`ErrorCode`].
+pub const ERROR_CODE_REGISTRY: &[ErrorCodeDowncast] = &[
+    downcast_error_code::<BallotPreEncryptedError>,
+    downcast_error_code::<RecordingError>,
+];
+
+/// Returns the code of the first error in the chain of an [`anyhow::Error`] which has an
+/// [`ErrorCode`], whether of this crate or of [`eg`].
+pub fn error_code_of(err: &anyhow::Error) -> Option<&'static str> {
+    error_code_of_with(err, ERROR_CODE_REGISTRY)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use eg::election_manifest::ContestIndex;
+
+    use super::*;
+    use crate::contest_selection::ContestSelectionPreEncryptedIndex;
+
+    #[test]
+    fn test_error_codes() {
+        let contest_ix = ContestIndex::from_one_based_index(1).unwrap();
+        let selection_ix = ContestSelectionPreEncryptedIndex::from_one_based_index(1).unwrap();
+        let errors: Vec<anyhow::Error> = vec![
+            anyhow::Error::new(BallotPreEncryptedError::ContestNotInManifest { contest_ix }),
+            anyhow::Error::new(BallotPreEncryptedError::SelectionCountMismatch {
+                contest_ix,
+                expected: 2,
+                actual: 1,
+            }),
+            anyhow::Error::new(BallotPreEncryptedError::CiphertextCountMismatch {
+                contest_ix,
+                selection_ix,
+                expected: 2,
+                actual: 1,
+            }),
+            anyhow::Error::new(BallotPreEncryptedError::SelectionHashMismatch {
+                contest_ix,
+                selection_ix,
+            }),
+            anyhow::Error::new(RecordingError::ContestNotOnBallot { contest_ix }),
+            anyhow::Error::new(RecordingError::UnknownCode {
+                contest_ix,
+                code: "ff".to_string(),
+            }),
+            anyhow::Error::new(RecordingError::AmbiguousCode {
+                contest_ix,
+                code: "c4".to_string(),
+                candidates: vec![selection_ix],
+            }),
+        ];
+        let codes: Vec<_> = errors
+            .iter()
+            .map(|err| error_code_of(err).unwrap())
+            .collect();
+
+        // The codes are stable.
+        assert_eq!(
+            codes,
+            [
+                "PREENCRYPTED_CONTEST_NOT_IN_MANIFEST",
+                "PREENCRYPTED_SELECTION_COUNT_MISMATCH",
+                "PREENCRYPTED_CIPHERTEXT_COUNT_MISMATCH",
+                "PREENCRYPTED_SELECTION_HASH_MISMATCH",
+                "RECORDING_CONTEST_NOT_ON_BALLOT",
+                "RECORDING_UNKNOWN_CODE",
+                "RECORDING_AMBIGUOUS_CODE",
+            ]
+        );
+
+        // Every type of the registry is covered above.
+        for downcast in ERROR_CODE_REGISTRY {
+            assert!(errors
+                .iter()
+                .any(|err| downcast(err.as_ref() as &(dyn std::error::Error + 'static)).is_some()));
+        }
+
+        // The codes of `eg` are found as well.
+        let err = anyhow::Error::new(eg::zk::ProofRangeError::NotExactlyOne { small_l: 0 });
+        assert_eq!(error_code_of(&err), Some("PROOF_NOT_EXACTLY_ONE"));
+    }
+}
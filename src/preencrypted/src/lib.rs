@@ -12,4 +12,5 @@ pub mod confirmation_code;
 pub mod contest;
 pub mod contest_hash;
 pub mod contest_selection;
+pub mod error_code;
 pub mod nonce;
@@ -5,6 +5,17 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
+//! Nonces of pre-encrypted ballots.
+//!
+//! Every nonce of a pre-encrypted ballot is derived from the ballot's primary nonce `ξ` by
+//! [`option_nonce`] (Equation 97). The nonce `ξ_(i,j,k)` encrypts the `k`-th component of the
+//! selection vector of the pre-encrypted selection `j` of contest `i`, where the null selections
+//! follow the options. The domain separator `0x43` keeps these nonces distinct from those
+//! derived from the same primary nonce for a standard encrypted ballot (Equation 22, `0x20`).
+//!
+//! Hence anyone knowing the primary nonce can re-derive all the encryptions of a pre-encrypted
+//! ballot, and so its selection hashes and short codes, to verify a recorded ballot.
+
 use eg::{
     election_manifest::{ContestIndex, ContestOptionIndex},
     election_record::PreVotingData,
@@ -35,3 +46,88 @@ pub fn option_nonce(
     let nonce = eg_h(&header.hashes_ext.h_e, &v);
     FieldElement::from_bytes_be(nonce.0.as_slice(), field)
 }
+
+/// Re-derives the nonces `ξ_(i,j,k)` for `k = 1..=num_selections` of the pre-encrypted
+/// selection `j` of contest `i`, where `num_selections` is the count of options plus the
+/// selection limit of the contest.
+#[cfg(any(test, feature = "preencrypted_test_api"))]
+pub fn selection_nonces(
+    header: &PreVotingData,
+    primary_nonce: &[u8],
+    index_i: ContestIndex,
+    index_j: ContestOptionIndex,
+    num_selections: usize,
+) -> Vec<FieldElement> {
+    ContestOptionIndex::iter_range_inclusive(ContestOptionIndex::MIN, ContestOptionIndex::MAX)
+        .take(num_selections)
+        .map(|index_k| option_nonce(header, primary_nonce, index_i, index_j, index_k))
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use eg::example_pre_voting_data::example_pre_voting_data;
+    use util::csprng::Csprng;
+
+    use crate::{
+        ballot_encrypting_tool::BallotEncryptingTool, contest::ContestPreEncrypted,
+        contest_selection::ContestSelectionPreEncrypted,
+    };
+
+    #[test]
+    fn test_selection_nonces() {
+        let mut csprng = Csprng::new(b"test_selection_nonces");
+        let header = example_pre_voting_data(&mut csprng).unwrap();
+        let fixed_parameters = &header.parameters.fixed_parameters;
+        let primary_nonce = [7u8; 32];
+
+        // Contest 1 has two options and a selection limit of 1.
+        let contest_index = ContestIndex::from_one_based_index(1).unwrap();
+        let contest = header.manifest.contests.get(contest_index).unwrap();
+        let contest_pre_encrypted =
+            ContestPreEncrypted::new(&header, &primary_nonce, true, contest, contest_index);
+        let num_selections = contest.options.len() + contest.selection_limit;
+        assert_eq!(contest_pre_encrypted.selections.len(), num_selections);
+
+        let check_selection = |j: u32, selection: &ContestSelectionPreEncrypted| {
+            let index_j = ContestOptionIndex::from_one_based_index(j).unwrap();
+            let nonces = selection_nonces(
+                &header,
+                &primary_nonce,
+                contest_index,
+                index_j,
+                num_selections,
+            );
+            assert_eq!(nonces.len(), num_selections);
+
+            // The stored nonces are the derived ones, and re-encrypting with them reproduces the
+            // selection vector, which has a 1 in position `j` only for an option.
+            let mut ciphertexts = Vec::new();
+            for (k, (nonce, (ciphertext, stored_nonce))) in
+                nonces.iter().zip(&selection.selections).enumerate()
+            {
+                assert_eq!(&stored_nonce.as_ref().unwrap().xi, nonce);
+                let vote = (k + 1 == j as usize && j as usize <= contest.options.len()) as usize;
+                let expected = header
+                    .public_key
+                    .encrypt_with(fixed_parameters, nonce, vote);
+                assert_eq!(ciphertext, &expected);
+                ciphertexts.push(expected);
+            }
+
+            // As well as the selection hash and short code.
+            let selection_hash = BallotEncryptingTool::selection_hash(&header, &ciphertexts);
+            assert_eq!(selection.selection_hash, selection_hash);
+            assert_eq!(
+                selection.shortcode,
+                BallotEncryptingTool::short_code_last_byte(&selection_hash)
+            );
+        };
+
+        for (j, selection) in (1..).zip(contest_pre_encrypted.selections.iter()) {
+            check_selection(j, selection);
+        }
+    }
+}
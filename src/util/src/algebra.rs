@@ -83,7 +83,10 @@ impl FieldElement {
         FieldElement(self.0.modpow(&x, &field.q))
     }
 
-    /// Creates a field element from a given integer.
+    /// Creates a field element from a given integer, reduced modulo the field order `q`.
+    ///
+    /// Prefer [`FieldElement::from_u64_checked`] where a value `>= q` would be a mistake, e.g.
+    /// a count which exceeds the tiny `q` of reduced-size parameters.
     pub fn from<T>(x: T, field: &ScalarField) -> Self
     where
         BigUint: From<T>,
@@ -92,6 +95,13 @@ impl FieldElement {
         FieldElement(x % &field.q)
     }
 
+    /// Creates a field element from a given integer, or returns `None` if it is not less than
+    /// the field order `q`.
+    pub fn from_u64_checked(x: u64, field: &ScalarField) -> Option<Self> {
+        let x = BigUint::from(x);
+        (x < field.q).then_some(FieldElement(x))
+    }
+
     /// Creates a field element from a bytes vector.
     ///
     /// Bytes interpreted as an big-endian encoded integer that is then reduced modulo order `q`.
@@ -115,6 +125,18 @@ impl FieldElement {
         to_be_bytes_left_pad(&self.0, field.q_len_bytes())
     }
 
+    /// Returns the uppercase hexadecimal representation of the field element, left-padded with
+    /// zeros to the byte length of the field order, as in the encoding of Section 5.1.2 in the
+    /// specs.
+    pub fn to_hex_string(&self, field: &ScalarField) -> String {
+        format!("{:0width$X}", self.0, width = 2 * field.q_len_bytes())
+    }
+
+    /// Returns the decimal representation of the field element.
+    pub fn to_dec_string(&self) -> String {
+        self.0.to_str_radix(10)
+    }
+
     /// Returns true if the element is zero.
     pub fn is_zero(&self) -> bool {
         BigUint::is_zero(&self.0)
@@ -169,6 +191,10 @@ impl ScalarField {
 
     /// Returns a random field element, i.e., a uniform random integer in `[0,q)` where `q` is the field order.
     ///
+    /// The sampling is unbiased: candidates of the bit length of `q` are drawn and rejected until
+    /// one is less than `q` (see [`Csprng::next_biguint_lt`]), rather than reducing a larger
+    /// random number modulo `q`. This matters as the elements are used as nonces.
+    ///
     /// The given `csprng` is assumed to be a secure randomness generator.
     pub fn random_field_elem(&self, csprng: &mut Csprng) -> FieldElement {
         FieldElement(csprng.next_biguint_lt(&self.q))
@@ -188,7 +214,14 @@ impl ScalarField {
 }
 
 /// An element of the multiplicative group `Z_p^r` as defined by [`Group`].
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+///
+/// The `Debug` representation abbreviates the value to its leading and trailing hex digits
+/// and its bit length. Use the alternate form `{:#?}` for the complete value.
+///
+/// It serializes as uppercase hex left-padded to a fixed 4096 bits, the size of `p` for the
+/// standard parameters. So for those parameters, the serialized bytes are those hashed by way of
+/// [`GroupElement::to_be_bytes_left_pad`]. Deserialization rejects any other width.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct GroupElement(
     #[serde(
         serialize_with = "crate::biguint_serde::biguint_serialize_4096_bits",
@@ -197,6 +230,26 @@ pub struct GroupElement(
     BigUint,
 );
 
+impl std::fmt::Debug for GroupElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        /// Number of hex digits shown at each end of an abbreviated value.
+        const CNT_HEX_DIGITS_SHOWN: usize = 8;
+
+        let hex = format!("{:X}", self.0);
+        if f.alternate() || hex.len() <= 2 * CNT_HEX_DIGITS_SHOWN + 1 {
+            write!(f, "GroupElement(0x{hex})")
+        } else {
+            write!(
+                f,
+                "GroupElement(0x{}…{}, {} bits)",
+                &hex[..CNT_HEX_DIGITS_SHOWN],
+                &hex[hex.len() - CNT_HEX_DIGITS_SHOWN..],
+                self.0.bits()
+            )
+        }
+    }
+}
+
 /// The group `Z_p^r`, a multiplicative subgroup of `Z_p`.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Group {
@@ -267,6 +320,18 @@ impl GroupElement {
         to_be_bytes_left_pad(&self.0, group.p_len_bytes())
     }
 
+    /// Returns the uppercase hexadecimal representation of the group element, left-padded with
+    /// zeros to the byte length of the group modulus, as in the encoding of Section 5.1.1 in the
+    /// specs.
+    pub fn to_hex_string(&self, group: &Group) -> String {
+        format!("{:0width$X}", self.0, width = 2 * group.p_len_bytes())
+    }
+
+    /// Returns the decimal representation of the group element.
+    pub fn to_dec_string(&self) -> String {
+        self.0.to_str_radix(10)
+    }
+
     /// Returns a reference to group element as BigUint
     pub fn as_biguint(&self) -> &BigUint {
         &self.0
@@ -401,11 +466,98 @@ impl Group {
     }
 }
 
+/// A table of powers of a fixed group element, for faster exponentiation with that base.
+///
+/// The exponent is split into windows of `w` bits, and the table holds `base^(d * 2^(w*i))` for
+/// each digit `0 <= d < 2^w` of each window `i` of an exponent less than `q`. Exponentiation then
+/// takes one multiplication per window, instead of a squaring per bit, at the cost of `2^w`
+/// group elements of memory per window. The result is the same as [`GroupElement::exp`].
+///
+/// Computing the table takes about as long as `2^w / w` exponentiations, so it pays off only if
+/// the base is used for many exponentiations, e.g., `g` and `K` when encrypting many ballots.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedBaseTable {
+    base: GroupElement,
+    window_bits: u8,
+    windows: Vec<Vec<GroupElement>>,
+}
+
+impl FixedBaseTable {
+    /// Window size in bits used by [`FixedBaseTable::new`].
+    ///
+    /// For the standard parameters, the table then takes about 512 KiB.
+    pub const DEFAULT_WINDOW_BITS: u8 = 4;
+
+    /// Computes the table for `base` with the default window size.
+    pub fn new(base: &GroupElement, group: &Group) -> FixedBaseTable {
+        Self::with_window_bits(base, Self::DEFAULT_WINDOW_BITS, group)
+    }
+
+    /// Computes the table for `base` with windows of `window_bits` bits, clamped to `1..=8`.
+    pub fn with_window_bits(base: &GroupElement, window_bits: u8, group: &Group) -> FixedBaseTable {
+        let window_bits = window_bits.clamp(1, 8);
+        let cnt_windows = cnt_bits_repr(&group.q).div_ceil(window_bits as usize);
+
+        let mut windows = Vec::with_capacity(cnt_windows);
+        // The base of the current window, `base^(2^(w*i))`.
+        let mut window_base = base.clone();
+        for _ in 0..cnt_windows {
+            let mut powers = Vec::with_capacity(1 << window_bits);
+            let mut power = Group::one();
+            for _ in 0..(1_usize << window_bits) {
+                let next_power = power.mul(&window_base, group);
+                powers.push(power);
+                power = next_power;
+            }
+            windows.push(powers);
+            window_base = power;
+        }
+
+        FixedBaseTable {
+            base: base.clone(),
+            window_bits,
+            windows,
+        }
+    }
+
+    /// Returns the base of the table.
+    pub fn base(&self) -> &GroupElement {
+        &self.base
+    }
+
+    /// Computes `base^exponent mod p`, the same as [`GroupElement::exp`] on the base.
+    ///
+    /// The group must be the one the table was computed for.
+    pub fn exp(&self, exponent: &FieldElement, group: &Group) -> GroupElement {
+        let window_bits = u64::from(self.window_bits);
+        let cnt_exponent_bits = exponent.0.bits();
+        if (self.windows.len() as u64) * window_bits < cnt_exponent_bits {
+            // The exponent is not less than `q` of the group, so the table does not cover it.
+            return self.base.exp(exponent, group);
+        }
+
+        let mut result = Group::one();
+        for (i, powers) in self.windows.iter().enumerate() {
+            let first_bit = i as u64 * window_bits;
+            if cnt_exponent_bits <= first_bit {
+                break;
+            }
+            let digit = (0..window_bits).fold(0_usize, |digit, j| {
+                digit | (usize::from(exponent.0.bit(first_bit + j)) << j)
+            });
+            if digit != 0 {
+                result = result.mul(&powers[digit], group);
+            }
+        }
+        result
+    }
+}
+
 // Unit tests for algebra.
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
-    use crate::algebra::{FieldElement, Group, GroupElement, ScalarField};
+    use crate::algebra::{FieldElement, FixedBaseTable, Group, GroupElement, ScalarField};
     use crate::csprng::Csprng;
     use num_bigint::BigUint;
 
@@ -420,6 +572,41 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_to_hex_and_dec_string() {
+        let (field, group) = get_toy_algebras();
+
+        // q = 127 takes 1 byte, p = 59183 takes 2 bytes.
+        let fe = FieldElement::from(10_u8, &field);
+        assert_eq!(fe.to_hex_string(&field), "0A");
+        assert_eq!(fe.to_dec_string(), "10");
+
+        let ge = GroupElement(BigUint::from(10_u8));
+        assert_eq!(ge.to_hex_string(&group), "000A");
+        assert_eq!(ge.to_dec_string(), "10");
+
+        let ge = GroupElement(BigUint::from(59182_u32));
+        assert_eq!(ge.to_hex_string(&group), "E72E");
+        assert_eq!(ge.to_dec_string(), "59182");
+    }
+
+    #[test]
+    fn test_group_element_debug() {
+        let small = GroupElement(BigUint::from(0xABCDu32));
+        assert_eq!(format!("{small:?}"), "GroupElement(0xABCD)");
+        assert_eq!(format!("{small:#?}"), "GroupElement(0xABCD)");
+
+        let large = GroupElement(BigUint::from(3u8).pow(2000));
+        let hex = format!("{:X}", large.0);
+
+        let debug = format!("{large:?}");
+        assert!(debug.len() < 60, "{debug}");
+        assert!(debug.starts_with(&format!("GroupElement(0x{}…", &hex[..8])));
+        assert!(debug.ends_with(&format!("…{}, 3170 bits)", &hex[hex.len() - 8..])));
+
+        assert_eq!(format!("{large:#?}"), format!("GroupElement(0x{hex})"));
+    }
+
     #[test]
     fn test_field_operations() {
         // Toy parameters according to specs
@@ -496,6 +683,33 @@ mod test {
         assert!(!h.is_valid(&group));
     }
 
+    #[test]
+    fn test_fixed_base_table() {
+        let mut csprng = Csprng::new(b"testing fixed-base tables");
+        let (field, group) = get_toy_algebras();
+
+        let bases = [
+            group.generator(),
+            Group::one(),
+            group.random_group_elem(&mut csprng),
+        ];
+        for base in &bases {
+            for window_bits in [0, 1, 3, 4, 8, 9] {
+                let table = FixedBaseTable::with_window_bits(base, window_bits, &group);
+                assert_eq!(table.base(), base);
+                for x in 0..127_u8 {
+                    let x = FieldElement::from(x, &field);
+                    assert_eq!(table.exp(&x, &group), base.exp(&x, &group));
+                }
+            }
+        }
+
+        // An exponent not less than `q` is not covered by the table, but gives the same result.
+        let table = FixedBaseTable::new(&group.generator(), &group);
+        let x = FieldElement(BigUint::from(1000_u32));
+        assert_eq!(table.exp(&x, &group), group.g_exp(&x));
+    }
+
     #[test]
     fn test_field_group_validity() {
         let mut csprng = Csprng::new(b"testing field/group validity");
@@ -558,6 +772,56 @@ mod test {
         assert_eq!(invalid_group, None);
     }
 
+    #[test]
+    fn test_random_field_elem_uniform() {
+        let (field, _) = get_toy_algebras();
+        let mut csprng = Csprng::new(b"test_random_field_elem_uniform");
+
+        // Count the samples of each of the q = 127 field elements.
+        let q = 127;
+        let cnt_expected_per_bucket = 200;
+        let mut buckets = vec![0_usize; q];
+        for _ in 0..q * cnt_expected_per_bucket {
+            let fe = field.random_field_elem(&mut csprng);
+            assert!(fe.is_valid(&field));
+            let ix: usize = fe.0.try_into().unwrap();
+            buckets[ix] += 1;
+        }
+
+        // Pearson's chi-square statistic. With q - 1 = 126 degrees of freedom, it exceeds 180.4
+        // with probability 0.001 for uniform samples.
+        let chi_square: f64 = buckets
+            .iter()
+            .map(|&cnt| {
+                let d = cnt as f64 - cnt_expected_per_bucket as f64;
+                d * d / cnt_expected_per_bucket as f64
+            })
+            .sum();
+        assert!(chi_square < 180.4, "chi_square = {chi_square}");
+        assert!(buckets.iter().all(|&cnt| 0 < cnt));
+    }
+
+    #[test]
+    fn test_from_u64_checked() {
+        let (field, _) = get_toy_algebras();
+
+        // q = 127
+        let fe = FieldElement::from_u64_checked(126, &field).unwrap();
+        assert_eq!(fe, FieldElement::from(126_u8, &field));
+        assert_eq!(FieldElement::from(126_u64, &field), fe);
+
+        assert_eq!(FieldElement::from_u64_checked(127, &field), None);
+        assert_eq!(FieldElement::from_u64_checked(300, &field), None);
+        assert_eq!(FieldElement::from_u64_checked(u64::MAX, &field), None);
+
+        assert!(FieldElement::from(127_u64, &field).is_zero());
+        assert_eq!(
+            FieldElement::from(300_u64, &field),
+            FieldElement::from(46_u8, &field)
+        );
+        assert!(FieldElement::from(u64::MAX, &field).is_valid(&field));
+    }
+
     #[test]
     fn test_field_conversions() {
         let (field, _) = get_toy_algebras();
@@ -583,4 +847,27 @@ mod test {
         // Testing length of encoding
         assert_eq!(u.to_32_be_bytes().len(), 32)
     }
+
+    #[test]
+    fn test_group_element_serde_fixed_width() {
+        let (_, group) = get_toy_algebras();
+        let g = group.generator();
+
+        // 4096 bits is 512 bytes, or 1024 hex digits.
+        let json = serde_json::to_string(&g).unwrap();
+        let hex = json.trim_matches('"');
+        assert_eq!(hex, format!("{:0>1024}", "7F68"));
+
+        // It ends with the bytes hashed, which are left-padded to the size of `p`.
+        assert_eq!(g.to_be_bytes_left_pad(&group), [0x7F, 0x68]);
+
+        let g2: GroupElement = serde_json::from_str(&json).unwrap();
+        assert_eq!(g2, g);
+
+        // Other widths are rejected.
+        let small = GroupElement(BigUint::from(0x1F_u8));
+        assert!(serde_json::to_string(&small).unwrap().ends_with("001F\""));
+        assert!(serde_json::from_str::<GroupElement>("\"1F\"").is_err());
+        assert!(serde_json::from_str::<GroupElement>(&format!("\"{:0>1026}\"", "1F")).is_err());
+    }
 }
@@ -239,6 +239,47 @@ fn get_lagrange_coefficients_at_zero_unchecked(
     coeffs
 }
 
+/// Represents errors occurring if the nodes and values are unsuitable for Lagrange interpolation
+/// at zero.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum LagrangeError {
+    /// Occurs if there is not exactly one value per node.
+    #[error("There are {cnt_xs} nodes, but {cnt_ys} values.")]
+    LengthMismatch { cnt_xs: usize, cnt_ys: usize },
+
+    /// Occurs if a node is zero, the point at which the polynomial is interpolated.
+    #[error("Node {ix} is zero.")]
+    ZeroNode { ix: usize },
+
+    /// Occurs if a node is the same as an earlier one.
+    #[error("Node {ix} duplicates node {first_ix}.")]
+    DuplicateNode { ix: usize, first_ix: usize },
+}
+
+/// Checks that the nodes `xs` are distinct and non-zero, and that there are `cnt_ys` values,
+/// one per node. The indices in the errors are 0-based indices into `xs`.
+fn validate_lagrange_nodes(xs: &[FieldElement], cnt_ys: usize) -> Result<(), LagrangeError> {
+    if xs.len() != cnt_ys {
+        return Err(LagrangeError::LengthMismatch {
+            cnt_xs: xs.len(),
+            cnt_ys,
+        });
+    }
+
+    let mut first_ixs: HashMap<&FieldElement, usize> = HashMap::with_capacity(xs.len());
+    for (ix, x) in xs.iter().enumerate() {
+        if x.is_zero() {
+            return Err(LagrangeError::ZeroNode { ix });
+        }
+        if let Some(&first_ix) = first_ixs.get(x) {
+            return Err(LagrangeError::DuplicateNode { ix, first_ix });
+        }
+        first_ixs.insert(x, ix);
+    }
+
+    Ok(())
+}
+
 /// Computes the Lagrange interpolation in the field Z_q.
 ///
 /// The arguments are
@@ -246,20 +287,19 @@ fn get_lagrange_coefficients_at_zero_unchecked(
 /// - `ys` - the list of values, field elements in Z_q
 /// - `field` - the field Z_q
 ///
-/// The function returns `None` if the nodes in `xs` are not unique or if `xs` and `ys` are not of the same length.
+/// The function returns an error if the nodes in `xs` are not distinct and non-zero, or if `xs`
+/// and `ys` are not of the same length.
 pub fn field_lagrange_at_zero(
     xs: &[FieldElement],
     ys: &[FieldElement],
     field: &ScalarField,
-) -> Option<FieldElement> {
-    if xs.len() != ys.len() || !xs.iter().all_unique() {
-        return None;
-    }
+) -> Result<FieldElement, LagrangeError> {
+    validate_lagrange_nodes(xs, ys.len())?;
     let coeffs = get_lagrange_coefficients_at_zero_unchecked(xs, field);
     let y0 = zip(coeffs, ys)
         .map(|(c, y)| c.mul(y, field))
         .fold(ScalarField::zero(), |acc, s| acc.add(&s, field));
-    Some(y0)
+    Ok(y0)
 }
 
 /// Computes the Lagrange interpolation in the exponent of group element.
@@ -270,21 +310,66 @@ pub fn field_lagrange_at_zero(
 /// - `field` - the field Z_q
 /// - `group` - the group Z_p^r
 ///
-/// The function returns `None` if the nodes in `xs` are not unique or if `xs` and `ys` are not of the same length.
+/// The function returns an error if the nodes in `xs` are not distinct and non-zero, or if `xs`
+/// and `ys` are not of the same length.
 pub fn group_lagrange_at_zero(
     xs: &[FieldElement],
     ys: &[GroupElement],
     field: &ScalarField,
     group: &Group,
-) -> Option<GroupElement> {
-    if xs.len() != ys.len() || !xs.iter().all_unique() {
-        return None;
-    }
+) -> Result<GroupElement, LagrangeError> {
+    validate_lagrange_nodes(xs, ys.len())?;
     let coeffs = get_lagrange_coefficients_at_zero_unchecked(xs, field);
     let y0 = zip(coeffs, ys)
         .map(|(c, y)| y.exp(&c, group))
         .fold(Group::one(), |acc, s| acc.mul(&s, group));
-    Some(y0)
+    Ok(y0)
+}
+
+/// The Lagrange coefficients `w_i = \prod_{l != i} l/(l-i) % q` at zero, as in Equation `67` of
+/// EG `2.0.0`, precomputed for a fixed list of nodes.
+///
+/// Use this instead of repeated calls to [`get_single_coefficient_at_zero`] when coefficients are
+/// needed for several nodes of the same list. Each coefficient is then looked up in O(1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LagrangeBasisAtZero {
+    coefficients: HashMap<FieldElement, FieldElement>,
+}
+
+impl LagrangeBasisAtZero {
+    /// Computes the Lagrange coefficients at zero for the nodes `xs`, field elements in Z_q.
+    ///
+    /// Returns an error if the nodes in `xs` are not distinct and non-zero.
+    pub fn new(xs: &[FieldElement], field: &ScalarField) -> Result<Self, LagrangeError> {
+        validate_lagrange_nodes(xs, xs.len())?;
+
+        // w_i = (\prod_l l) / (i * \prod_{l != i} (l-i)), so that only one inversion is needed per node.
+        let prod_xs = xs
+            .iter()
+            .fold(ScalarField::one(), |acc, l| acc.mul(l, field));
+
+        let mut coefficients = HashMap::with_capacity(xs.len());
+        for (ix, i) in xs.iter().enumerate() {
+            let denominator = xs
+                .iter()
+                .filter(|&l| l != i)
+                .fold(i.clone(), |acc, l| acc.mul(&l.sub(i, field), field));
+
+            // The nodes are distinct and non-zero, so the denominator is non-zero.
+            let Some(inv_denominator) = denominator.inv(field) else {
+                return Err(LagrangeError::ZeroNode { ix });
+            };
+
+            coefficients.insert(i.clone(), prod_xs.mul(&inv_denominator, field));
+        }
+
+        Ok(LagrangeBasisAtZero { coefficients })
+    }
+
+    /// Returns the coefficient `w_i` of node `i`, or `None` if `i` is not one of the nodes.
+    pub fn coefficient(&self, i: &FieldElement) -> Option<&FieldElement> {
+        self.coefficients.get(i)
+    }
 }
 
 #[cfg(test)]
@@ -391,22 +476,38 @@ mod tests {
         let x_0 = FieldElement::from(126_u8, &field);
         let g_x_0 = group.g_exp(&x_0);
 
-        assert_eq!(field_lagrange_at_zero(&xs, &ys, &field), Some(x_0));
+        assert_eq!(field_lagrange_at_zero(&xs, &ys, &field), Ok(x_0));
         assert_eq!(
             group_lagrange_at_zero(&xs, &group_ys, &field, &group),
-            Some(g_x_0)
+            Ok(g_x_0)
         );
 
         // List of different length
-        assert_eq!(field_lagrange_at_zero(&xs[0..2], &ys, &field), None);
-        assert_eq!(field_lagrange_at_zero(&xs, &ys[0..2], &field), None);
+        let length_mismatch = Err(LagrangeError::LengthMismatch {
+            cnt_xs: 2,
+            cnt_ys: 3,
+        });
+        assert_eq!(
+            field_lagrange_at_zero(&xs[0..2], &ys, &field),
+            length_mismatch
+        );
         assert_eq!(
-            group_lagrange_at_zero(&xs[0..2], &group_ys, &field, &group),
-            None
+            field_lagrange_at_zero(&xs, &ys[0..2], &field),
+            Err(LagrangeError::LengthMismatch {
+                cnt_xs: 3,
+                cnt_ys: 2
+            })
+        );
+        assert_eq!(
+            group_lagrange_at_zero(&xs[0..2], &group_ys, &field, &group).map(|_| ()),
+            length_mismatch.map(|_| ())
         );
         assert_eq!(
             group_lagrange_at_zero(&xs, &group_ys[0..2], &field, &group),
-            None
+            Err(LagrangeError::LengthMismatch {
+                cnt_xs: 3,
+                cnt_ys: 2
+            })
         );
         // Repeated nodes
         let xs = [
@@ -414,8 +515,29 @@ mod tests {
             FieldElement::from(2_u8, &field),
             FieldElement::from(2_u8, &field),
         ];
-        assert_eq!(field_lagrange_at_zero(&xs, &ys, &field), None);
-        assert_eq!(group_lagrange_at_zero(&xs, &group_ys, &field, &group), None);
+        let duplicate_node = LagrangeError::DuplicateNode { ix: 2, first_ix: 1 };
+        assert_eq!(
+            field_lagrange_at_zero(&xs, &ys, &field),
+            Err(duplicate_node.clone())
+        );
+        assert_eq!(
+            group_lagrange_at_zero(&xs, &group_ys, &field, &group),
+            Err(duplicate_node)
+        );
+        // Zero node
+        let xs = [
+            FieldElement::from(1_u8, &field),
+            FieldElement::from(0_u8, &field),
+            FieldElement::from(3_u8, &field),
+        ];
+        assert_eq!(
+            field_lagrange_at_zero(&xs, &ys, &field),
+            Err(LagrangeError::ZeroNode { ix: 1 })
+        );
+        assert_eq!(
+            group_lagrange_at_zero(&xs, &group_ys, &field, &group),
+            Err(LagrangeError::ZeroNode { ix: 1 })
+        );
     }
 
     #[test]
@@ -455,4 +577,40 @@ mod tests {
             "The function should reject xs with non-unique elements"
         );
     }
+
+    #[test]
+    fn test_lagrange_basis_at_zero() {
+        let (field, _) = get_toy_algebras();
+        let mut csprng = Csprng::new(b"test_lagrange_basis_at_zero");
+
+        let mut xs: Vec<FieldElement> = vec![];
+        while xs.len() < 5 {
+            let x = field.random_field_elem(&mut csprng);
+            if !x.is_zero() && !xs.contains(&x) {
+                xs.push(x);
+            }
+        }
+
+        let basis = LagrangeBasisAtZero::new(&xs, &field).unwrap();
+        for x in &xs {
+            assert_eq!(
+                basis.coefficient(x),
+                get_single_coefficient_at_zero(&xs, x, &field).as_ref()
+            );
+        }
+
+        let x = xs
+            .iter()
+            .fold(ScalarField::one(), |acc, x| acc.add(x, &field));
+        if !xs.contains(&x) {
+            assert_eq!(basis.coefficient(&x), None);
+        }
+
+        let mut xs_duplicate = xs.clone();
+        xs_duplicate.push(xs[0].clone());
+        assert_eq!(
+            LagrangeBasisAtZero::new(&xs_duplicate, &field),
+            Err(LagrangeError::DuplicateNode { ix: 5, first_ix: 0 })
+        );
+    }
 }
@@ -5,7 +5,11 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use crate::logging::Logging;
 
@@ -56,6 +60,35 @@ pub fn create_path(path: &PathBuf) {
     }
 }
 
+/// Writes bytes to path atomically: either the complete new contents are written, or the file
+/// is left as it was, even if the process dies mid-write.
+///
+/// The bytes are written to a temporary file in the same directory, which is then renamed
+/// into place. On error, the temporary file is removed.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let temp_path = temp_path_for(path);
+
+    let result = fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(bytes)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp_path, path));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// The path of the temporary file used by [`write_atomic`] to write `path`.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let mut file_name = std::ffi::OsString::from(".");
+    file_name.push(path.file_name().unwrap_or_default());
+    file_name.push(format!(".{}.tmp", std::process::id()));
+    path.with_file_name(file_name)
+}
+
 // pub fn export(dir: &PathBuf, public_key: &PublicKey, proof: &ProofGuardian) {
 //     let private_dir = dir.join("private");
 //     let public_dir = dir.join("public");
@@ -78,3 +111,37 @@ pub fn create_path(path: &PathBuf) {
 //     // )
 //     // .unwrap();
 // }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic() {
+        let dir_path =
+            std::env::temp_dir().join(format!("util_test_write_atomic_{}", std::process::id()));
+        fs::create_dir_all(&dir_path).unwrap();
+        let path = dir_path.join("artifact.json");
+
+        write_atomic(&path, b"old").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"old");
+
+        write_atomic(&path, b"complete new contents").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"complete new contents");
+        assert!(!temp_path_for(&path).exists());
+
+        // A write that fails before being renamed into place, here because the target is a
+        // non-empty directory, leaves the target as it was and no temporary file behind.
+        let dir_target = dir_path.join("subdir");
+        fs::create_dir_all(&dir_target).unwrap();
+        fs::write(dir_target.join("file"), b"old").unwrap();
+        assert!(write_atomic(&dir_target, b"new").is_err());
+        assert_eq!(fs::read(dir_target.join("file")).unwrap(), b"old");
+        assert!(!temp_path_for(&dir_target).exists());
+
+        let cnt_entries = fs::read_dir(&dir_path).unwrap().count();
+        fs::remove_dir_all(&dir_path).unwrap();
+        assert_eq!(cnt_entries, 2);
+    }
+}
@@ -16,3 +16,4 @@ pub mod file;
 pub mod hex_dump;
 pub mod logging;
 pub mod prime;
+pub mod serde;
@@ -0,0 +1,182 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! Deserialization of maps keyed by 1-based indices, e.g., `BTreeMap<ContestIndex, _>`.
+//!
+//! JSON object keys are strings, so such a map is written as, e.g., `{"1": ..., "3": ...}`.
+//! Use [`deserialize_one_based_index_map`] with `#[serde(deserialize_with = "...")]` to
+//! validate every key with an error naming the offending key.
+
+use std::{collections::BTreeMap, fmt::Display, marker::PhantomData};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use thiserror::Error;
+
+/// Represents errors occurring if a key of a map is not a valid 1-based index.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IndexMapKeyError {
+    /// Occurs if the key is not a decimal number without sign or leading zeros.
+    #[error("The map key {key:?} is not a 1-based index, as it is not a decimal number without sign or leading zeros.")]
+    NotANumber { key: String },
+
+    /// Occurs if the key is `0`.
+    #[error("The map key \"0\" is not a 1-based index.")]
+    Zero,
+
+    /// Occurs if the key is a number too large to be an index.
+    #[error("The map key {key:?} is out of range for an index: {reason}")]
+    OutOfRange { key: String, reason: String },
+
+    /// Occurs if the map has the same key more than once.
+    #[error("The map key {key:?} appears more than once.")]
+    Duplicate { key: String },
+}
+
+/// Parses a map key as a 1-based index of type `K`.
+pub fn parse_one_based_index_key<K>(key: &str) -> Result<K, IndexMapKeyError>
+where
+    K: TryFrom<u32>,
+    K::Error: Display,
+{
+    let is_canonical_decimal = !key.is_empty()
+        && key.bytes().all(|b| b.is_ascii_digit())
+        && (key == "0" || !key.starts_with('0'));
+    if !is_canonical_decimal {
+        return Err(IndexMapKeyError::NotANumber {
+            key: key.to_string(),
+        });
+    }
+
+    if key == "0" {
+        return Err(IndexMapKeyError::Zero);
+    }
+
+    let out_of_range = |reason: String| IndexMapKeyError::OutOfRange {
+        key: key.to_string(),
+        reason,
+    };
+    let ix1: u32 = key.parse().map_err(|e| out_of_range(format!("{e}")))?;
+    K::try_from(ix1).map_err(|e| out_of_range(e.to_string()))
+}
+
+/// Deserializes a map whose keys are 1-based indices, such as `BTreeMap<ContestIndex, _>`,
+/// rejecting a key which is `0`, not a canonical decimal number, out of range for `K`, or
+/// repeated.
+pub fn deserialize_one_based_index_map<'de, D, K, V>(
+    deserializer: D,
+) -> Result<BTreeMap<K, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Ord + TryFrom<u32>,
+    K::Error: Display,
+    V: Deserialize<'de>,
+{
+    struct IndexMapVisitor<K, V>(PhantomData<fn() -> BTreeMap<K, V>>);
+
+    impl<'de, K, V> Visitor<'de> for IndexMapVisitor<K, V>
+    where
+        K: Ord + TryFrom<u32>,
+        K::Error: Display,
+        V: Deserialize<'de>,
+    {
+        type Value = BTreeMap<K, V>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a map keyed by 1-based indices")
+        }
+
+        fn visit_map<A>(self, mut map_access: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            use serde::de::Error;
+
+            let mut map = BTreeMap::new();
+            while let Some((key, value)) = map_access.next_entry::<String, V>()? {
+                let ix = parse_one_based_index_key::<K>(&key).map_err(A::Error::custom)?;
+                if map.insert(ix, value).is_some() {
+                    return Err(A::Error::custom(IndexMapKeyError::Duplicate { key }));
+                }
+            }
+            Ok(map)
+        }
+    }
+
+    deserializer.deserialize_map(IndexMapVisitor(PhantomData))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    /// A 1-based index of at most 255.
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct OptionIx(u8);
+
+    impl TryFrom<u32> for OptionIx {
+        type Error = String;
+
+        fn try_from(ix1: u32) -> Result<Self, Self::Error> {
+            u8::try_from(ix1)
+                .map(OptionIx)
+                .map_err(|_| format!("{ix1} exceeds 255"))
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Selections {
+        #[serde(deserialize_with = "deserialize_one_based_index_map")]
+        votes: BTreeMap<OptionIx, u8>,
+    }
+
+    fn deserialize(json: &str) -> Result<BTreeMap<OptionIx, u8>, String> {
+        serde_json::from_str::<Selections>(json)
+            .map(|selections| selections.votes)
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_deserialize_one_based_index_map() {
+        assert_eq!(
+            deserialize(r#"{"votes": {"3": 0, "1": 1}}"#).unwrap(),
+            BTreeMap::from([(OptionIx(1), 1), (OptionIx(3), 0)])
+        );
+        assert_eq!(deserialize(r#"{"votes": {}}"#).unwrap(), BTreeMap::new());
+
+        let err = deserialize(r#"{"votes": {"1": 1, "0": 0}}"#).unwrap_err();
+        assert!(
+            err.starts_with(&IndexMapKeyError::Zero.to_string()),
+            "{err}"
+        );
+
+        for key in ["one", "", "-1", "+1", "01", "1.0"] {
+            let err = deserialize(&format!(r#"{{"votes": {{"{key}": 1}}}}"#)).unwrap_err();
+            let expected = IndexMapKeyError::NotANumber {
+                key: key.to_string(),
+            };
+            assert!(err.starts_with(&expected.to_string()), "{err}");
+        }
+
+        // Out of range for the key type.
+        let err = deserialize(r#"{"votes": {"256": 1}}"#).unwrap_err();
+        let expected = IndexMapKeyError::OutOfRange {
+            key: "256".to_string(),
+            reason: "256 exceeds 255".to_string(),
+        };
+        assert!(err.starts_with(&expected.to_string()), "{err}");
+
+        let err = deserialize(r#"{"votes": {"2": 1, "2": 0}}"#).unwrap_err();
+        let expected = IndexMapKeyError::Duplicate {
+            key: "2".to_string(),
+        };
+        assert!(err.starts_with(&expected.to_string()), "{err}");
+    }
+}